@@ -2,11 +2,42 @@
 
 // State
 let branches = [];
+let branchGroups = {};
 let currentBranch = null;
+let installOrigin = null;
+// Whether the install's files match what was installed; null when unknown
+let installPristine = null;
+// Other Blender versions using this same install folder through a link
+let installSharedWith = [];
+// Remote the branch list comes from; null is the official repo
+let currentRemote = null;
+// Addon every command works on; null is Serpens
+let currentAddon = null;
 let settings = {
     blenderVersion: '5.0',
     customPath: '',
-    autoBackup: true
+    autoBackup: true,
+    minimizeToTray: false,
+    notifications: true,
+    launchOnStartup: false,
+    newBranchNotifications: true,
+    fixPermissions: false,
+    checkOnLaunch: true,
+    appUpdateCheck: true,
+    includePrereleases: false,
+    selfUpdate: true,
+    verifyAfterUpdate: false,
+    clearPycache: true,
+    githubToken: '',
+    gitPath: '',
+    minimalCloneFallback: true,
+    mirrorCache: true,
+    recreateSymlinks: false,
+    cloudPlaceholders: 'download',
+    preferredProtocol: 'https',
+    cloneDepth: 1,
+    addons: [],
+    profiles: []
 };
 let invoke = null;
 
@@ -43,17 +74,22 @@ function initElements() {
     };
 }
 
+// Every command is told which addon it's for; the ones that don't take one ignore it
+function addonInvoke(raw) {
+    return (cmd, args = {}) => cmd.startsWith('plugin:') ? raw(cmd, args) : raw(cmd, { addonId: currentAddon, ...args });
+}
+
 // Initialize Tauri
 async function initTauri() {
     // Tauri 2.0 exposes invoke via __TAURI_INTERNALS__
     if (window.__TAURI_INTERNALS__) {
-        invoke = window.__TAURI_INTERNALS__.invoke;
+        invoke = addonInvoke(window.__TAURI_INTERNALS__.invoke);
         return true;
     }
 
     // Fallback: try __TAURI__.core (Tauri with npm package)
     if (window.__TAURI__?.core?.invoke) {
-        invoke = window.__TAURI__.core.invoke;
+        invoke = addonInvoke(window.__TAURI__.core.invoke);
         return true;
     }
 
@@ -61,12 +97,12 @@ async function initTauri() {
     await new Promise(resolve => setTimeout(resolve, 200));
 
     if (window.__TAURI_INTERNALS__) {
-        invoke = window.__TAURI_INTERNALS__.invoke;
+        invoke = addonInvoke(window.__TAURI_INTERNALS__.invoke);
         return true;
     }
 
     if (window.__TAURI__?.core?.invoke) {
-        invoke = window.__TAURI__.core.invoke;
+        invoke = addonInvoke(window.__TAURI__.core.invoke);
         return true;
     }
 
@@ -74,6 +110,13 @@ async function initTauri() {
     return false;
 }
 
+// Subscribe to a backend event (serpens://...) without the npm package
+async function listen(event, handler) {
+    if (!window.__TAURI_INTERNALS__) return;
+    const callback = window.__TAURI_INTERNALS__.transformCallback((e) => handler(e.payload));
+    await invoke('plugin:event|listen', { event, target: { kind: 'Any' }, handler: callback });
+}
+
 // Initialize Application
 async function init() {
     initElements();
@@ -89,6 +132,25 @@ async function init() {
     }
 
     logToConsole('Tauri backend connected', 'success');
+    invoke('app_info')
+        .then(info => logToConsole(`Serpens Dev Manager ${info.version} (${info.gitCommit?.slice(0, 12) ?? 'unknown commit'}, ${info.target}, ${info.profile})`, 'info'))
+        .catch(() => {});
+    // A broken git is behind every other failure, so say so up front
+    invoke('git_info')
+        .then(git => {
+            if (git.error) logToConsole(`git (${git.path}) doesn't run: ${git.error}`, 'error');
+            else if (git.source !== 'path') logToConsole(`Using ${git.version} from ${git.path}`, 'info');
+        })
+        .catch(() => {});
+
+    try {
+        const dir = await invoke('check_settings_dir');
+        if (dir.repaired) logToConsole(`Made the settings folder writable again (${dir.path})`, 'info');
+        if (dir.error) logToConsole(dir.error, 'error');
+        document.getElementById('dataDirPath').value = dir.path;
+    } catch (e) {
+        logToConsole(`Settings folder check failed: ${e}`, 'warning');
+    }
 
     try {
         await loadSettings();
@@ -105,27 +167,94 @@ async function init() {
     }
 
     try {
-        await fetchBranches();
+        await setupAddons();
+        await setupRemotes();
+        // With launch checks off, the branch list waits for Refresh like everything else
+        if (settings.checkOnLaunch) {
+            await fetchBranches();
+        } else {
+            elements.branchesList.innerHTML = '<div class="loading-state"><span>Click Refresh to load branches</span></div>';
+        }
     } catch (e) {
         logToConsole(`Branches error: ${e}`, 'error');
         elements.branchesList.innerHTML = `<div class="loading-state"><span>Error: ${e}</span></div>`;
     }
 
     setupEventListeners();
+    resumeActiveOperation();
+    await setupDeepLinks();
+    await setupZipDrop();
+    await setupBranchWatch();
+    await setupUpdateStatus();
+    await setupInstallWatch();
+    await setupNotices();
     logToConsole('Ready!', 'success');
+    if (settings.appUpdateCheck && settings.checkOnLaunch) checkAppUpdate();
+}
+
+// Manager self-update check; the backend repeats it daily and sends serpens://app-update
+let appUpdate = null;
+
+async function checkAppUpdate() {
+    try {
+        await listen('serpens://app-update', (update) => { appUpdate = update; });
+        const update = await invoke('check_app_update');
+        if (update.updateAvailable) {
+            appUpdate = update;
+            logToConsole(`Serpens Dev Manager ${update.latestVersion} is available (you have ${update.currentVersion}): ${update.downloadUrl}`, 'warning');
+        }
+    } catch (e) {
+        logToConsole(`Could not check for manager updates: ${e}`, 'info');
+    }
 }
 
 // Load Settings
 async function loadSettings() {
+    try {
+        // Only takes effect on first run, before any settings have been saved
+        const detected = await invoke('first_run_detect');
+        if (detected) logToConsole(`Detected Blender ${detected}`, 'info');
+    } catch (e) {
+        logToConsole(`Blender version detection failed: ${e}`, 'warning');
+    }
     try {
         const saved = await invoke('load_settings');
         if (saved) settings = { ...settings, ...saved };
         document.getElementById('blenderVersionSelect').value = settings.blenderVersion;
         document.getElementById('customPath').value = settings.customPath || '';
+        showUserDirs();
+        document.getElementById('githubToken').value = settings.githubToken || '';
+        document.getElementById('gitPath').value = settings.gitPath || '';
         document.getElementById('autoBackup').checked = settings.autoBackup;
+        document.getElementById('minimizeToTray').checked = settings.minimizeToTray;
+        document.getElementById('notifications').checked = settings.notifications;
+        document.getElementById('launchOnStartup').checked = settings.launchOnStartup;
+        document.getElementById('newBranchNotifications').checked = settings.newBranchNotifications;
+        document.getElementById('fixPermissions').checked = settings.fixPermissions;
+        document.getElementById('checkOnLaunch').checked = settings.checkOnLaunch;
+        document.getElementById('appUpdateCheck').checked = settings.appUpdateCheck;
+        document.getElementById('includePrereleases').checked = settings.includePrereleases;
+        document.getElementById('selfUpdate').checked = settings.selfUpdate;
+        document.getElementById('verifyAfterUpdate').checked = settings.verifyAfterUpdate;
+        document.getElementById('clearPycache').checked = settings.clearPycache;
+        document.getElementById('minimalCloneFallback').checked = settings.minimalCloneFallback;
+        document.getElementById('mirrorCache').checked = settings.mirrorCache;
+        document.getElementById('recreateSymlinks').checked = settings.recreateSymlinks;
+        document.getElementById('cloudPlaceholders').value = settings.cloudPlaceholders;
+        document.getElementById('preferredProtocol').value = settings.preferredProtocol;
+        document.getElementById('cloneDepth').value = settings.cloneDepth ?? '';
+        renderAddonList();
     } catch (e) {
         logToConsole('Using default settings', 'info');
     }
+    if (settings.launchOnStartup) {
+        try {
+            const autostart = await invoke('get_autostart_status');
+            if (autostart.repairError) logToConsole(`Startup entry could not be repaired: ${autostart.repairError}`, 'warning');
+        } catch (e) {
+            logToConsole(`Startup entry check failed: ${e}`, 'warning');
+        }
+    }
 }
 
 // Check Installation Status
@@ -133,41 +262,263 @@ async function checkInstallation() {
     try {
         const status = await invoke('check_installation', { blenderVersion: settings.blenderVersion });
         updateStatusUI(status);
+        updateLastBackup(status);
+        await updatePristineBadge(status);
+        if (status.modeOnlyChanges > 0) offerFileModeFix(status.modeOnlyChanges);
+        if (status.syncFolder) warnSyncFolder(status.syncFolder);
+        if (status.nestedAddon) offerNestedFix(status.nestedAddon);
+        warnSharedInstall(status.sharedWith || []);
     } catch (e) {
         logToConsole(`Error checking installation: ${e}`, 'error');
         elements.installStatus.innerHTML = '<span class="status-indicator error"></span>Error';
     }
 }
 
+// Said once per set of versions, not on every refresh
+function warnSharedInstall(sharedWith) {
+    const changed = sharedWith.join() !== installSharedWith.join();
+    installSharedWith = sharedWith;
+    if (changed && sharedWith.length) {
+        logToConsole(`Blender ${sharedWith.join(', ')} use${sharedWith.length === 1 ? 's' : ''} this same install through a link - changes here affect ${sharedWith.length === 1 ? 'it' : 'them'} too`, 'warning');
+    }
+}
+
+async function updatePristineBadge(status) {
+    installPristine = null;
+    if (!status.installed) return;
+    try {
+        installPristine = await invoke('is_pristine', { blenderVersion: settings.blenderVersion });
+    } catch (e) {
+        // No git history or manifest - nothing to show
+        return;
+    }
+    const badge = document.createElement('span');
+    badge.className = `pristine-badge ${installPristine ? 'clean' : 'dirty'}`;
+    badge.textContent = installPristine ? 'clean' : 'modified';
+    badge.title = installPristine ? 'The files match what was installed' : 'Files were edited, added or removed since installing';
+    elements.installStatus.appendChild(badge);
+}
+
+// Files that only differ by the executable bit - the install was used from another OS.
+// Asked once per session so declining doesn't nag on every refresh.
+let fileModeOffered = false;
+
+function offerFileModeFix(count) {
+    if (fileModeOffered) return;
+    fileModeOffered = true;
+    showConfirmModal(
+        'Ignore permission changes',
+        `${count} file${count === 1 ? '' : 's'} only look modified because of file permissions, usually from sharing the install between Windows and Linux/WSL. Tell git to ignore permission changes for this install?`,
+        async () => {
+            try {
+                await invoke('set_file_mode', { blenderVersion: settings.blenderVersion, enabled: false });
+                logToConsole('git now ignores permission changes in this install (core.fileMode = false)', 'success');
+            } catch (e) {
+                logToConsole(`Couldn't change core.fileMode: ${e}`, 'error');
+            }
+        }
+    );
+}
+
+// The addon one folder too deep, where Blender can't see it. Asked once per session.
+let nestedOffered = false;
+
+function offerNestedFix(nestedPath) {
+    logToConsole(`The addon is nested one folder too deep (${nestedPath}/__init__.py), so Blender won't find it`, 'warning');
+    if (nestedOffered) return;
+    nestedOffered = true;
+    showConfirmModal(
+        'Fix nested install',
+        `The addon's files are in ${nestedPath} instead of directly in ${nestedPath.split('/')[0]}. Move them up so Blender can load the addon?`,
+        async () => {
+            try {
+                const result = await invoke('fix_nested_install', { blenderVersion: settings.blenderVersion });
+                logToConsole(`Moved the addon up out of ${result.movedFrom}`, 'success');
+                if (result.discarded.length) logToConsole(`Dropped leftovers: ${result.discarded.join(', ')}`, 'info');
+                await checkInstallation();
+            } catch (e) {
+                logToConsole(`Couldn't fix the nested install: ${e}`, 'error');
+            }
+        }
+    );
+}
+
+// Logged once per session: the folder won't move between refreshes
+let syncFolderWarned = false;
+
+function warnSyncFolder(folder) {
+    if (syncFolderWarned) return;
+    syncFolderWarned = true;
+    logToConsole(`The addons folder is inside ${folder.provider} (${folder.root}). Switches will be slower and files may be locked while it syncs; consider pausing sync or moving Blender's config out of it.`, 'warning');
+}
+
 function updateStatusUI(status) {
     const indicator = status.installed ? 'success' : 'warning';
-    const statusText = status.installed ? 'Installed' : 'Not Installed';
+    const ORIGIN_LABELS = { git: 'external clone', archive: 'from archive', symlink: 'linked' };
+    const locationLabel = status.location === 'extensions' ? 'extension' : null;
+    const originLabel = ORIGIN_LABELS[status.origin];
+    const labels = [locationLabel, originLabel, status.fork && `fork: ${status.fork}`].filter(Boolean).join(', ');
+    const statusText = status.installed ? `Installed${labels ? ` (${labels})` : ''}` : 'Not Installed';
     elements.installStatus.innerHTML = `<span class="status-indicator ${indicator}"></span>${statusText}`;
     elements.blenderVersion.textContent = settings.blenderVersion;
     elements.currentBranch.textContent = status.branch || '—';
     elements.lastUpdated.textContent = status.lastUpdated || '—';
+    elements.lastUpdated.title = status.lastUpdatedIso ? new Date(status.lastUpdatedIso).toLocaleString() : '';
     if (elements.installPath) {
         elements.installPath.textContent = status.path;
     }
     currentBranch = status.branch;
+    installOrigin = status.origin;
+
+    document.getElementById('stuckUpdate').style.display = status.mergeInProgress ? 'flex' : 'none';
 
     // Show/hide install panel based on installation status
     const installPanel = document.getElementById('installPanel');
     if (installPanel) {
         installPanel.style.display = status.installed ? 'none' : 'flex';
     }
+    const [major, minor] = settings.blenderVersion.split('.').map(Number);
+    document.getElementById('installExtensionBtn').style.display = major > 4 || (major === 4 && minor >= 2) ? '' : 'none';
+}
+
+// Nudge towards a fresh backup once the last one is a couple of weeks old
+const BACKUP_WARN_DAYS = 14;
+
+function updateLastBackup(status) {
+    const el = document.getElementById('lastBackup');
+    if (!status.hasBackup) {
+        el.textContent = 'Never';
+        el.style.color = '';
+        return;
+    }
+    const days = Math.floor((Date.now() - new Date(status.newestBackupDate).getTime()) / 86400000);
+    el.textContent = days === 0 ? 'Today' : `${days} day${days === 1 ? '' : 's'} ago`;
+    el.title = `${status.backupCount} backup${status.backupCount === 1 ? '' : 's'}`;
+    el.style.color = days >= BACKUP_WARN_DAYS ? 'var(--warning)' : '';
 }
 
 // Fetch Branches from GitHub
 async function fetchBranches() {
     elements.branchesList.innerHTML = '<div class="loading-state"><div class="spinner"></div><span>Fetching branches from GitHub...</span></div>';
     try {
-        branches = await invoke('fetch_branches');
+        const list = await invoke('fetch_branches', { remote: currentRemote });
+        branches = list.branches;
+        branchGroups = list.groups;
         renderBranches(branches);
         logToConsole(`Found ${branches.length} branches`, 'success');
     } catch (e) {
         logToConsole(`Error fetching branches: ${e}`, 'error');
         elements.branchesList.innerHTML = `<div class="loading-state"><span>Failed to fetch branches: ${e}</span></div>`;
+        await runConnectionCheck();
+    }
+}
+
+// Layer-by-layer look at why the repo can't be reached
+async function runConnectionCheck() {
+    logToConsole('Checking the connection to the repository...', 'info');
+    try {
+        const check = await invoke('check_remote', {});
+        for (const probe of check.probes) {
+            const latency = probe.latencyMs != null ? ` (${probe.latencyMs}ms)` : '';
+            const level = probe.status === 'pass' ? 'success' : probe.status === 'fail' ? 'error' : 'info';
+            logToConsole(`${probe.layer.toUpperCase()}: ${probe.detail}${latency}`, level);
+        }
+        if (check.proxy) logToConsole(`Using proxy ${check.proxy}`, 'info');
+    } catch (e) {
+        logToConsole(`Connection check failed: ${e}`, 'error');
+    }
+}
+
+// Remote picker - only shown once forks are configured in settings
+// The picker only shows once settings list an addon besides Serpens
+async function setupAddons() {
+    const select = document.getElementById('addonSelect');
+    let addons = [];
+    try {
+        addons = await invoke('list_addons');
+    } catch (e) {
+        logToConsole(`Couldn't load addons: ${e}`, 'warning');
+    }
+    if (!addons.some(a => !a.builtin && a.id === currentAddon)) currentAddon = null;
+    if (addons.length < 2) {
+        select.style.display = 'none';
+        return;
+    }
+    select.innerHTML = addons
+        .map(a => `<option value="${a.builtin ? '' : a.id}" title="${a.repoUrl}">${a.displayName}</option>`)
+        .join('');
+    select.value = currentAddon || '';
+    select.style.display = '';
+    select.onchange = async () => {
+        currentAddon = select.value || null;
+        currentRemote = null;
+        await checkInstallation();
+        await setupRemotes();
+        await fetchBranches();
+    };
+}
+
+function renderAddonList() {
+    const select = document.getElementById('addonList');
+    select.innerHTML = settings.addons.length
+        ? settings.addons.map(a => `<option value="${a.id}">${a.displayName} · ${a.folderName} · ${a.repoUrl}</option>`).join('')
+        : '<option value="">Only Serpens</option>';
+}
+
+// Takes effect on Save, which checks the entries
+function addAddon() {
+    const field = (id) => document.getElementById(id).value.trim();
+    const addon = { id: field('addonId'), folderName: field('addonFolder'), repoUrl: field('addonRepo'), displayName: field('addonName') };
+    if (!addon.id || !addon.folderName || !addon.repoUrl) {
+        logToConsole('An addon needs an id, a folder name and a repo', 'warning');
+        return;
+    }
+    if (!addon.displayName) addon.displayName = addon.id;
+    settings.addons = [...settings.addons.filter(a => a.id !== addon.id), addon];
+    ['addonId', 'addonFolder', 'addonRepo', 'addonName'].forEach(id => { document.getElementById(id).value = ''; });
+    renderAddonList();
+}
+
+function removeAddon() {
+    const id = document.getElementById('addonList').value;
+    if (!id) return;
+    settings.addons = settings.addons.filter(a => a.id !== id);
+    renderAddonList();
+}
+
+async function setupRemotes() {
+    const select = document.getElementById('remoteSelect');
+    let remotes = [];
+    try {
+        remotes = await invoke('list_remotes');
+    } catch (e) {
+        logToConsole(`Couldn't load remotes: ${e}`, 'warning');
+    }
+    if (remotes.length < 2) {
+        select.style.display = 'none';
+        currentRemote = null;
+        return;
+    }
+    select.innerHTML = remotes
+        .map(r => `<option value="${r.builtin ? '' : r.name}" title="${r.url}">${r.builtin ? 'official' : r.name}</option>`)
+        .join('');
+    select.value = currentRemote || '';
+    select.style.display = '';
+    select.onchange = () => {
+        currentRemote = select.value || null;
+        fetchBranches();
+        const remote = remotes.find(r => !r.builtin && r.name === currentRemote);
+        if (remote) checkCredentials(remote.url);
+    };
+}
+
+// A private fork fails the first switch unless git can sign in - say so up front
+async function checkCredentials(url) {
+    try {
+        const check = await invoke('check_git_credentials', { repoUrl: url });
+        if (!check.likely) logToConsole(`Sign-in to ${url} may fail: ${check.message}`, 'warning');
+    } catch (e) {
+        logToConsole(`Couldn't check git credentials: ${e}`, 'warning');
     }
 }
 
@@ -216,7 +567,25 @@ function renderBranches(branchList) {
         return;
     }
 
-    elements.branchesList.innerHTML = visibleBranches.map(branch => `
+    // Headers only help once there's more than the un-prefixed group
+    const visibleNames = new Set(visibleBranches.map(b => b.name));
+    const groups = Object.keys(branchGroups)
+        .sort((a, b) => (a === '' ? -1 : b === '' ? 1 : a.localeCompare(b)))
+        .map(prefix => ({ prefix, members: visibleBranches.filter(b => branchGroups[prefix].some(g => g.name === b.name)) }))
+        .filter(group => group.members.length);
+    const grouped = groups.reduce((n, group) => n + group.members.length, 0) === visibleNames.size;
+    if (groups.length < 2 || !grouped) {
+        elements.branchesList.innerHTML = visibleBranches.map(renderBranchItem).join('');
+        return;
+    }
+    elements.branchesList.innerHTML = groups.map(group => `
+        ${group.prefix ? `<div class="branch-group-header">${group.prefix}/</div>` : ''}
+        ${group.members.map(renderBranchItem).join('')}
+    `).join('');
+}
+
+function renderBranchItem(branch) {
+    return `
         <div class="branch-item ${branch.name === currentBranch ? 'active' : ''}" data-branch="${branch.name}" onclick="switchBranch('${branch.name}')">
             <div class="branch-icon">
                 <svg viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2">
@@ -226,10 +595,11 @@ function renderBranches(branchList) {
             <div class="branch-info">
                 <div class="branch-name">${getBranchDisplayName(branch.name)}</div>
             </div>
+            ${branch.aheadOfDefault != null ? `<span class="branch-tag divergence" title="Commits ahead of / behind the default branch">+${branch.aheadOfDefault} / −${branch.behindDefault}</span>` : ''}
             ${branch.name === currentBranch ? '<span class="branch-tag current">Current</span>' : ''}
             <span class="branch-action-text">${branch.name === currentBranch ? 'Update' : 'Switch'}</span>
         </div>
-    `).join('');
+    `;
 }
 
 // Branch Search Filter
@@ -243,21 +613,375 @@ function filterBranches(query) {
 
 // Switch Branch (or Update if already on that branch)
 async function switchBranch(branchName) {
+    // Installs the manager didn't make get replaced wholesale - make sure that's intended
+    let warning = '';
+    if (installOrigin === 'symlink') {
+        warning = 'The addon folder is a link to another folder. Switching replaces the link with a fresh clone (the linked folder itself is left alone). ';
+    } else if (installOrigin === 'git') {
+        warning = 'The current install is a git clone the manager didn\'t create. Switching replaces it with a fresh clone. ';
+    }
+    if (installPristine === false) {
+        warning += 'Files in the install were changed since it was installed; switching replaces them. ';
+    }
+    if (installSharedWith.length && installOrigin !== 'symlink') {
+        warning += `Blender ${installSharedWith.join(', ')} share${installSharedWith.length === 1 ? 's' : ''} this install, so switching changes ${installSharedWith.length === 1 ? 'it' : 'them'} too. `;
+    } else if (installSharedWith.length) {
+        warning += `The linked folder is also used by Blender ${installSharedWith.join(', ')}, which keep${installSharedWith.length === 1 ? 's' : ''} it after the switch. `;
+    }
+    // Worth knowing on a metered connection; the switch goes ahead without it if GitHub can't say
+    let size = '';
+    try {
+        const estimate = await invoke('estimate_clone_size', { branch: branchName, remote: currentRemote });
+        size = `~${formatBytes(estimate.bytes)} will be downloaded.`;
+    } catch (e) {
+        size = 'The download size is unknown.';
+    }
+    const changes = await describeSwitchChanges(branchName);
+    const external = installOrigin === 'symlink' || installOrigin === 'git';
+    const title = external ? 'Replace external install?' : `Switch to ${branchName}?`;
+    showConfirmModal(title, `${warning}${changes}${size}`, () => doSwitchBranch(branchName));
+}
+
+// "12 files change (3 commits ahead, 1 behind); 2 of them have local edits. "
+async function describeSwitchChanges(branchName) {
+    if (branchName === currentBranch || !installOrigin) return '';
+    let preview;
+    try {
+        preview = await invoke('preview_switch', { blenderVersion: settings.blenderVersion, branch: branchName, remote: currentRemote });
+    } catch (e) {
+        logToConsole(`Couldn't preview the switch: ${e}`, 'warning');
+        return '';
+    }
+    if (!preview.computable) return `${preview.reason}. `;
+    const counts = preview.ahead != null ? ` (${preview.ahead} commits ahead, ${preview.behind} behind)` : '';
+    preview.files.forEach(f => logToConsole(`  ${f.status}: ${f.oldPath ? `${f.oldPath} → ` : ''}${f.path}${f.locallyModified ? ' (edited locally)' : ''}`, 'info'));
+    const edited = preview.files.filter(f => f.locallyModified).length;
+    const localOnly = preview.localChanges.length - edited;
+    let text = `${preview.files.length} files change${counts}. `;
+    if (edited) text += `${edited} of them have local edits that will be overwritten. `;
+    if (localOnly > 0) text += `${localOnly} other locally edited files are reset too. `;
+    return text;
+}
+
+// Delete staging folders interrupted installs left behind
+async function cleanupTempDirs() {
+    try {
+        const cleanup = await invoke('cleanup_temp_dirs');
+        cleanup.failed.forEach(e => logToConsole(e, 'warning'));
+        if (cleanup.removed.length === 0) {
+            logToConsole('No leftover temp folders found', 'info');
+        } else {
+            logToConsole(`Removed ${cleanup.removed.length} temp folder(s), freeing ${formatBytes(cleanup.freedBytes)}`, 'success');
+        }
+    } catch (e) {
+        logToConsole(`Cleanup failed: ${e}`, 'error');
+    }
+}
+
+// Where the install stands against commits support has vouched for
+async function checkKnownGood() {
+    const commits = document.getElementById('knownGoodCommits').value.split(/[\s,]+/).filter(Boolean);
+    try {
+        const report = await invoke('check_known_good', { blenderVersion: settings.blenderVersion, commits });
+        const installed = report.commit.slice(0, 12);
+        const nearest = report.nearest;
+        if (report.matched) {
+            logToConsole(`The installed commit ${installed} is known-good`, 'success');
+        } else if (!nearest) {
+            logToConsole(`None of the known-good commits could be compared with ${installed}`, 'warning');
+        } else {
+            const where = {
+                ahead: `${nearest.ahead} commit${nearest.ahead === 1 ? '' : 's'} past`,
+                behind: `${nearest.behind} commit${nearest.behind === 1 ? '' : 's'} behind`,
+                diverged: `${nearest.ahead} ahead of and ${nearest.behind} behind`,
+            }[nearest.relation];
+            logToConsole(`The installed commit ${installed} isn't known-good - it's ${where} the nearest, ${nearest.commit.slice(0, 12)}`, 'warning');
+        }
+        if (report.unknown.length) logToConsole(`Unknown commits: ${report.unknown.join(', ')}`, 'warning');
+    } catch (e) {
+        logToConsole(`Known-good check failed: ${e}`, 'error');
+    }
+}
+
+// Local vs remote for every branch the install knows of, after one fetch
+async function showSyncOverview() {
+    logToConsole('Fetching every remote...', 'info');
+    try {
+        const overview = await invoke('branch_sync_overview', { blenderVersion: settings.blenderVersion });
+        if (overview.fetchError) logToConsole(`Fetch failed, showing the last known state: ${overview.fetchError}`, 'warning');
+        overview.branches.forEach(b => {
+            const where = b.remote ? `${b.remote}/${b.name}` : `${b.name} (local only)`;
+            const state = b.ahead != null ? `+${b.ahead} / −${b.behind}` : (b.local ? 'no remote' : 'remote only');
+            const date = b.remoteDate ? `, remote ${new Date(b.remoteDate).toLocaleDateString()}` : '';
+            logToConsole(`${b.checkedOut ? '* ' : '  '}${where}: ${state}${date}`, 'info');
+        });
+        if (overview.shallow) logToConsole('The install is shallow, so ahead/behind only count as far back as it reaches', 'info');
+    } catch (e) {
+        logToConsole(`Sync overview failed: ${e}`, 'error');
+    }
+}
+
+// Restore points: a local tag plus the edits at the time, for git installs only
+async function loadRestorePoints() {
+    const select = document.getElementById('restorePointSelect');
+    let points = [];
+    try {
+        points = await invoke('list_states', { blenderVersion: settings.blenderVersion });
+    } catch (e) {
+        // Not a git install - nothing to list
+    }
+    select.innerHTML = points.length
+        ? points.map(p => `<option value="${p.label}">${p.label} · ${p.commit.slice(0, 7)}${p.stashed ? ' + edits' : ''}</option>`).join('')
+        : '<option value="">No restore points</option>';
+}
+
+async function tagState() {
+    const label = document.getElementById('restorePointLabel').value.trim();
+    try {
+        const point = await invoke('tag_state', { label, blenderVersion: settings.blenderVersion });
+        logToConsole(`Tagged ${point.commit.slice(0, 7)} as ${point.tag}${point.stashed ? ', with the local edits' : ''}`, 'success');
+        document.getElementById('restorePointLabel').value = '';
+        await loadRestorePoints();
+    } catch (e) {
+        logToConsole(`Couldn't tag the install: ${e}`, 'error');
+    }
+}
+
+function restoreState() {
+    const label = document.getElementById('restorePointSelect').value;
+    if (!label) return;
+    showConfirmModal('Restore point', `Put the install back to "${label}"? Current edits are stashed first.`, async () => {
+        try {
+            const restored = await invoke('restore_state', { label, blenderVersion: settings.blenderVersion });
+            if (restored.savedCurrent) logToConsole(`Current edits stashed as "${restored.savedCurrent}"`, 'info');
+            logToConsole(`Restored ${label} (${restored.commit.slice(0, 7)}${restored.restoredChanges ? ', with its edits' : ''})`, 'success');
+            if (restored.detached) logToConsole('The branch has moved on since, so the install is on the commit itself - switch or pull to get back onto the branch', 'warning');
+            await checkInstallation();
+        } catch (e) {
+            logToConsole(`Restore failed: ${e}`, 'error');
+        }
+    });
+}
+
+async function deleteState() {
+    const label = document.getElementById('restorePointSelect').value;
+    if (!label) return;
+    try {
+        await invoke('delete_state', { label, blenderVersion: settings.blenderVersion });
+        logToConsole(`Deleted restore point ${label}`, 'info');
+        await loadRestorePoints();
+    } catch (e) {
+        logToConsole(`Couldn't delete ${label}: ${e}`, 'error');
+    }
+}
+
+// Profiles are saved straight to disk, so the copy here is refreshed for the next Save
+async function loadProfiles() {
+    const select = document.getElementById('profileSelect');
+    try {
+        settings.profiles = await invoke('list_profiles');
+    } catch (e) {
+        logToConsole(`Couldn't load profiles: ${e}`, 'warning');
+    }
+    select.innerHTML = settings.profiles.length
+        ? settings.profiles.map(p => `<option value="${p.name}">${p.name} · ${p.repoUrl} · ${p.branch} · Blender ${p.blenderVersion}</option>`).join('')
+        : '<option value="">No profiles</option>';
+}
+
+async function saveProfile() {
+    const name = document.getElementById('profileName').value.trim();
+    try {
+        const profile = await invoke('save_profile', { name });
+        logToConsole(`Saved profile "${profile.name}": ${profile.branch} from ${profile.repoUrl} on Blender ${profile.blenderVersion}`, 'success');
+        document.getElementById('profileName').value = '';
+        await loadProfiles();
+    } catch (e) {
+        logToConsole(`Couldn't save the profile: ${e}`, 'error');
+    }
+}
+
+async function applyProfile() {
+    const name = document.getElementById('profileSelect').value;
+    if (!name) return;
+    elements.settingsModal.classList.remove('active');
+    logToConsole(`Applying profile "${name}"...`, 'info');
+    const stopProgress = trackOperationProgress();
+    try {
+        const applied = await invoke('apply_profile', { name });
+        applied.steps.forEach(step => logToConsole(step, 'info'));
+        logToConsole(`Profile "${name}" applied`, 'success');
+        currentAddon = applied.addonId;
+        currentRemote = applied.remote;
+    } catch (e) {
+        logToConsole(`Couldn't apply profile "${name}": ${e}`, 'error');
+    } finally {
+        stopProgress();
+    }
+    // The settings may have changed even when the switch failed
+    await loadSettings();
+    await setupAddons();
+    await checkInstallation();
+    await setupRemotes();
+    await fetchBranches();
+}
+
+async function deleteProfile() {
+    const name = document.getElementById('profileSelect').value;
+    if (!name) return;
+    try {
+        await invoke('delete_profile', { name });
+        logToConsole(`Deleted profile ${name}`, 'info');
+        await loadProfiles();
+    } catch (e) {
+        logToConsole(`Couldn't delete ${name}: ${e}`, 'error');
+    }
+}
+
+// Force Blender to recompile the addon on its next start
+async function clearPycache() {
+    try {
+        const report = await invoke('clear_pycache', { blenderVersion: settings.blenderVersion });
+        const found = report.directories.length + report.files.length;
+        if (found === 0) {
+            logToConsole('No compiled Python caches found', 'info');
+        } else {
+            logToConsole(`Removed ${report.removed} of ${found} compiled Python cache entries (${formatBytes(report.bytes)})`, report.removed === found ? 'success' : 'warning');
+        }
+    } catch (e) {
+        logToConsole(`Couldn't clear the Python cache: ${e}`, 'error');
+    }
+}
+
+async function clearMirrorCache() {
+    try {
+        const freed = await invoke('clear_mirror_cache');
+        logToConsole(`Cleared the mirror cache, freeing ${formatBytes(freed)}`, 'success');
+    } catch (e) {
+        logToConsole(`Couldn't clear the mirror cache: ${e}`, 'error');
+    }
+}
+
+// The whole data folder moves; the old location keeps only a pointer to the new one
+async function relocateDataDir() {
+    const input = document.getElementById('dataDirPath');
+    const newPath = input.value.trim();
+    if (!newPath) return;
+    logToConsole(`Moving the data folder to ${newPath}...`, 'info');
+    try {
+        const moved = await invoke('relocate_data_dir', { newPath });
+        logToConsole(`Moved ${formatBytes(moved.bytes)} of data to ${moved.to}`, 'success');
+        if (moved.leftover) logToConsole(`Some of the old folder was left behind: ${moved.leftover}`, 'warning');
+        input.value = moved.to;
+    } catch (e) {
+        logToConsole(`Couldn't move the data folder: ${e}`, 'error');
+    }
+}
+
+function formatBytes(bytes) {
+    if (bytes < 1024 * 1024) return `${Math.max(1, Math.round(bytes / 1024))} KB`;
+    const mb = bytes / (1024 * 1024);
+    return mb < 10 ? `${mb.toFixed(1)} MB` : `${Math.round(mb)} MB`;
+}
+
+// Bar under the status card while git reports its transfer
+const TRANSFER_LABELS = {
+    counting: 'Counting objects',
+    compressing: 'Compressing objects',
+    receiving: 'Receiving objects',
+    resolving: 'Resolving deltas',
+    checkout: 'Checking out files',
+};
+
+function renderOperationProgress(op) {
+    document.body.classList.toggle('busy', op?.status === 'running');
+    const box = document.getElementById('gitProgress');
+    if (!op || op.status !== 'running' || op.progress == null) {
+        box.style.display = 'none';
+        return;
+    }
+    box.style.display = 'block';
+    document.getElementById('gitProgressFill').style.width = `${op.progress}%`;
+    const t = op.transfer;
+    let label = op.phase || op.kind;
+    if (t) {
+        label = TRANSFER_LABELS[t.phase] || t.phase;
+        if (t.objectsTotal) label += ` ${t.objectsDone}/${t.objectsTotal}`;
+        if (t.bytesReceived) {
+            label += `, ${formatBytes(t.bytesReceived)}`;
+            if (t.totalBytesEstimate && !t.done) label += ` of ~${formatBytes(t.totalBytesEstimate)}`;
+        }
+        if (t.bytesPerSecond && !t.done) label += ` at ${formatBytes(t.bytesPerSecond)}/s`;
+    }
+    document.getElementById('gitProgressLabel').textContent = label;
+}
+
+// git lines the progress parser didn't recognise, printed once each
+function logNewGitOutput(op, shown) {
+    if (!op?.log?.length) return shown;
+    const last = shown ? op.log.lastIndexOf(shown) : -1;
+    op.log.slice(last + 1).forEach(line => logToConsole(`git: ${line}`, 'info'));
+    return op.log[op.log.length - 1];
+}
+
+// Poll the running operation until stop() is called
+function trackOperationProgress() {
+    let lastLine = null;
+    const timer = setInterval(async () => {
+        const op = await invoke('get_active_operation').catch(() => null);
+        renderOperationProgress(op);
+        lastLine = logNewGitOutput(op, lastLine);
+    }, 500);
+    return () => {
+        clearInterval(timer);
+        renderOperationProgress(null);
+    };
+}
+
+// "in 8.2s (cloning 6.9s, backing up 1.1s, ...)", slowest phase first
+function formatTimings(timed) {
+    const seconds = (ms) => `${(ms / 1000).toFixed(1)}s`;
+    const phases = Object.entries(timed.timings)
+        .sort((a, b) => b[1] - a[1])
+        .map(([phase, ms]) => `${phase} ${seconds(ms)}`);
+    return `in ${seconds(timed.totalMs)} (${phases.join(', ')})`;
+}
+
+async function doSwitchBranch(branchName, force = false) {
     const isUpdate = branchName === currentBranch;
     const action = isUpdate ? 'Updating' : 'Switching to branch';
     logToConsole(`${action}: ${branchName}...`, 'info');
+    const stopProgress = trackOperationProgress();
     try {
-        await invoke('switch_branch', { branchName, blenderVersion: settings.blenderVersion });
-        logToConsole(`Successfully ${isUpdate ? 'updated' : 'switched to'} ${branchName}!`, 'success');
+        const timed = await invoke('switch_branch', { branchName, blenderVersion: settings.blenderVersion, remote: currentRemote, force });
+        logToConsole(`Successfully ${isUpdate ? 'updated' : 'switched to'} ${branchName}! ${formatTimings(timed)}`, 'success');
+        if (timed.result.degraded) {
+            logToConsole('The connection kept failing, so only the latest commit was downloaded - fetch the full history from the install folder once it recovers (git fetch --unshallow)', 'warning');
+        }
         currentBranch = branchName;
         await checkInstallation();
+        if (settings.verifyAfterUpdate) await verifyInstallation();
         renderBranches(branches);
     } catch (e) {
-        logToConsole(`Error ${isUpdate ? 'updating' : 'switching'}: ${e}`, 'error');
+        if (e.code === 'UNPUSHED_COMMITS') {
+            confirmDiscardCommits(e, () => doSwitchBranch(branchName, true));
+            return;
+        }
+        logToConsole(`Error ${isUpdate ? 'updating' : 'switching'}: ${e.message ?? e}`, 'error');
+    } finally {
+        stopProgress();
     }
 }
 window.switchBranch = switchBranch;
 
+// The install has commits no remote has - show them and how to keep them, and run
+// `proceed` if the user goes ahead anyway
+function confirmDiscardCommits(error, proceed) {
+    logToConsole(error.message, 'warning');
+    error.commits.forEach(commit => logToConsole(`  ${commit}`, 'warning'));
+    logToConsole(`To keep them, run in the install folder: ${error.suggestions.join('  or  ')}`, 'info');
+    showConfirmModal('Unpushed commits', `${error.message}. Replace it anyway?`, proceed);
+}
+
 // Install Serpens (for new users without installation)
 async function installSerpens() {
     logToConsole('Installing Serpens (personal-dev branch)...', 'info');
@@ -268,7 +992,21 @@ async function installSerpens() {
         await checkInstallation();
         await fetchBranches();
     } catch (e) {
-        logToConsole(`Installation failed: ${e}`, 'error');
+        logToConsole(`Installation failed: ${e.message ?? e}`, 'error');
+    }
+}
+
+// Install into Blender's extensions folder instead of scripts/addons
+async function installAsExtension() {
+    logToConsole('Installing Serpens as an extension (personal-dev branch)...', 'info');
+    try {
+        const timed = await invoke('install_as_extension', { branch: 'personal-dev', blenderVersion: settings.blenderVersion });
+        logToConsole(`Installed as an extension in ${timed.result.path}`, 'success');
+        timed.result.warnings.forEach(w => logToConsole(w, 'warning'));
+        await checkInstallation();
+        await fetchBranches();
+    } catch (e) {
+        logToConsole(`Extension install failed: ${e.message ?? e}`, 'error');
     }
 }
 
@@ -281,6 +1019,558 @@ async function openFolder() {
     }
 }
 
+// Back out of a pull that stopped on conflicts
+async function fixStuckUpdate() {
+    try {
+        await invoke('abort_merge', { blenderVersion: settings.blenderVersion });
+        logToConsole('Stuck update undone - the install is back to how it was before the pull', 'success');
+        await checkInstallation();
+    } catch (e) {
+        logToConsole(`Couldn't fix the stuck update: ${e}`, 'error');
+    }
+}
+
+// Uninstall - always keeps a backup from the UI
+function uninstallAddon() {
+    showConfirmModal(
+        'Uninstall Serpens',
+        `Remove Serpens from Blender ${settings.blenderVersion}?${installPristine === false ? ' The install has local changes.' : ''} A backup is taken first.`,
+        async () => {
+            logToConsole('Uninstalling...', 'info');
+            try {
+                const result = await invoke('uninstall_addon', {
+                    blenderVersion: settings.blenderVersion,
+                    keepBackup: true,
+                    clearPycache: true,
+                });
+                logToConsole(`Uninstalled${result.backupPath ? ` - backup at ${result.backupPath}` : ''}`, 'success');
+            } catch (e) {
+                logToConsole(`Uninstall failed: ${e.message ?? e}`, 'error');
+                if (e.partial) await checkInstallation();
+            }
+        }
+    );
+}
+
+// Check the installed files against git (or the install manifest) and report anything off
+async function verifyInstallation() {
+    try {
+        const result = await invoke('verify_installation', { blenderVersion: settings.blenderVersion });
+        if (result.verdict === 'intact') {
+            logToConsole('All installed files check out', 'success');
+        } else if (result.verdict === 'unverifiable') {
+            logToConsole('This install has nothing to verify its files against', 'info');
+        } else {
+            const parts = [];
+            if (result.missing.length) parts.push(`missing: ${result.missing.join(', ')}`);
+            if (result.modified.length) parts.push(`modified: ${result.modified.join(', ')}`);
+            logToConsole(`Installed files ${result.verdict} - ${parts.join('; ')}`, result.verdict === 'damaged' ? 'error' : 'warning');
+            if (result.method === 'git') {
+                showConfirmModal(
+                    'Repair installation',
+                    'Restore the missing and changed files from git? Your packages and snippets are left as they are.',
+                    repairInstallation
+                );
+            }
+        }
+    } catch (e) {
+        logToConsole(`Couldn't verify installation: ${e}`, 'warning');
+    }
+}
+
+async function repairInstallation() {
+    logToConsole('Repairing installation...', 'info');
+    try {
+        const result = await invoke('repair_installation', { blenderVersion: settings.blenderVersion });
+        if (result.restored.length) logToConsole(`Restored ${result.restored.length} file(s)`, 'success');
+        if (result.skipped.length) logToConsole(`Left your edits in: ${result.skipped.join(', ')}`, 'info');
+        if (result.unfixed.length) logToConsole(`Couldn't fix: ${result.unfixed.join(', ')}`, 'error');
+        if (result.recommendation) logToConsole(result.recommendation, 'warning');
+    } catch (e) {
+        logToConsole(`Repair failed: ${e}`, 'error');
+    }
+}
+
+// Re-attach to an operation that was running when the page (re)loaded
+async function resumeActiveOperation() {
+    let op;
+    try {
+        op = await invoke('current_operation') ?? await invoke('get_active_operation');
+    } catch (e) {
+        return;
+    }
+    if (!op) return;
+    if (op.status !== 'running') {
+        const level = op.status === 'succeeded' ? 'success' : op.status === 'failed' ? 'error' : op.status === 'abandoned' ? 'warning' : 'info';
+        logToConsole(`${op.kind} ${op.status}${op.error ? `: ${op.error}` : ''}`, level);
+        return;
+    }
+    renderOperationProgress(op);
+    const minutes = Math.floor(op.elapsedMs / 60000);
+    const running = minutes ? `${minutes}m ${Math.floor(op.elapsedMs / 1000) % 60}s` : `${Math.floor(op.elapsedMs / 1000)}s`;
+    logToConsole(`${op.kind} is still running${op.phase ? ` (${op.phase})` : ''}, started ${running} ago...`, 'info');
+    let lastPhase = op.phase;
+    let wasPaused = op.paused;
+    while (op && op.status === 'running') {
+        await new Promise(resolve => setTimeout(resolve, 1000));
+        op = await invoke('get_active_operation').catch(() => null);
+        renderOperationProgress(op);
+        if (op?.phase && op.phase !== lastPhase) {
+            logToConsole(`${op.kind}: ${op.phase}...`, 'info');
+            lastPhase = op.phase;
+        }
+        if (op && op.paused !== wasPaused) {
+            logToConsole(`${op.kind} ${op.paused ? 'paused' : 'resumed'}`, 'info');
+            wasPaused = op.paused;
+        }
+    }
+    if (op) {
+        const level = op.status === 'succeeded' ? 'success' : op.status === 'failed' ? 'error' : op.status === 'abandoned' ? 'warning' : 'info';
+        logToConsole(`${op.kind} ${op.status}${op.error ? `: ${op.error}` : ''}`, level);
+    }
+    await checkInstallation();
+}
+
+// Deep Links (serpens-dev://install?branch=...)
+async function setupDeepLinks() {
+    try {
+        await listen('serpens://deep-link-install', async (link) => {
+            await invoke('take_pending_deep_link');
+            promptDeepLinkInstall(link);
+        });
+        await listen('serpens://deep-link-rejected', (reason) => {
+            logToConsole(`Ignored install link: ${reason}`, 'warning');
+        });
+        // A link that launched the app arrived before we were listening
+        const pending = await invoke('take_pending_deep_link');
+        if (pending) promptDeepLinkInstall(pending);
+    } catch (e) {
+        logToConsole(`Deep link setup failed: ${e}`, 'warning');
+    }
+}
+
+function promptDeepLinkInstall(link) {
+    const version = link.version || settings.blenderVersion;
+    showConfirmModal(
+        'Install from link',
+        `Install branch "${link.branch}" for Blender ${version}? This replaces the current installation.`,
+        async () => {
+            logToConsole(`Installing ${link.branch} for Blender ${version} from link...`, 'info');
+            try {
+                await invoke('switch_branch', { branchName: link.branch, blenderVersion: version });
+                logToConsole(`Successfully switched to ${link.branch}!`, 'success');
+                if (version === settings.blenderVersion) currentBranch = link.branch;
+                await checkInstallation();
+                renderBranches(branches);
+            } catch (e) {
+                logToConsole(`Error switching: ${e.message ?? e}`, 'error');
+            }
+        }
+    );
+}
+
+// Drag-and-drop zip or folder install
+async function setupZipDrop() {
+    try {
+        await listen('serpens://zip-dropped', (addon) => {
+            const name = addon.zipPath.split(/[\\/]/).pop();
+            const version = addon.version ? ` (v${addon.version})` : '';
+            showConfirmModal(
+                'Install from zip',
+                `Install ${name}${version} for Blender ${settings.blenderVersion}? This replaces the current installation.`,
+                async function install(force = false) {
+                    logToConsole(`Installing ${name}...`, 'info');
+                    try {
+                        await invoke('install_from_zip', { zipPath: addon.zipPath, blenderVersion: settings.blenderVersion, force });
+                        logToConsole(`Installed ${name}`, 'success');
+                        await checkInstallation();
+                    } catch (e) {
+                        if (e.code === 'UNPUSHED_COMMITS') {
+                            confirmDiscardCommits(e, () => install(true));
+                            return;
+                        }
+                        logToConsole(`Zip install failed: ${e.message ?? e}`, 'error');
+                    }
+                }
+            );
+        });
+        await listen('serpens://folder-dropped', (addon) => {
+            const version = addon.version ? ` (v${addon.version})` : '';
+            showConfirmModal(
+                'Install from folder',
+                `Copy ${addon.addonPath}${version} into Blender ${settings.blenderVersion}? This replaces the current installation.`,
+                async function install(force = false) {
+                    logToConsole(`Installing from ${addon.addonPath}...`, 'info');
+                    try {
+                        await invoke('install_from_folder', { blenderVersion: settings.blenderVersion, sourcePath: addon.addonPath, force });
+                        logToConsole('Installed from folder', 'success');
+                        await checkInstallation();
+                    } catch (e) {
+                        if (e.code === 'UNPUSHED_COMMITS') {
+                            confirmDiscardCommits(e, () => install(true));
+                            return;
+                        }
+                        logToConsole(`Folder install failed: ${e.message ?? e}`, 'error');
+                    }
+                }
+            );
+        });
+        await listen('serpens://zip-drop-rejected', (reason) => logToConsole(reason, 'warning'));
+    } catch (e) {
+        logToConsole(`Drag-and-drop setup failed: ${e}`, 'warning');
+    }
+}
+
+// Installs removed or added outside a switch, e.g. by uninstall
+async function setupInstallWatch() {
+    try {
+        await listen('serpens://installation-changed', async (change) => {
+            if (change.blenderVersion === settings.blenderVersion) await checkInstallation();
+        });
+    } catch (e) {
+        logToConsole(`Install watch setup failed: ${e}`, 'warning');
+    }
+}
+
+// New commits on the installed branch, found by the launch or background check
+async function setupUpdateStatus() {
+    const announce = (status) => {
+        if (status?.updateAvailable) {
+            logToConsole(`${status.pending} new commit${status.pending === 1 ? '' : 's'} on ${status.branch} - pull to update`, 'info');
+        }
+    };
+    try {
+        await listen('serpens://update-status', announce);
+        // The launch check may have finished before the window was listening
+        announce(await invoke('last_update_status'));
+    } catch (e) {
+        logToConsole(`Update status setup failed: ${e}`, 'warning');
+    }
+}
+
+// Branches created or deleted on the remote, spotted by the background check
+async function setupBranchWatch() {
+    try {
+        await listen('serpens://new-branches', async (changes) => {
+            if (changes.added.length) {
+                logToConsole(`New branches: ${changes.added.join(', ')}`, 'info');
+            }
+            if (changes.removed.length) {
+                logToConsole(`Branches removed: ${changes.removed.join(', ')}`, 'info');
+            }
+            await fetchBranches();
+        });
+    } catch (e) {
+        logToConsole(`Branch watch setup failed: ${e}`, 'warning');
+    }
+}
+
+// Download, verify and install the newer manager, or just open the download when
+// self-update is off or there's no verifiable installer
+async function applyAppUpdate() {
+    if (!appUpdate) return;
+    if (!settings.selfUpdate || !appUpdate.checksumUrl) {
+        try {
+            await invoke('plugin:shell|open', { path: appUpdate.downloadUrl });
+        } catch (e) {
+            logToConsole(`Couldn't open the download: ${e}`, 'error');
+        }
+        return;
+    }
+
+    logToConsole(`Downloading Serpens Dev Manager ${appUpdate.latestVersion}...`, 'info');
+    let lastPercent = -1;
+    await listen('serpens://app-update-progress', ({ downloaded, total }) => {
+        if (!total) return;
+        const percent = Math.floor(downloaded * 100 / total / 25) * 25;
+        if (percent > lastPercent) {
+            lastPercent = percent;
+            logToConsole(`Download ${percent}%`, 'info');
+        }
+    });
+    try {
+        const downloaded = await invoke('download_app_update');
+        showConfirmModal(
+            'Install update',
+            `Serpens Dev Manager ${downloaded.version} is downloaded and verified. Install it and restart now?`,
+            async () => {
+                try {
+                    await invoke('install_app_update_and_restart');
+                } catch (e) {
+                    logToConsole(`Update install failed: ${e.message}`, 'error');
+                }
+            }
+        );
+    } catch (e) {
+        logToConsole(`Update ${e.stage} failed: ${e.message}`, 'error');
+    }
+}
+
+// Backend heads-ups (stale backup, low disk, ...) shown as dismissable toasts
+const NOTICE_ACTIONS = {
+    backup: {
+        label: 'Back up now',
+        run: async () => {
+            try {
+                const { result: path } = await invoke('backup_installation', { blenderVersion: settings.blenderVersion });
+                logToConsole(`Backup saved: ${path}`, 'success');
+            } catch (e) {
+                logToConsole(`Backup failed: ${e}`, 'error');
+            }
+        }
+    },
+    branches: {
+        label: 'Pick a branch',
+        run: () => elements.branchSearch.focus()
+    },
+    app_update: {
+        label: 'Update',
+        run: () => applyAppUpdate()
+    },
+    settings: {
+        label: 'Settings',
+        run: () => elements.settingsModal.classList.add('active')
+    },
+    retry: {
+        label: 'Retry',
+        run: () => retryLastOperation()
+    }
+};
+
+// Run the last failed switch or pull again, resuming its clone when one was kept
+async function retryLastOperation() {
+    logToConsole('Retrying the last failed operation...', 'info');
+    const stopProgress = trackOperationProgress();
+    try {
+        const retried = await invoke('retry_last_operation');
+        logToConsole(`Retry succeeded (${retried.method === 'resumed' ? 'resumed the kept download' : 'started over'})`, 'success');
+        await checkInstallation();
+        renderBranches(branches);
+    } catch (e) {
+        logToConsole(`Retry failed: ${e}`, 'error');
+    } finally {
+        stopProgress();
+    }
+}
+
+function showNotice(notice) {
+    const stack = document.getElementById('toastStack');
+    stack.querySelector(`[data-code="${notice.code}"]`)?.remove();
+
+    const toast = document.createElement('div');
+    toast.className = `toast toast-${notice.level}`;
+    toast.dataset.code = notice.code;
+    const message = document.createElement('span');
+    message.textContent = notice.message;
+    toast.appendChild(message);
+
+    const action = NOTICE_ACTIONS[notice.action];
+    if (action) {
+        const button = document.createElement('button');
+        button.className = 'btn btn-ghost';
+        button.textContent = action.label;
+        button.onclick = () => { toast.remove(); action.run(); };
+        toast.appendChild(button);
+    }
+    const close = document.createElement('button');
+    close.className = 'btn btn-ghost';
+    close.textContent = '×';
+    close.onclick = () => toast.remove();
+    toast.appendChild(close);
+
+    stack.appendChild(toast);
+    logToConsole(notice.message, notice.level);
+}
+
+async function setupNotices() {
+    try {
+        await listen('serpens://notice', showNotice);
+        // Catch up on anything sent before we were listening
+        const recent = await invoke('get_notices');
+        recent.forEach(showNotice);
+    } catch (e) {
+        logToConsole(`Notice setup failed: ${e}`, 'warning');
+    }
+}
+
+// Side-by-side file lists of two remote branches
+function openCompareModal() {
+    const options = sortBranches(branches.filter(b => !HIDDEN_BRANCHES.includes(b.name)))
+        .map(b => `<option value="${b.name}">${getBranchDisplayName(b.name)}</option>`)
+        .join('');
+    const base = document.getElementById('compareBase');
+    const other = document.getElementById('compareOther');
+    base.innerHTML = options;
+    other.innerHTML = options;
+    if (currentBranch) base.value = currentBranch;
+    if (other.options.length > 1 && other.value === base.value) other.selectedIndex = 1;
+    document.getElementById('compareModal').classList.add('active');
+}
+
+// Blender honours BLENDER_USER_SCRIPTS and friends, so say when they moved the folders
+async function showUserDirs() {
+    const hint = document.getElementById('userDirsHint');
+    try {
+        const dirs = await invoke('blender_user_dirs', { blenderVersion: settings.blenderVersion });
+        const moved = [['Addons', dirs.addonsDir, dirs.addonsSource], ['Extensions', dirs.extensionsDir, dirs.extensionsSource]]
+            .filter(([, , source]) => source !== 'default' && source !== 'setting')
+            .map(([what, dir, source]) => `${what}: ${dir} (from ${source})`);
+        hint.textContent = moved.join(' · ');
+        moved.forEach(line => logToConsole(`Blender environment override - ${line}`, 'info'));
+    } catch (e) {
+        hint.textContent = '';
+    }
+}
+
+// A branch's changelog or readme, read from the remote without installing it
+function openPreviewModal() {
+    const select = document.getElementById('previewBranch');
+    select.innerHTML = sortBranches(branches.filter(b => !HIDDEN_BRANCHES.includes(b.name)))
+        .map(b => `<option value="${b.name}">${getBranchDisplayName(b.name)}</option>`)
+        .join('');
+    if (currentBranch) select.value = currentBranch;
+    document.getElementById('previewContent').textContent = '';
+    document.getElementById('previewSummary').textContent = '';
+    document.getElementById('previewModal').classList.add('active');
+    runPreview();
+}
+
+async function runPreview() {
+    const branch = document.getElementById('previewBranch').value;
+    const path = document.getElementById('previewPath').value;
+    const summary = document.getElementById('previewSummary');
+    const content = document.getElementById('previewContent');
+    if (!branch) return;
+    content.textContent = '';
+    if (path === 'log') return showRemoteLog(branch, summary, content);
+    if (path === 'tree') return showRemoteTree(branch, summary, content);
+    summary.textContent = `Reading ${path} from ${branch}...`;
+    try {
+        const file = await invoke('get_branch_file', { branch, path });
+        if (!file.present) {
+            summary.textContent = `${branch} has no ${path}`;
+            return;
+        }
+        summary.textContent = `${path} at ${file.commit.slice(0, 7)}` + (file.truncated ? ' - long file, showing the start only' : '');
+        content.textContent = file.content;
+    } catch (e) {
+        summary.textContent = `Couldn't read ${path}: ${e}`;
+        logToConsole(`Branch file preview failed: ${e}`, 'error');
+    }
+}
+
+async function showRemoteTree(branch, summary, content) {
+    summary.textContent = `Listing ${branch}'s files...`;
+    try {
+        const tree = await invoke('get_remote_tree', { branch });
+        const lines = tree.entries.map(e => e.type === 'dir'
+            ? `${e.path}/`
+            : `${e.path}${e.size != null ? `  (${formatBytes(e.size)})` : ''}`);
+        if (tree.truncated) lines.push(`\n(${tree.total - tree.entries.length} more not shown)`);
+        summary.textContent = `${tree.total} entries at ${tree.commit.slice(0, 7)}`;
+        content.textContent = lines.join('\n');
+    } catch (e) {
+        summary.textContent = `Couldn't list the files: ${e}`;
+        logToConsole(`Remote file listing failed: ${e}`, 'error');
+    }
+}
+
+async function showRemoteLog(branch, summary, content) {
+    summary.textContent = `Reading ${branch}'s history...`;
+    try {
+        const log = await invoke('get_remote_log', { branch, limit: 20 });
+        const lines = log.commits.map(c =>
+            `${c.sha.slice(0, 7)}  ${new Date(c.date).toLocaleDateString()}  ${c.author}\n    ${c.subject}`);
+        if (log.boundary) lines.push(`\n(history known up to ${log.boundary.slice(0, 7)} - older commits weren't fetched)`);
+        else if (log.more) lines.push('\n(older commits not shown)');
+        summary.textContent = `Last ${log.commits.length} commits on ${branch}`;
+        content.textContent = lines.join('\n');
+    } catch (e) {
+        summary.textContent = `Couldn't read the history: ${e}`;
+        logToConsole(`Remote history failed: ${e}`, 'error');
+    }
+}
+
+// Forks of the official repo; picking one adds it as a remote and lists its branches
+async function openForksModal() {
+    const list = document.getElementById('forksList');
+    const summary = document.getElementById('forksSummary');
+    list.innerHTML = '';
+    summary.textContent = 'Loading forks from GitHub...';
+    document.getElementById('forksModal').classList.add('active');
+    let result;
+    try {
+        result = await invoke('fetch_forks');
+    } catch (e) {
+        summary.textContent = `Couldn't list forks: ${e}`;
+        return;
+    }
+    summary.textContent = `${result.forks.length} forks, most recently updated first` +
+        (result.complete ? '' : ` - list cut short: ${result.error}`);
+    list.innerHTML = result.forks.map((fork, i) => `
+        <li class="fork-item" data-index="${i}">
+            <span>${fork.owner}</span>
+            <span class="fork-meta">★ ${fork.stars} · ${fork.defaultBranch} · pushed ${fork.pushedAt ? new Date(fork.pushedAt).toLocaleDateString() : 'never'}</span>
+        </li>
+    `).join('');
+    list.querySelectorAll('.fork-item').forEach(item => {
+        item.onclick = () => selectFork(result.forks[item.dataset.index]);
+    });
+}
+
+async function selectFork(fork) {
+    try {
+        const name = await invoke('add_fork_remote', { owner: fork.owner, url: fork.url });
+        // Keep the remote when the settings dialog saves next
+        settings = { ...settings, ...await invoke('load_settings') };
+        document.getElementById('forksModal').classList.remove('active');
+        currentRemote = name;
+        await setupRemotes();
+        await fetchBranches();
+        logToConsole(`Showing branches from ${fork.owner}'s fork`, 'info');
+    } catch (e) {
+        logToConsole(`Couldn't add fork ${fork.owner}: ${e}`, 'error');
+    }
+}
+
+async function runCompare() {
+    const base = document.getElementById('compareBase').value;
+    const other = document.getElementById('compareOther').value;
+    const summary = document.getElementById('compareSummary');
+    const fill = (id, files) => {
+        document.getElementById(id).innerHTML = files.length
+            ? files.map(f => `<li>${f}</li>`).join('')
+            : '<li class="compare-empty">Nothing</li>';
+    };
+    summary.textContent = `Fetching ${base} and ${other}...`;
+    try {
+        const result = await invoke('compare_branch_trees', { base, other, remote: currentRemote });
+        document.getElementById('compareBaseTitle').textContent = `Only in ${base}`;
+        document.getElementById('compareOtherTitle').textContent = `Only in ${other}`;
+        fill('compareOnlyBase', result.onlyInBase);
+        fill('compareOnlyOther', result.onlyInOther);
+        summary.textContent = `${result.common.length} files in common`;
+    } catch (e) {
+        summary.textContent = `Comparison failed: ${e}`;
+        logToConsole(`Branch comparison failed: ${e}`, 'error');
+    }
+}
+
+// Draft a GitHub issue with diagnostics; the backend opens it in the browser
+async function reportIssue() {
+    try {
+        const report = await invoke('prepare_issue_report', { errorCode: null });
+        if (report.truncated) {
+            await navigator.clipboard?.writeText(report.body).catch(() => {});
+            logToConsole(`The report was too long for the link - the full text is in your clipboard and at ${report.path}`, 'warning');
+        } else {
+            logToConsole('Opened a prefilled issue in your browser', 'info');
+        }
+    } catch (e) {
+        logToConsole(`Couldn't prepare the report: ${e}`, 'error');
+    }
+}
+
 // Modal Helpers
 function showConfirmModal(title, message, onConfirm) {
     document.getElementById('modalTitle').textContent = title;
@@ -300,11 +1590,33 @@ function hideConfirmModal() {
 async function saveSettings() {
     settings.blenderVersion = document.getElementById('blenderVersionSelect').value;
     settings.customPath = document.getElementById('customPath').value;
+    settings.githubToken = document.getElementById('githubToken').value.trim();
+    settings.gitPath = document.getElementById('gitPath').value.trim();
     settings.autoBackup = document.getElementById('autoBackup').checked;
+    settings.minimizeToTray = document.getElementById('minimizeToTray').checked;
+    settings.notifications = document.getElementById('notifications').checked;
+    settings.launchOnStartup = document.getElementById('launchOnStartup').checked;
+    settings.newBranchNotifications = document.getElementById('newBranchNotifications').checked;
+    settings.fixPermissions = document.getElementById('fixPermissions').checked;
+    settings.checkOnLaunch = document.getElementById('checkOnLaunch').checked;
+    settings.appUpdateCheck = document.getElementById('appUpdateCheck').checked;
+    settings.includePrereleases = document.getElementById('includePrereleases').checked;
+    settings.selfUpdate = document.getElementById('selfUpdate').checked;
+    settings.verifyAfterUpdate = document.getElementById('verifyAfterUpdate').checked;
+    settings.clearPycache = document.getElementById('clearPycache').checked;
+    settings.minimalCloneFallback = document.getElementById('minimalCloneFallback').checked;
+    settings.mirrorCache = document.getElementById('mirrorCache').checked;
+    settings.recreateSymlinks = document.getElementById('recreateSymlinks').checked;
+    settings.cloudPlaceholders = document.getElementById('cloudPlaceholders').value;
+    settings.preferredProtocol = document.getElementById('preferredProtocol').value;
+    const depth = document.getElementById('cloneDepth').value.trim();
+    settings.cloneDepth = depth === '' ? null : parseInt(depth, 10);
     try {
-        await invoke('save_settings', { settings });
+        // What was persisted - a save made at the same moment elsewhere may have won
+        settings = { ...settings, ...await invoke('save_settings', { settings }) };
         logToConsole('Settings saved', 'success');
         elements.settingsModal.classList.remove('active');
+        await setupAddons();
         await checkInstallation();
     } catch (e) {
         logToConsole(`Failed to save settings: ${e}`, 'error');
@@ -313,16 +1625,52 @@ async function saveSettings() {
 
 // Event Listeners
 function setupEventListeners() {
+    // The backend's background git and network work waits for this when launch checks are off
+    if (invoke) {
+        const active = () => invoke('user_active').catch(() => {});
+        document.addEventListener('pointerdown', active, { once: true, capture: true });
+        document.addEventListener('keydown', active, { once: true, capture: true });
+    }
     document.getElementById('refreshBtn').onclick = fetchBranches;
-    document.getElementById('settingsBtn').onclick = () => elements.settingsModal.classList.add('active');
+    document.getElementById('settingsBtn').onclick = () => {
+        elements.settingsModal.classList.add('active');
+        loadRestorePoints();
+        loadProfiles();
+    };
+    document.getElementById('compareBtn').onclick = openCompareModal;
+    document.getElementById('compareRun').onclick = runCompare;
+    document.getElementById('compareClose').onclick = () => document.getElementById('compareModal').classList.remove('active');
+    document.getElementById('previewBtn').onclick = openPreviewModal;
+    document.getElementById('previewRun').onclick = runPreview;
+    document.getElementById('previewClose').onclick = () => document.getElementById('previewModal').classList.remove('active');
+    document.getElementById('forksBtn').onclick = openForksModal;
+    document.getElementById('forksClose').onclick = () => document.getElementById('forksModal').classList.remove('active');
     document.getElementById('installBtn')?.addEventListener('click', installSerpens);
+    document.getElementById('installExtensionBtn').onclick = installAsExtension;
     document.getElementById('openFolderBtn').onclick = openFolder;
+    document.getElementById('uninstallBtn').onclick = uninstallAddon;
+    document.getElementById('fixStuckUpdateBtn').onclick = fixStuckUpdate;
     document.getElementById('branchSearch').oninput = (e) => filterBranches(e.target.value);
     document.getElementById('modalCancel').onclick = hideConfirmModal;
     document.getElementById('modalClose').onclick = hideConfirmModal;
     document.getElementById('settingsClose').onclick = () => elements.settingsModal.classList.remove('active');
     document.getElementById('settingsCancel').onclick = () => elements.settingsModal.classList.remove('active');
     document.getElementById('settingsSave').onclick = saveSettings;
+    document.getElementById('reportIssueBtn').onclick = reportIssue;
+    document.getElementById('cleanupTempBtn').onclick = cleanupTempDirs;
+    document.getElementById('relocateDataBtn').onclick = relocateDataDir;
+    document.getElementById('clearMirrorBtn').onclick = clearMirrorCache;
+    document.getElementById('clearPycacheBtn').onclick = clearPycache;
+    document.getElementById('checkKnownGoodBtn').onclick = checkKnownGood;
+    document.getElementById('tagStateBtn').onclick = tagState;
+    document.getElementById('addAddonBtn').onclick = addAddon;
+    document.getElementById('saveProfileBtn').onclick = saveProfile;
+    document.getElementById('applyProfileBtn').onclick = applyProfile;
+    document.getElementById('deleteProfileBtn').onclick = deleteProfile;
+    document.getElementById('removeAddonBtn').onclick = removeAddon;
+    document.getElementById('syncOverviewBtn').onclick = showSyncOverview;
+    document.getElementById('restoreStateBtn').onclick = restoreState;
+    document.getElementById('deleteStateBtn').onclick = deleteState;
     document.getElementById('clearConsoleBtn').onclick = () => { elements.consoleContent.innerHTML = ''; };
     document.getElementById('toggleConsoleBtn').onclick = () => {
         document.getElementById('consolePanel').classList.toggle('collapsed');
@@ -1,3 +1,58 @@
+use std::path::Path;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
 fn main() {
+    build_info();
     tauri_build::build()
 }
+
+/// What app_info reports about this build, passed to the compiler as SERPENS_* variables
+fn build_info() {
+    let commit = git(&["rev-parse", "HEAD"]).map(|commit| {
+        let dirty = git(&["status", "--porcelain", "--untracked-files=no"]).is_some_and(|s| !s.is_empty());
+        if dirty {
+            format!("{}-dirty", commit)
+        } else {
+            commit
+        }
+    });
+    println!("cargo:rustc-env=SERPENS_GIT_COMMIT={}", commit.unwrap_or_default());
+
+    // Reproducible builds pin the time
+    let built = std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or_else(|| SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0));
+    println!("cargo:rustc-env=SERPENS_BUILD_TIME={}", built);
+    println!("cargo:rustc-env=SERPENS_TARGET={}", std::env::var("TARGET").unwrap_or_default());
+    println!("cargo:rustc-env=SERPENS_PROFILE={}", std::env::var("PROFILE").unwrap_or_default());
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let rustc_version = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string());
+    println!("cargo:rustc-env=SERPENS_RUSTC={}", rustc_version.unwrap_or_default());
+
+    // A new commit, checkout or edit should refresh the commit and dirty flag
+    if let Some(git_dir) = git(&["rev-parse", "--absolute-git-dir"]) {
+        let git_dir = Path::new(&git_dir);
+        println!("cargo:rerun-if-changed={}", git_dir.join("HEAD").display());
+        println!("cargo:rerun-if-changed={}", git_dir.join("index").display());
+        if let Ok(head) = std::fs::read_to_string(git_dir.join("HEAD")) {
+            if let Some(reference) = head.trim().strip_prefix("ref: ") {
+                println!("cargo:rerun-if-changed={}", git_dir.join(reference).display());
+            }
+        }
+    }
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+}
+
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !text.is_empty()).then_some(text)
+}
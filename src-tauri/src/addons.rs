@@ -0,0 +1,161 @@
+// The addons the manager looks after. Serpens is built in; settings can add others, each
+// with its own folder and repo. A command resolves the addon it was asked about and
+// hands it to everything that depends on it - install paths, the repo URL, backup and
+// journal locations. A command given no addon works on Serpens exactly as before.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::spec::parse_repo;
+use crate::{data_dir, giturl, load_settings, ADDON_FOLDER_NAME, ORIGINAL_BACKUP_FOLDER, REPO_URL, USER_DATA_PRESERVE};
+
+/// The built-in Serpens entry, always available under this id
+pub const DEFAULT_ADDON: &str = "serpens";
+
+/// An addon as stored in settings
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Addon {
+    pub id: String,
+    /// The folder it's installed as in the addons dir, which is also its Python module name
+    #[serde(rename = "folderName")]
+    pub folder_name: String,
+    /// https or SSH URL, or GitHub "owner/name" to follow the preferred protocol
+    #[serde(rename = "repoUrl")]
+    pub repo_url: String,
+    #[serde(rename = "displayName")]
+    pub display_name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AddonInfo {
+    pub id: String,
+    #[serde(rename = "folderName")]
+    pub folder_name: String,
+    #[serde(rename = "repoUrl")]
+    pub repo_url: String,
+    #[serde(rename = "displayName")]
+    pub display_name: String,
+    /// Serpens itself rather than one from settings
+    pub builtin: bool,
+}
+
+impl Addon {
+    pub fn builtin() -> Addon {
+        Addon {
+            id: DEFAULT_ADDON.to_string(),
+            folder_name: ADDON_FOLDER_NAME.to_string(),
+            repo_url: REPO_URL.to_string(),
+            display_name: "Serpens".to_string(),
+        }
+    }
+
+    pub fn is_builtin(&self) -> bool {
+        self.id == DEFAULT_ADDON
+    }
+
+    /// The repo to clone, in the preferred form when the manager built the URL itself
+    pub fn url(&self) -> String {
+        if self.is_builtin() {
+            return giturl::preferred(REPO_URL);
+        }
+        parse_repo(&self.repo_url).unwrap_or_else(|_| self.repo_url.clone())
+    }
+
+    /// Top-level folders that hold the user's own data. Only Serpens is known to have
+    /// any; another addon's folders are all its repo's.
+    pub fn preserved(&self) -> &'static [&'static str] {
+        if self.is_builtin() {
+            USER_DATA_PRESERVE
+        } else {
+            &[]
+        }
+    }
+
+    /// A name for something the manager keeps beside the addon in the addons dir: `name`
+    /// as it always was for Serpens, suffixed with the addon's id for the rest
+    pub fn beside(&self, name: &str) -> String {
+        if self.is_builtin() {
+            name.to_string()
+        } else {
+            format!("{}_{}", name, self.id)
+        }
+    }
+
+    /// The single "Backup Your Serpens" copy, next to the install
+    pub fn original_backup_folder(&self) -> String {
+        self.beside(ORIGINAL_BACKUP_FOLDER)
+    }
+
+    /// Where the addon's own records live: the data folder itself for Serpens, so nothing
+    /// moves, and a folder per addon under it for the rest
+    pub fn data_root(&self) -> Result<PathBuf, String> {
+        if self.is_builtin() {
+            return data_dir();
+        }
+        let root = data_dir()?.join("addons").join(&self.id);
+        fs::create_dir_all(&root)
+            .map_err(|e| format!("Failed to create the data folder for {}: {}", self.display_name, e))?;
+        Ok(root)
+    }
+}
+
+/// The addon with `addon_id`; None or an empty id is Serpens
+pub fn resolve(addon_id: Option<&str>) -> Result<Addon, String> {
+    match addon_id {
+        None | Some(DEFAULT_ADDON) | Some("") => Ok(Addon::builtin()),
+        Some(id) => load_settings()?
+            .addons
+            .into_iter()
+            .find(|a| a.id == id)
+            .ok_or_else(|| format!("No addon '{}' - add it in settings first", id)),
+    }
+}
+
+#[tauri::command]
+pub fn list_addons() -> Result<Vec<AddonInfo>, String> {
+    let addons = std::iter::once(Addon::builtin()).chain(load_settings()?.addons);
+    Ok(addons
+        .map(|addon| AddonInfo {
+            builtin: addon.is_builtin(),
+            id: addon.id,
+            folder_name: addon.folder_name,
+            repo_url: addon.repo_url,
+            display_name: addon.display_name,
+        })
+        .collect())
+}
+
+/// Ids double as folder names in the data folder; folder names must be one Python
+/// module name each, and no two addons may share a folder
+pub fn validate_addons(addons: &[Addon]) -> Result<(), String> {
+    for (i, addon) in addons.iter().enumerate() {
+        let id = addon.id.as_str();
+        let id_ok = id.len() <= 64
+            && id.starts_with(|c: char| c.is_ascii_alphabetic())
+            && id.chars().all(|c| c.is_ascii_alphanumeric() || "-_".contains(c));
+        if !id_ok {
+            return Err(format!("Invalid addon id '{}': start with a letter, then letters, digits, '-' or '_'", id));
+        }
+        if id == DEFAULT_ADDON {
+            return Err(format!("'{}' is the built-in Serpens entry - pick another id", id));
+        }
+        let folder = addon.folder_name.as_str();
+        let folder_ok = folder.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            && folder.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !folder_ok {
+            return Err(format!("Addon '{}': '{}' isn't a valid Python module name for its folder", id, folder));
+        }
+        if folder.eq_ignore_ascii_case(ADDON_FOLDER_NAME) {
+            return Err(format!("Addon '{}': {} is Serpens' own folder", id, folder));
+        }
+        if addons[..i].iter().any(|a| a.id == id) {
+            return Err(format!("Addon '{}' is listed twice", id));
+        }
+        if let Some(other) = addons[..i].iter().find(|a| a.folder_name.eq_ignore_ascii_case(folder)) {
+            return Err(format!("Addons '{}' and '{}' would both install as {}", other.id, id, folder));
+        }
+        parse_repo(&addon.repo_url).map_err(|e| format!("Addon '{}': {}", id, e))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,62 @@
+// Exactly which build is running, for support - stamped in by build.rs
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AppInfo {
+    pub version: String,
+    #[serde(rename = "tauriVersion")]
+    pub tauri_version: String,
+    /// When the build script last ran, RFC 3339
+    #[serde(rename = "builtAt")]
+    pub built_at: Option<String>,
+    /// The commit built from, with "-dirty" for uncommitted changes; None outside a checkout
+    #[serde(rename = "gitCommit")]
+    pub git_commit: Option<String>,
+    /// Target triple, e.g. "x86_64-pc-windows-msvc"
+    pub target: String,
+    /// "debug" or "release"
+    pub profile: String,
+    pub rustc: Option<String>,
+}
+
+#[tauri::command]
+pub fn app_info() -> AppInfo {
+    info()
+}
+
+pub fn info() -> AppInfo {
+    let non_empty = |value: &str| (!value.is_empty()).then(|| value.to_string());
+    AppInfo {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        tauri_version: tauri::VERSION.to_string(),
+        built_at: env!("SERPENS_BUILD_TIME")
+            .parse::<i64>()
+            .ok()
+            .filter(|secs| *secs > 0)
+            .and_then(|secs| chrono::DateTime::from_timestamp(secs, 0))
+            .map(|time| time.to_rfc3339()),
+        git_commit: non_empty(env!("SERPENS_GIT_COMMIT")),
+        target: env!("SERPENS_TARGET").to_string(),
+        profile: env!("SERPENS_PROFILE").to_string(),
+        rustc: non_empty(env!("SERPENS_RUSTC")),
+    }
+}
+
+/// "1.0.0 (abc1234, x86_64-pc-windows-msvc, release, built 2026-01-02T03:04:05+00:00)"
+pub fn summary() -> String {
+    let info = info();
+    let mut details = Vec::new();
+    if let Some(commit) = &info.git_commit {
+        let dirty = commit.strip_suffix("-dirty");
+        let hash = dirty.unwrap_or(commit);
+        let short = hash.get(..12).unwrap_or(hash);
+        details.push(if dirty.is_some() { format!("{}-dirty", short) } else { short.to_string() });
+    }
+    details.push(info.target.clone());
+    details.push(info.profile.clone());
+    if let Some(built) = &info.built_at {
+        details.push(format!("built {}", built));
+    }
+    format!("{} ({})", info.version, details.join(", "))
+}
@@ -0,0 +1,36 @@
+// The manager's own log file, for things worth knowing about after the fact
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::data_dir;
+
+/// Start over in a fresh file once the log reaches this size, keeping one old copy
+const MAX_LOG_BYTES: u64 = 1024 * 1024;
+
+pub fn log_path() -> Result<PathBuf, String> {
+    Ok(data_dir()?.join("manager.log"))
+}
+
+pub fn info(message: &str) {
+    write("INFO", message);
+}
+
+pub fn warn(message: &str) {
+    write("WARN", message);
+}
+
+/// Best effort - logging must never be the reason an operation fails
+fn write(level: &str, message: &str) {
+    let Ok(path) = log_path() else { return };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).ok();
+    }
+    if fs::metadata(&path).map(|m| m.len() > MAX_LOG_BYTES).unwrap_or(false) {
+        fs::rename(&path, path.with_extension("log.1")).ok();
+    }
+    if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(&path) {
+        writeln!(file, "{} [{}] {}", chrono::Local::now().to_rfc3339(), level, message).ok();
+    }
+}
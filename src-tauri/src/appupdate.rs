@@ -0,0 +1,280 @@
+// Checking GitHub for a newer release of the manager itself
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::notices::{self, Notice};
+use crate::updates::BackgroundGate;
+use crate::{data_dir, load_settings, notify};
+
+pub const DEFAULT_MANAGER_REPO: &str = "CoreyCorza/serpens_dev_manager";
+
+const HTTP_TIMEOUT: Duration = Duration::from_secs(15);
+/// How often the background check actually asks GitHub
+const CHECK_EVERY: chrono::TimeDelta = chrono::TimeDelta::hours(24);
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ManagerUpdate {
+    #[serde(rename = "currentVersion")]
+    pub current_version: String,
+    #[serde(rename = "latestVersion")]
+    pub latest_version: String,
+    #[serde(rename = "updateAvailable")]
+    pub update_available: bool,
+    /// Installer for this platform, or the release page when there's no matching asset
+    #[serde(rename = "downloadUrl")]
+    pub download_url: String,
+    #[serde(rename = "releaseUrl")]
+    pub release_url: String,
+    /// Markdown body of the release
+    #[serde(rename = "releaseNotes", default)]
+    pub release_notes: Option<String>,
+    #[serde(default)]
+    pub prerelease: bool,
+    /// File name of the installer behind `downloadUrl`, when there is one
+    #[serde(rename = "assetName", default)]
+    pub asset_name: Option<String>,
+    /// Published SHA-256 for the installer ("<asset>.sha256" or a SHA256SUMS file)
+    #[serde(rename = "checksumUrl", default)]
+    pub checksum_url: Option<String>,
+    #[serde(rename = "checkedAt")]
+    pub checked_at: String,
+    /// True when GitHub couldn't be reached and this is the last successful result
+    #[serde(default)]
+    pub cached: bool,
+}
+
+#[derive(Deserialize)]
+struct Release {
+    tag_name: String,
+    html_url: String,
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    prerelease: bool,
+    #[serde(default)]
+    assets: Vec<Asset>,
+}
+
+#[derive(Deserialize)]
+struct Asset {
+    name: String,
+    browser_download_url: String,
+}
+
+fn cache_path() -> Result<PathBuf, String> {
+    Ok(data_dir()?.join("manager_update.json"))
+}
+
+/// The manager's GitHub repo as "owner/name", overridable in settings for forks
+pub fn manager_repo() -> String {
+    load_settings()
+        .map(|s| s.manager_repo)
+        .ok()
+        .map(|r| r.trim().trim_matches('/').to_string())
+        .filter(|r| r.contains('/'))
+        .unwrap_or_else(|| DEFAULT_MANAGER_REPO.to_string())
+}
+
+/// The version of this build
+fn current_version() -> &'static str {
+    env!("CARGO_PKG_VERSION")
+}
+
+#[tauri::command]
+pub async fn check_app_update() -> Result<ManagerUpdate, String> {
+    tokio::task::spawn_blocking(check_now).await.map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// The name check_app_update had before it was renamed - deprecated, kept so scripts and
+/// older frontends invoking it keep working
+#[tauri::command]
+pub async fn check_manager_update() -> Result<ManagerUpdate, String> {
+    check_app_update().await
+}
+
+pub fn check_now() -> Result<ManagerUpdate, String> {
+    let current = current_version();
+    match fetch_latest(current) {
+        Ok(update) => {
+            if let (Ok(path), Ok(content)) = (cache_path(), serde_json::to_string_pretty(&update)) {
+                fs::write(path, content).ok();
+            }
+            Ok(update)
+        }
+        Err(e) => {
+            // Offline or rate limited: fall back to what we learned last time, re-judged
+            // against this build
+            let cached = load_cached().ok_or(e)?;
+            Ok(ManagerUpdate {
+                update_available: is_newer(&cached.latest_version, current),
+                current_version: current.to_string(),
+                cached: true,
+                ..cached
+            })
+        }
+    }
+}
+
+fn load_cached() -> Option<ManagerUpdate> {
+    let content = fs::read_to_string(cache_path().ok()?).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn github_get(url: &str) -> Result<String, String> {
+    github_api(url, "No releases published yet")
+}
+
+/// GET from the GitHub API with readable errors; `not_found` explains a 404. Sends the
+/// configured token, if any, for the higher rate limit and private forks.
+pub fn github_api(url: &str, not_found: &str) -> Result<String, String> {
+    let mut request = ureq::get(url)
+        .set("User-Agent", "serpens-dev-manager")
+        .set("Accept", "application/vnd.github+json")
+        .timeout(HTTP_TIMEOUT);
+    let token = load_settings().map(|s| s.github_token).unwrap_or_default();
+    if !token.trim().is_empty() {
+        request = request.set("Authorization", &format!("Bearer {}", token.trim()));
+    }
+    request
+        .call()
+        .map_err(|e| match e {
+            ureq::Error::Status(404, _) => not_found.to_string(),
+            ureq::Error::Status(403 | 429, response) if response.header("x-ratelimit-remaining") == Some("0") => {
+                let reset = response
+                    .header("x-ratelimit-reset")
+                    .and_then(|t| t.parse::<i64>().ok())
+                    .and_then(|t| chrono::DateTime::from_timestamp(t, 0))
+                    .map(|t| format!(" until {}", t.with_timezone(&chrono::Local).format("%H:%M")));
+                format!("GitHub rate limit reached{}", reset.unwrap_or_default())
+            }
+            ureq::Error::Status(code, _) => format!("GitHub returned HTTP {}", code),
+            ureq::Error::Transport(t) => format!("Couldn't reach GitHub: {}", t),
+        })?
+        .into_string()
+        .map_err(|e| format!("Failed to read response: {}", e))
+}
+
+/// The newest release, counting pre-releases only when the user opted in.
+/// GitHub's /releases/latest already skips pre-releases and drafts.
+fn newest_release() -> Result<Release, String> {
+    let repo = manager_repo();
+    if !load_settings().map(|s| s.include_prereleases).unwrap_or(false) {
+        let body = github_get(&format!("https://api.github.com/repos/{}/releases/latest", repo))?;
+        return serde_json::from_str(&body).map_err(|e| format!("Unexpected release data: {}", e));
+    }
+
+    let body = github_get(&format!("https://api.github.com/repos/{}/releases?per_page=30", repo))?;
+    let releases: Vec<Release> = serde_json::from_str(&body).map_err(|e| format!("Unexpected release data: {}", e))?;
+    releases
+        .into_iter()
+        .filter(|r| !r.draft)
+        .filter_map(|r| semver::Version::parse(r.tag_name.trim_start_matches('v')).ok().map(|v| (v, r)))
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, r)| r)
+        .ok_or_else(|| "No releases published yet".to_string())
+}
+
+fn fetch_latest(current: &str) -> Result<ManagerUpdate, String> {
+    let release = newest_release()?;
+    let latest = release.tag_name.trim_start_matches('v').to_string();
+    let asset = platform_asset(&release.assets);
+    let checksum_url = asset.and_then(|a| checksum_asset(&release.assets, &a.name));
+    let download_url = asset.map(|a| a.browser_download_url.clone()).unwrap_or_else(|| release.html_url.clone());
+    Ok(ManagerUpdate {
+        update_available: is_newer(&latest, current),
+        current_version: current.to_string(),
+        latest_version: latest,
+        download_url,
+        release_url: release.html_url,
+        release_notes: release.body.filter(|b| !b.trim().is_empty()),
+        prerelease: release.prerelease,
+        asset_name: asset.map(|a| a.name.clone()),
+        checksum_url,
+        checked_at: chrono::Local::now().to_rfc3339(),
+        cached: false,
+    })
+}
+
+/// Ask GitHub at most once a day (the last answer is cached on disk, so restarts don't
+/// reset the clock) and announce a newer build when there is one.
+pub fn spawn_app_update_checker(app: AppHandle) {
+    thread::spawn(move || {
+        // Without checkOnLaunch the first look waits for the user, then its full hour
+        let on_launch = load_settings().map(|s| s.check_on_launch).unwrap_or(true);
+        if !on_launch {
+            app.state::<BackgroundGate>().wait();
+        }
+        thread::sleep(Duration::from_secs(if on_launch { 30 } else { 60 * 60 }));
+        loop {
+            if load_settings().map(|s| s.app_update_check).unwrap_or(true) && check_due() {
+                match check_now() {
+                    Ok(update) if update.update_available && !update.cached => announce(&app, &update),
+                    _ => {}
+                }
+            }
+            thread::sleep(Duration::from_secs(60 * 60));
+        }
+    });
+}
+
+fn check_due() -> bool {
+    load_cached()
+        .and_then(|c| chrono::DateTime::parse_from_rfc3339(&c.checked_at).ok())
+        .is_none_or(|at| chrono::Local::now().fixed_offset() - at >= CHECK_EVERY)
+}
+
+fn announce(app: &AppHandle, update: &ManagerUpdate) {
+    app.emit("serpens://app-update", update).ok();
+    let message =
+        format!("Serpens Dev Manager {} is available (you have {})", update.latest_version, update.current_version);
+    notify::show(app, "Manager update available", &message);
+    notices::emit(
+        app,
+        Notice {
+            level: "info".to_string(),
+            code: format!("app_update_{}", update.latest_version),
+            message,
+            action: Some("app_update".to_string()),
+        },
+    );
+}
+
+/// Whether `latest` is a higher version than `current`. Tags that aren't semver never are.
+pub fn is_newer(latest: &str, current: &str) -> bool {
+    match (semver::Version::parse(latest), semver::Version::parse(current)) {
+        (Ok(latest), Ok(current)) => latest > current,
+        _ => false,
+    }
+}
+
+/// The installer a user on this OS would want
+fn platform_asset(assets: &[Asset]) -> Option<&Asset> {
+    let wanted: &[&str] = if cfg!(windows) {
+        &["-setup.exe", ".msi"]
+    } else if cfg!(target_os = "macos") {
+        &[".dmg"]
+    } else {
+        &[".AppImage", ".deb"]
+    };
+    wanted.iter().find_map(|suffix| assets.iter().find(|a| a.name.ends_with(suffix)))
+}
+
+fn checksum_asset(assets: &[Asset], installer: &str) -> Option<String> {
+    let own = format!("{}.sha256", installer);
+    assets
+        .iter()
+        .find(|a| a.name == own)
+        .or_else(|| {
+            assets
+                .iter()
+                .find(|a| ["SHA256SUMS", "SHA256SUMS.txt", "checksums.txt"].contains(&a.name.as_str()))
+        })
+        .map(|a| a.browser_download_url.clone())
+}
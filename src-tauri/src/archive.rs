@@ -0,0 +1,407 @@
+// Packaging the installed addon into distributable archives
+
+use chrono::{Datelike, Timelike};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tauri::State;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::addons::Addon;
+use crate::blender::{find_blender_executables, version_key};
+use crate::ops::OperationState;
+use crate::{addon_dir, addons, applog, backups, fingerprint, git_output, hidden_command, marker};
+
+const EXTENSION_MANIFEST: &str = "blender_manifest.toml";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub path: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AddonZip {
+    pub path: String,
+    pub files: Vec<ManifestEntry>,
+    #[serde(rename = "totalSize")]
+    pub total_size: u64,
+    #[serde(rename = "archiveSize")]
+    pub archive_size: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExtensionPackage {
+    pub path: String,
+    /// "blender" when built by `blender --command extension build`, "direct" otherwise
+    #[serde(rename = "builtWith")]
+    pub built_with: String,
+}
+
+#[tauri::command]
+pub async fn build_addon_zip(
+    blender_version: String,
+    dest_path: String,
+    addon_id: Option<String>,
+    ops: State<'_, OperationState>,
+) -> Result<AddonZip, String> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    let guard = ops.begin("build_addon_zip", &blender_version)?;
+    tokio::task::spawn_blocking(move || {
+        let result = build_zip(&addon, &blender_version, &dest_path);
+        guard.finish(&result);
+        result
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+fn build_zip(addon: &Addon, blender_version: &str, dest_path: &str) -> Result<AddonZip, String> {
+    let addon_path = addon_dir(addon, blender_version)?;
+    if !addon_path.join("__init__.py").exists() {
+        return Err("No installation found".to_string());
+    }
+
+    let version = read_bl_info_version(&addon_path).unwrap_or_else(|| "unknown".to_string());
+    let sha = git_output(&addon_path, &["rev-parse", "--short", "HEAD"]).unwrap_or_else(|_| "local".to_string());
+
+    let dest_dir = PathBuf::from(dest_path);
+    fs::create_dir_all(&dest_dir).map_err(|e| format!("Failed to create destination: {}", e))?;
+    let zip_path = dest_dir.join(format!("{}-{}-{}.zip", addon.folder_name, version, sha));
+
+    let manifest = write_addon_zip(addon, &addon_path, &zip_path)?;
+    let total_size = manifest.iter().map(|e| e.size).sum();
+    let archive_size = fs::metadata(&zip_path).map(|m| m.len()).unwrap_or(0);
+
+    Ok(AddonZip {
+        path: zip_path.to_string_lossy().to_string(),
+        files: manifest,
+        total_size,
+        archive_size,
+    })
+}
+
+#[tauri::command]
+pub async fn build_extension(
+    blender_version: String,
+    dest_path: String,
+    addon_id: Option<String>,
+    ops: State<'_, OperationState>,
+) -> Result<ExtensionPackage, String> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    let guard = ops.begin("build_extension", &blender_version)?;
+    tokio::task::spawn_blocking(move || {
+        let result = build_extension_package(&addon, &blender_version, &dest_path);
+        guard.finish(&result);
+        result
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+fn build_extension_package(addon: &Addon, blender_version: &str, dest_path: &str) -> Result<ExtensionPackage, String> {
+    let addon_path = addon_dir(addon, blender_version)?;
+    if !addon_path.exists() {
+        return Err("No installation found".to_string());
+    }
+    let manifest = fs::read_to_string(addon_path.join(EXTENSION_MANIFEST))
+        .map_err(|_| format!("This branch doesn't support extensions (no {} found)", EXTENSION_MANIFEST))?;
+    let id = manifest_value(&manifest, "id").ok_or_else(|| format!("{} has no id", EXTENSION_MANIFEST))?;
+    let version =
+        manifest_value(&manifest, "version").ok_or_else(|| format!("{} has no version", EXTENSION_MANIFEST))?;
+
+    let dest_dir = PathBuf::from(dest_path);
+    fs::create_dir_all(&dest_dir).map_err(|e| format!("Failed to create destination: {}", e))?;
+    let zip_path = dest_dir.join(format!("{}-{}.zip", id, version));
+
+    let built_with = match extension_capable_blender(blender_version) {
+        Some(blender) => {
+            let output = hidden_command(&blender)
+                .args(["--command", "extension", "build", "--source-dir"])
+                .arg(&addon_path)
+                .arg("--output-filepath")
+                .arg(&zip_path)
+                .output()
+                .map_err(|e| format!("Failed to run Blender: {}", e))?;
+            if !output.status.success() {
+                return Err(format!(
+                    "Blender extension build failed:\n{}{}",
+                    String::from_utf8_lossy(&output.stdout),
+                    String::from_utf8_lossy(&output.stderr)
+                ));
+            }
+            "blender"
+        }
+        None => {
+            // Extensions are zipped flat - the manifest sits at the archive root
+            let files =
+                collect_addon_files(addon, &addon_path).map_err(|e| format!("Failed to read installation: {}", e))?;
+            let entries: Vec<(String, PathBuf)> =
+                files.iter().map(|rel| (zip_name(rel), addon_path.join(rel))).collect();
+            write_zip(&zip_path, &entries).map_err(|e| format!("Failed to write zip: {}", e))?;
+            "direct"
+        }
+    };
+
+    if let Err(e) = verify_extension_layout(&zip_path) {
+        fs::remove_file(&zip_path).ok();
+        return Err(e);
+    }
+
+    Ok(ExtensionPackage {
+        path: zip_path.to_string_lossy().to_string(),
+        built_with: built_with.to_string(),
+    })
+}
+
+/// A Blender new enough for `--command extension` (4.2+), preferring the one matching the target version.
+fn extension_capable_blender(blender_version: &str) -> Option<PathBuf> {
+    let capable: Vec<_> = find_blender_executables()
+        .into_iter()
+        .filter(|b| version_key(&b.version) >= vec![4, 2])
+        .collect();
+    capable
+        .iter()
+        .find(|b| b.version == blender_version)
+        .or(capable.first())
+        .map(|b| PathBuf::from(&b.executable_path))
+}
+
+/// Read a top-level `key = "value"` from a TOML manifest (ignoring any [tables]).
+fn manifest_value(manifest: &str, key: &str) -> Option<String> {
+    for line in manifest.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            break;
+        }
+        let Some((k, v)) = line.split_once('=') else { continue };
+        if k.trim() == key {
+            let v = v.split('#').next().unwrap_or("").trim();
+            return Some(v.trim_matches(|c| c == '"' || c == '\'').to_string());
+        }
+    }
+    None
+}
+
+/// Zip the install at `addon_path` as Blender's "Install Add-on" expects it, removing
+/// the zip again if the layout came out wrong
+fn write_addon_zip(addon: &Addon, addon_path: &Path, zip_path: &Path) -> Result<Vec<ManifestEntry>, String> {
+    let files = collect_addon_files(addon, addon_path).map_err(|e| format!("Failed to read installation: {}", e))?;
+
+    // Blender's "Install Add-on" extracts the zip into the addons folder as-is,
+    // so everything must live under a single scripting_nodes/ folder.
+    let entries: Vec<(String, PathBuf)> = files
+        .iter()
+        .map(|rel| (format!("{}/{}", addon.folder_name, zip_name(rel)), addon_path.join(rel)))
+        .collect();
+    write_zip(zip_path, &entries).map_err(|e| format!("Failed to write zip: {}", e))?;
+
+    if let Err(e) = verify_addon_layout(addon, zip_path) {
+        fs::remove_file(zip_path).ok();
+        return Err(e);
+    }
+
+    Ok(files
+        .iter()
+        .map(|rel| ManifestEntry {
+            path: zip_name(rel),
+            size: fs::metadata(addon_path.join(rel)).map(|m| m.len()).unwrap_or(0),
+        })
+        .collect())
+}
+
+/// Pull the `"version": (3, 4, 1)` tuple out of bl_info as "3.4.1".
+pub fn read_bl_info_version(addon_path: &Path) -> Option<String> {
+    bl_info_version(&fs::read_to_string(addon_path.join("__init__.py")).ok()?)
+}
+
+/// The `bl_info["version"]` tuple from an `__init__.py`'s source, as "1.2.3"
+pub fn bl_info_version(source: &str) -> Option<String> {
+    let bl_info = &source[source.find("bl_info")?..];
+    let key = bl_info.find("\"version\"").or_else(|| bl_info.find("'version'"))?;
+    let after = &bl_info[key..];
+    let open = after.find('(')? + 1;
+    let tuple = &after[open..open + after[open..].find(')')?];
+    let parts: Vec<&str> = tuple.split(',').map(|p| p.trim()).filter(|p| !p.is_empty()).collect();
+    if parts.is_empty() || !parts.iter().all(|p| p.chars().all(|c| c.is_ascii_digit())) {
+        return None;
+    }
+    Some(parts.join("."))
+}
+
+/// What the manager writes into an install for itself; a zip carrying them would look
+/// like a manager install of whatever branch it was built from
+const MANAGER_FILES: &[&str] = &[marker::MARKER_FILE, fingerprint::MANIFEST_FILE, backups::META_FILE];
+
+/// Relative paths of everything worth shipping: no git metadata, bytecode, user data or
+/// the manager's own records.
+pub fn collect_addon_files(addon: &Addon, addon_path: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    collect_files(addon_path, Path::new(""), &mut files)?;
+    files.retain(|rel| {
+        let top = rel.components().next().map(|c| c.as_os_str().to_string_lossy().to_string());
+        !top.is_some_and(|t| addon.preserved().contains(&t.as_str()))
+    });
+    files.sort();
+    Ok(files)
+}
+
+fn collect_files(root: &Path, rel: &Path, out: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(root.join(rel))? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let manager_file = rel.as_os_str().is_empty() && MANAGER_FILES.iter().any(|f| name == *f);
+        if name == ".git" || name == "__pycache__" || manager_file {
+            continue;
+        }
+        let child = rel.join(&name);
+        if entry.file_type()?.is_dir() {
+            collect_files(root, &child, out)?;
+        } else {
+            out.push(child);
+        }
+    }
+    Ok(())
+}
+
+/// Zip entry names always use forward slashes, whatever the platform.
+pub fn zip_name(rel: &Path) -> String {
+    rel.components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Entries keep their files' modification times, and their modes on Unix
+pub fn write_zip(zip_path: &Path, entries: &[(String, PathBuf)]) -> io::Result<()> {
+    let mut writer = ZipWriter::new(File::create(zip_path)?);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut unkept = 0;
+    for (name, source) in entries {
+        let meta = fs::metadata(source)?;
+        let mut file_options = options;
+        match meta.modified().ok().and_then(zip_time) {
+            Some(time) => file_options = file_options.last_modified_time(time),
+            None => unkept += 1,
+        }
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            file_options = file_options.unix_permissions(meta.permissions().mode());
+        }
+        writer.start_file(name.as_str(), file_options)?;
+        io::copy(&mut File::open(source)?, &mut writer)?;
+    }
+    writer.finish()?;
+    if unkept > 0 {
+        applog::warn(&format!("{} files in {} got the zip's default timestamp", unkept, zip_path.display()));
+    }
+    Ok(())
+}
+
+/// Zip times are local and can't go before 1980
+fn zip_time(time: SystemTime) -> Option<zip::DateTime> {
+    let local: chrono::DateTime<chrono::Local> = time.into();
+    zip::DateTime::from_date_and_time(
+        u16::try_from(local.year()).ok()?,
+        local.month() as u8,
+        local.day() as u8,
+        local.hour() as u8,
+        local.minute() as u8,
+        local.second() as u8,
+    )
+    .ok()
+}
+
+/// Check the finished zip has exactly the layout Blender's installer expects:
+/// a single top-level scripting_nodes/ folder with __init__.py directly inside it.
+fn verify_addon_layout(addon: &Addon, zip_path: &Path) -> Result<(), String> {
+    let file = File::open(zip_path).map_err(|e| format!("Failed to reopen zip: {}", e))?;
+    let archive = ZipArchive::new(file).map_err(|e| format!("Written zip is unreadable: {}", e))?;
+
+    let prefix = format!("{}/", addon.folder_name);
+    if let Some(stray) = archive.file_names().find(|n| !n.starts_with(&prefix)) {
+        return Err(format!("Zip layout invalid: '{}' is outside {}", stray, prefix));
+    }
+    let init = format!("{}__init__.py", prefix);
+    if !archive.file_names().any(|n| n == init) {
+        return Err(format!("Zip layout invalid: {} is missing", init));
+    }
+    Ok(())
+}
+
+/// An extension zip must carry its manifest at the archive root.
+fn verify_extension_layout(zip_path: &Path) -> Result<(), String> {
+    let file = File::open(zip_path).map_err(|_| "Extension build produced no archive".to_string())?;
+    let archive = ZipArchive::new(file).map_err(|e| format!("Built extension is unreadable: {}", e))?;
+    if !archive.file_names().any(|n| n == EXTENSION_MANIFEST) {
+        return Err(format!("Extension layout invalid: {} is not at the archive root", EXTENSION_MANIFEST));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::scratch;
+
+    #[test]
+    fn addon_zip_is_one_folder_without_manager_files() {
+        let root = scratch("archive", "zip");
+        let addon_path = root.join("scripting_nodes");
+        for dir in ["nodes/__pycache__", ".git", "packages"] {
+            fs::create_dir_all(addon_path.join(dir)).unwrap();
+        }
+        fs::write(addon_path.join("__init__.py"), "bl_info = {\"version\": (1, 2, 3)}\n").unwrap();
+        fs::write(addon_path.join("nodes/node.py"), "").unwrap();
+        fs::write(addon_path.join("nodes/__pycache__/node.cpython-311.pyc"), "").unwrap();
+        fs::write(addon_path.join(".git/HEAD"), "ref: refs/heads/main\n").unwrap();
+        fs::write(addon_path.join("packages/installed.json"), "{}").unwrap();
+        for file in MANAGER_FILES {
+            fs::write(addon_path.join(file), "{}").unwrap();
+        }
+
+        let zip_path = root.join("out.zip");
+        let manifest = write_addon_zip(&Addon::builtin(), &addon_path, &zip_path).unwrap();
+        let archive = ZipArchive::new(File::open(&zip_path).unwrap()).unwrap();
+        let names: Vec<&str> = archive.file_names().collect();
+
+        assert!(names.iter().all(|n| n.starts_with("scripting_nodes/")), "{:?}", names);
+        assert!(names.contains(&"scripting_nodes/__init__.py"));
+        assert!(names.contains(&"scripting_nodes/nodes/node.py"));
+        let excluded =
+            [".git", "__pycache__", "packages", marker::MARKER_FILE, fingerprint::MANIFEST_FILE, backups::META_FILE];
+        for name in &names {
+            assert!(!name.split('/').any(|part| excluded.contains(&part)), "{} shouldn't be in the zip", name);
+        }
+        assert_eq!(manifest.len(), names.len());
+        fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn bl_info_version_reads_the_tuple() {
+        let source = "bl_info = {\n    \"name\": \"x\",\n    \"version\": (3, 4, 1),\n}";
+        assert_eq!(bl_info_version(source), Some("3.4.1".into()));
+        assert_eq!(bl_info_version("bl_info = {'version': (2, 0)}"), Some("2.0".into()));
+        assert_eq!(bl_info_version("bl_info = {\"version\": (1, 2, 3,)}"), Some("1.2.3".into()));
+    }
+
+    #[test]
+    fn malformed_bl_info_has_no_version() {
+        for source in [
+            "bl_info = {\"version\": \")(\"}",
+            "bl_info = {\"version\": )}",
+            "bl_info = {\"version\": (1, 2",
+            "bl_info = {\"version\": ()}",
+            "bl_info = {\"version\": (1, \"a\")}",
+            "bl_info = {\"name\": \"x\"}",
+            "\"version\": (1, 2)",
+            "",
+        ] {
+            assert_eq!(bl_info_version(source), None, "{}", source);
+        }
+    }
+}
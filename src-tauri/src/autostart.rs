@@ -0,0 +1,216 @@
+// Launch-on-startup entry - Run key on Windows, XDG autostart on Linux, LaunchAgent on macOS
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::State;
+
+#[cfg(not(windows))]
+use std::fs;
+
+use crate::load_settings;
+
+#[cfg(windows)]
+use crate::hidden_command;
+
+/// Passed by the startup entry so the app comes up in the tray instead of on screen
+pub const MINIMIZED_ARG: &str = "--minimized";
+
+#[cfg(windows)]
+const RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+#[cfg(windows)]
+const RUN_VALUE: &str = "SerpensDevManager";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AutostartStatus {
+    /// What the settings ask for
+    pub enabled: bool,
+    /// Whether a startup entry exists at all
+    pub present: bool,
+    /// The executable the entry launches
+    pub target: Option<String>,
+    /// The entry exists and launches this executable
+    #[serde(rename = "upToDate")]
+    pub up_to_date: bool,
+    /// Why the last attempt to repair the entry at launch failed
+    #[serde(rename = "repairError")]
+    pub repair_error: Option<String>,
+}
+
+/// Failure from repairing the entry at launch, kept until someone asks for the status
+#[derive(Default)]
+pub struct AutostartState(pub Mutex<Option<String>>);
+
+#[tauri::command]
+pub fn get_autostart_status(state: State<'_, AutostartState>) -> Result<AutostartStatus, String> {
+    let enabled = load_settings()?.launch_on_startup;
+    let target = entry_target();
+    let up_to_date = match (&target, std::env::current_exe()) {
+        (Some(target), Ok(exe)) => same_file(target, &exe),
+        _ => false,
+    };
+    Ok(AutostartStatus {
+        enabled,
+        present: target.is_some(),
+        target,
+        up_to_date,
+        repair_error: state.0.lock().unwrap_or_else(|e| e.into_inner()).clone(),
+    })
+}
+
+/// Create or remove the startup entry to match `enabled`.
+pub fn apply(enabled: bool) -> Result<(), String> {
+    if enabled {
+        let exe = std::env::current_exe().map_err(|e| format!("Could not locate the executable: {}", e))?;
+        write_entry(&exe).map_err(|e| format!("Failed to create startup entry: {}", e))
+    } else if entry_target().is_some() {
+        remove_entry().map_err(|e| format!("Failed to remove startup entry: {}", e))
+    } else {
+        Ok(())
+    }
+}
+
+/// Re-point the startup entry at this executable if it was moved since the entry was written.
+pub fn repair_on_launch(state: &AutostartState) {
+    let Ok(settings) = load_settings() else { return };
+    if !settings.launch_on_startup {
+        return;
+    }
+    let current = std::env::current_exe().ok();
+    let stale = match (entry_target(), &current) {
+        (Some(target), Some(exe)) => !same_file(&target, exe),
+        _ => true,
+    };
+    if stale {
+        let result = apply(true).err();
+        *state.0.lock().unwrap_or_else(|e| e.into_inner()) = result;
+    }
+}
+
+fn same_file(target: &str, exe: &Path) -> bool {
+    let target = PathBuf::from(target);
+    match (std::fs::canonicalize(&target), std::fs::canonicalize(exe)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => target == *exe,
+    }
+}
+
+#[cfg(windows)]
+fn write_entry(exe: &Path) -> Result<(), String> {
+    let command = format!("\"{}\" {}", exe.to_string_lossy(), MINIMIZED_ARG);
+    reg(&["add", RUN_KEY, "/v", RUN_VALUE, "/t", "REG_SZ", "/d", &command, "/f"])
+}
+
+#[cfg(windows)]
+fn remove_entry() -> Result<(), String> {
+    reg(&["delete", RUN_KEY, "/v", RUN_VALUE, "/f"])
+}
+
+#[cfg(windows)]
+fn entry_target() -> Option<String> {
+    let output = hidden_command("reg").args(["query", RUN_KEY, "/v", RUN_VALUE]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    // "    SerpensDevManager    REG_SZ    "C:\...\app.exe" --minimized"
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let line = stdout.lines().find(|l| l.trim_start().starts_with(RUN_VALUE))?;
+    let data = line.split_once("REG_SZ")?.1.trim();
+    match data.strip_prefix('"') {
+        Some(rest) => rest.split('"').next().map(str::to_string),
+        None => data.split_whitespace().next().map(str::to_string),
+    }
+}
+
+#[cfg(windows)]
+fn reg(args: &[&str]) -> Result<(), String> {
+    let output = hidden_command("reg").args(args).output().map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        // Group policy can lock the Run key; reg.exe says so on stderr
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn entry_path() -> Option<PathBuf> {
+    let config = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".config")))?;
+    Some(config.join("autostart").join("serpens-dev-manager.desktop"))
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn write_entry(exe: &Path) -> Result<(), String> {
+    let path = entry_path().ok_or("HOME not found")?;
+    let contents = format!(
+        "[Desktop Entry]\nType=Application\nName=Serpens Dev Manager\nExec=\"{}\" {}\nX-GNOME-Autostart-enabled=true\n",
+        exe.to_string_lossy(),
+        MINIMIZED_ARG
+    );
+    fs::create_dir_all(path.parent().unwrap_or(&path)).map_err(|e| e.to_string())?;
+    fs::write(&path, contents).map_err(|e| e.to_string())
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn entry_target() -> Option<String> {
+    let contents = fs::read_to_string(entry_path()?).ok()?;
+    let exec = contents.lines().find_map(|l| l.strip_prefix("Exec="))?.trim();
+    match exec.strip_prefix('"') {
+        Some(rest) => rest.split('"').next().map(str::to_string),
+        None => exec.split_whitespace().next().map(str::to_string),
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn entry_path() -> Option<PathBuf> {
+    let home = std::env::var_os("HOME")?;
+    Some(
+        PathBuf::from(home)
+            .join("Library")
+            .join("LaunchAgents")
+            .join("com.coreycorza.serpens-dev-manager.plist"),
+    )
+}
+
+#[cfg(target_os = "macos")]
+fn write_entry(exe: &Path) -> Result<(), String> {
+    let path = entry_path().ok_or("HOME not found")?;
+    let contents = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>com.coreycorza.serpens-dev-manager</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>{}</string>
+        <string>{}</string>
+    </array>
+    <key>RunAtLoad</key>
+    <true/>
+</dict>
+</plist>
+"#,
+        exe.to_string_lossy(),
+        MINIMIZED_ARG
+    );
+    fs::create_dir_all(path.parent().unwrap_or(&path)).map_err(|e| e.to_string())?;
+    fs::write(&path, contents).map_err(|e| e.to_string())
+}
+
+#[cfg(target_os = "macos")]
+fn entry_target() -> Option<String> {
+    let contents = fs::read_to_string(entry_path()?).ok()?;
+    let (_, args) = contents.split_once("<key>ProgramArguments</key>")?;
+    let (_, first) = args.split_once("<string>")?;
+    first.split("</string>").next().map(str::to_string)
+}
+
+#[cfg(not(windows))]
+fn remove_entry() -> Result<(), String> {
+    let path = entry_path().ok_or("HOME not found")?;
+    fs::remove_file(path).map_err(|e| e.to_string())
+}
@@ -0,0 +1,253 @@
+// Timestamped backups kept in the manager's data folder, plus the backup scheduler
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::addons::Addon;
+use crate::marker::read_marker;
+use crate::ops::{OperationState, Progress};
+use crate::{
+    addon_dir, addons, addons_dir, copy_dir_all, dir_size, git_output, load_settings, pre_switch_backup_dir,
+    remove_dir_robust, CopyOptions,
+};
+
+const BACKUP_PREFIX: &str = "scripting_nodes_";
+
+/// Sidecar inside each backup recording what was backed up
+pub const META_FILE: &str = ".serpens_backup_meta.json";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct BackupMeta {
+    pub branch: Option<String>,
+    pub commit: Option<String>,
+    #[serde(rename = "takenAt")]
+    pub taken_at: Option<String>,
+    /// Committer date of `commit`, RFC 3339
+    #[serde(rename = "lastUpdatedIso", default)]
+    pub last_updated_iso: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackupInfo {
+    pub name: String,
+    pub path: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<String>,
+    #[serde(rename = "sizeBytes")]
+    pub size_bytes: u64,
+    /// Branch and commit the install was on, for backups that recorded them
+    pub branch: Option<String>,
+    pub commit: Option<String>,
+    #[serde(rename = "lastUpdatedIso")]
+    pub last_updated_iso: Option<String>,
+}
+
+/// Where `addon`'s timestamped backups for a Blender version live
+pub fn backups_root(addon: &Addon, blender_version: &str) -> Result<PathBuf, String> {
+    Ok(addon.data_root()?.join("backups").join(blender_version))
+}
+
+#[tauri::command]
+pub fn list_backups(blender_version: String, addon_id: Option<String>) -> Result<Vec<BackupInfo>, String> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    Ok(backup_dirs(&backups_root(&addon, &blender_version)?)
+        .into_iter()
+        .map(|path| {
+            let meta = read_meta(&path).unwrap_or_default();
+            BackupInfo {
+                name: path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+                created_at: meta.taken_at.or_else(|| {
+                    fs::metadata(&path)
+                        .and_then(|m| m.modified())
+                        .ok()
+                        .map(|t| chrono::DateTime::<chrono::Local>::from(t).to_rfc3339())
+                }),
+                size_bytes: dir_size(&path),
+                path: path.to_string_lossy().to_string(),
+                branch: meta.branch,
+                commit: meta.commit,
+                last_updated_iso: meta.last_updated_iso,
+            }
+        })
+        .collect())
+}
+
+/// Every backup of a Blender version at a glance: no sizes, nothing recursive
+#[derive(Debug, Default)]
+pub struct BackupScan {
+    pub count: usize,
+    pub newest: Option<SystemTime>,
+}
+
+/// Timestamped backups (folders or zips), the pre-switch snapshot and the original
+/// backup. Dated by their metadata sidecar when there is one, else by the folder itself;
+/// anything unreadable is skipped rather than failing the scan.
+pub fn scan(addon: &Addon, blender_version: &str) -> BackupScan {
+    let root = backups_root(addon, blender_version).ok();
+    let mut candidates = root.as_deref().map(backup_dirs).unwrap_or_default();
+    if let Some(entries) = root.and_then(|root| fs::read_dir(root).ok()) {
+        candidates.extend(
+            entries
+                .flatten()
+                .filter(|e| {
+                    let name = e.file_name().to_string_lossy().to_string();
+                    name.starts_with(BACKUP_PREFIX) && name.ends_with(".zip")
+                })
+                .map(|e| e.path()),
+        );
+    }
+    candidates.extend(pre_switch_backup_dir(addon, blender_version).ok());
+    candidates.extend(addons_dir(blender_version).ok().map(|p| p.join(addon.original_backup_folder())));
+
+    let times: Vec<SystemTime> = candidates.iter().filter_map(|path| backup_time(path)).collect();
+    BackupScan { count: times.len(), newest: times.into_iter().max() }
+}
+
+fn backup_time(path: &Path) -> Option<SystemTime> {
+    let meta = fs::metadata(path).ok()?;
+    let recorded = meta
+        .is_dir()
+        .then(|| read_meta(path))
+        .flatten()
+        .and_then(|m| m.taken_at)
+        .and_then(|taken| chrono::DateTime::parse_from_rfc3339(&taken).ok())
+        .map(SystemTime::from);
+    // Creation time isn't available everywhere; a folder's mtime is the next best thing
+    recorded.or_else(|| meta.created().or_else(|_| meta.modified()).ok())
+}
+
+/// When the most recent backup of any kind was made
+pub fn newest_backup_time(addon: &Addon, blender_version: &str) -> Option<SystemTime> {
+    scan(addon, blender_version).newest
+}
+
+#[tauri::command]
+pub fn last_backup_time(blender_version: String, addon_id: Option<String>) -> Option<String> {
+    let addon = addons::resolve(addon_id.as_deref()).ok()?;
+    newest_backup_time(&addon, &blender_version).map(|t| chrono::DateTime::<chrono::Local>::from(t).to_rfc3339())
+}
+
+/// Record the branch and commit of `addon_path` in the backup just copied from it.
+/// Installs that aren't git checkouts fall back to what the install marker knows.
+pub fn write_meta(addon_path: &Path, backup_path: &Path) -> Result<(), String> {
+    let marker = read_marker(addon_path).unwrap_or_default();
+    let meta = BackupMeta {
+        branch: git_output(addon_path, &["rev-parse", "--abbrev-ref", "HEAD"]).ok().or(marker.branch),
+        commit: git_output(addon_path, &["rev-parse", "HEAD"]).ok().or(marker.commit),
+        taken_at: Some(chrono::Local::now().to_rfc3339()),
+        last_updated_iso: git_output(addon_path, &["log", "-1", "--format=%cI"]).ok(),
+    };
+    let content = serde_json::to_string_pretty(&meta).map_err(|e| format!("Failed to serialize backup info: {}", e))?;
+    fs::write(backup_path.join(META_FILE), content).map_err(|e| format!("Failed to write backup info: {}", e))
+}
+
+pub fn read_meta(backup_path: &Path) -> Option<BackupMeta> {
+    let content = fs::read_to_string(backup_path.join(META_FILE)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// A restore copies the sidecar back into the install, where it doesn't belong
+pub fn strip_meta(addon_path: &Path) {
+    fs::remove_file(addon_path.join(META_FILE)).ok();
+}
+
+/// Timestamped backup folders, newest first. The names sort chronologically.
+pub fn backup_dirs(root: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(root) else { return Vec::new() };
+    let mut dirs: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .filter(|e| e.file_name().to_string_lossy().starts_with(BACKUP_PREFIX))
+        .map(|e| e.path())
+        .collect();
+    dirs.sort();
+    dirs.reverse();
+    dirs
+}
+
+/// Copy the install into a new timestamped folder, then prune down to `max_backups`.
+pub fn create_timestamped_backup(addon: &Addon, blender_version: &str, max_backups: u32) -> Result<PathBuf, String> {
+    let addon_path = addon_dir(addon, blender_version)?;
+    if !addon_path.exists() {
+        return Err("No installation found to backup".to_string());
+    }
+
+    let root = backups_root(addon, blender_version)?;
+    let stamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+    let dest = root.join(format!("{}{}", BACKUP_PREFIX, stamp));
+    copy_dir_all(&addon_path, &dest, CopyOptions::from_settings(), &Progress::none())
+        .map_err(|e| format!("Failed to copy: {}", e))?;
+    write_meta(&addon_path, &dest)?;
+
+    if max_backups > 0 {
+        for old in backup_dirs(&root).into_iter().skip(max_backups as usize) {
+            remove_dir_robust(&old).ok();
+        }
+    }
+    Ok(dest)
+}
+
+#[derive(Debug, Serialize, Clone)]
+struct ScheduledBackup {
+    #[serde(rename = "blenderVersion")]
+    blender_version: String,
+    #[serde(rename = "addonId")]
+    addon_id: String,
+    path: String,
+}
+
+/// Back up each installed addon every `autoBackupIntervalHours`, measured from its newest
+/// existing backup so restarts don't reset the clock.
+pub fn spawn_backup_scheduler(app: AppHandle) {
+    thread::spawn(move || loop {
+        thread::sleep(Duration::from_secs(5 * 60));
+
+        let Ok(settings) = load_settings() else { continue };
+        if settings.auto_backup_interval_hours == 0 {
+            continue;
+        }
+        for addon in std::iter::once(addons::Addon::builtin()).chain(settings.addons.iter().cloned()) {
+            let (hours, keep) = (settings.auto_backup_interval_hours, settings.max_backups);
+            scheduled_backup(&addon, &app, &settings.blender_version, hours, keep);
+        }
+    });
+}
+
+fn scheduled_backup(addon: &Addon, app: &AppHandle, version: &str, interval_hours: u32, max_backups: u32) {
+    if !addon_dir(addon, version).map(|p| p.exists()).unwrap_or(false) {
+        return;
+    }
+
+    let interval = Duration::from_secs(u64::from(interval_hours) * 3600);
+    let newest = backups_root(addon, version)
+        .ok()
+        .and_then(|root| backup_dirs(&root).into_iter().next())
+        .and_then(|dir| fs::metadata(dir).and_then(|m| m.modified()).ok());
+    let due = newest
+        .and_then(|t| SystemTime::now().duration_since(t).ok())
+        .map(|age| age >= interval)
+        .unwrap_or(true);
+    if !due {
+        return;
+    }
+
+    // Try again on the next tick if the user is in the middle of something
+    let Ok(guard) = app.state::<OperationState>().begin("scheduled_backup", version) else { return };
+    let result = create_timestamped_backup(addon, version, max_backups);
+    guard.finish(&result);
+    if let Ok(path) = result {
+        app.emit(
+            "serpens://backup-created",
+            ScheduledBackup {
+                blender_version: version.to_string(),
+                addon_id: addon.id.clone(),
+                path: path.to_string_lossy().to_string(),
+            },
+        )
+        .ok();
+    }
+}
@@ -0,0 +1,286 @@
+// Blender program discovery - finds real Blender installs rather than config folders
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::hidden_command;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BlenderExecutable {
+    pub version: String,
+    #[serde(rename = "executablePath")]
+    pub executable_path: String,
+}
+
+#[tauri::command]
+pub async fn detect_blender_executables() -> Result<Vec<BlenderExecutable>, String> {
+    tokio::task::spawn_blocking(|| Ok(find_blender_executables()))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Every Blender install we can find, newest version first.
+pub fn find_blender_executables() -> Vec<BlenderExecutable> {
+    let mut found: Vec<BlenderExecutable> = Vec::new();
+    let mut seen: Vec<PathBuf> = Vec::new();
+
+    for (exe, hint) in candidate_executables() {
+        if !exe.is_file() {
+            continue;
+        }
+        let canonical = fs::canonicalize(&exe).unwrap_or_else(|_| exe.clone());
+        if seen.contains(&canonical) {
+            continue;
+        }
+        seen.push(canonical);
+
+        let version = version_from_layout(&exe).or(hint).or_else(|| version_from_binary(&exe));
+        if let Some(version) = version {
+            found.push(BlenderExecutable { version, executable_path: exe.to_string_lossy().to_string() });
+        }
+    }
+
+    found.sort_by_key(|e| std::cmp::Reverse(version_key(&e.version)));
+    found
+}
+
+/// The Blender binary whose config folder is `blender_version` - the only one that
+/// will actually load the addon installed for that version.
+pub fn blender_for_version(blender_version: &str) -> Result<PathBuf, String> {
+    find_blender_executables()
+        .into_iter()
+        .find(|b| b.version == blender_version)
+        .map(|b| PathBuf::from(b.executable_path))
+        .ok_or_else(|| format!("No Blender {} executable found", blender_version))
+}
+
+/// Whether any Blender is running. Blender keeps the addon's modules and DLLs loaded,
+/// so removing the install underneath it fails halfway on Windows.
+pub fn blender_running() -> bool {
+    #[cfg(windows)]
+    let output = hidden_command("tasklist").args(["/FI", "IMAGENAME eq blender.exe", "/NH"]).output();
+    #[cfg(not(windows))]
+    let output = hidden_command("pgrep").args(["-i", "-x", "blender"]).output();
+    match output {
+        #[cfg(windows)]
+        Ok(output) => String::from_utf8_lossy(&output.stdout).to_lowercase().contains("blender.exe"),
+        #[cfg(not(windows))]
+        Ok(output) => output.status.success(),
+        Err(_) => false,
+    }
+}
+
+/// Numeric sort key for "major.minor" strings so 4.10 sorts after 4.9.
+pub fn version_key(version: &str) -> Vec<u32> {
+    version.split('.').map(|p| p.parse().unwrap_or(0)).collect()
+}
+
+/// Reduce "4.2.1" (or "Blender 4.2.1 LTS") to the "4.2" form Blender uses for its config folders.
+fn major_minor(raw: &str) -> Option<String> {
+    let start = raw.find(|c: char| c.is_ascii_digit())?;
+    let mut parts = raw[start..].split(|c: char| !c.is_ascii_digit());
+    let major = parts.next().filter(|p| !p.is_empty())?;
+    let minor = parts.next().filter(|p| !p.is_empty())?;
+    Some(format!("{}.{}", major, minor))
+}
+
+pub fn is_version_dir_name(name: &str) -> bool {
+    let mut parts = name.split('.');
+    matches!(
+        (parts.next(), parts.next(), parts.next()),
+        (Some(a), Some(b), None)
+            if !a.is_empty() && !b.is_empty()
+                && a.chars().all(|c| c.is_ascii_digit())
+                && b.chars().all(|c| c.is_ascii_digit())
+    )
+}
+
+/// Blender ships its bundled data in a folder named after its version ("4.2/")
+/// next to the binary (or under Resources/ on macOS, /usr/share/blender on distros).
+fn version_from_layout(exe: &Path) -> Option<String> {
+    let dir = exe.parent()?;
+    let mut roots = vec![dir.to_path_buf()];
+    if let Some(contents) = dir.parent() {
+        roots.push(contents.join("Resources"));
+    }
+    roots.push(PathBuf::from("/usr/share/blender"));
+
+    for root in roots {
+        let Ok(entries) = fs::read_dir(&root) else { continue };
+        let mut versions: Vec<String> = entries
+            .flatten()
+            .filter(|e| e.path().is_dir())
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .filter(|name| is_version_dir_name(name))
+            .collect();
+        versions.sort_by_key(|v| std::cmp::Reverse(version_key(v)));
+        if let Some(v) = versions.into_iter().next() {
+            return Some(v);
+        }
+    }
+    None
+}
+
+/// Last resort - ask the binary itself. Slow-ish, so only used when the layout gives no hint.
+fn version_from_binary(exe: &Path) -> Option<String> {
+    let output = hidden_command(exe).arg("--version").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    stdout.lines().find(|l| l.trim_start().starts_with("Blender")).and_then(major_minor)
+}
+
+/// Directories directly under `parent` whose name starts with `prefix`.
+fn dirs_with_prefix(parent: &Path, prefix: &str) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(parent) else { return Vec::new() };
+    entries
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .filter(|e| e.file_name().to_string_lossy().to_lowercase().starts_with(prefix))
+        .map(|e| e.path())
+        .collect()
+}
+
+fn path_lookup(program: &str) -> Vec<PathBuf> {
+    std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).map(|p| p.join(program)).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(windows)]
+fn candidate_executables() -> Vec<(PathBuf, Option<String>)> {
+    let mut candidates = Vec::new();
+
+    // Installer (MSI) entries - per-machine, per-user and 32-bit views
+    for root in [
+        r"HKLM\SOFTWARE\Microsoft\Windows\CurrentVersion\Uninstall",
+        r"HKLM\SOFTWARE\WOW6432Node\Microsoft\Windows\CurrentVersion\Uninstall",
+        r"HKCU\SOFTWARE\Microsoft\Windows\CurrentVersion\Uninstall",
+    ] {
+        for values in reg_query_tree(root) {
+            let name = reg_value(&values, "DisplayName").unwrap_or_default();
+            if !name.to_lowercase().contains("blender") {
+                continue;
+            }
+            if let Some(location) = reg_value(&values, "InstallLocation").filter(|l| !l.is_empty()) {
+                let hint = reg_value(&values, "DisplayVersion").and_then(|v| major_minor(&v));
+                candidates.push((PathBuf::from(location).join("blender.exe"), hint));
+            }
+        }
+    }
+
+    // Steam library folders
+    if let Some(steam) = reg_query_tree(r"HKCU\Software\Valve\Steam")
+        .into_iter()
+        .find_map(|values| reg_value(&values, "SteamPath"))
+    {
+        for library in steam_libraries(Path::new(&steam)) {
+            candidates.push((library.join("steamapps").join("common").join("Blender").join("blender.exe"), None));
+        }
+    }
+
+    // Portable / zip installs dropped into the usual place
+    for var in ["ProgramFiles", "ProgramFiles(x86)"] {
+        if let Ok(program_files) = std::env::var(var) {
+            let foundation = PathBuf::from(program_files).join("Blender Foundation");
+            for dir in dirs_with_prefix(&foundation, "blender") {
+                candidates.push((dir.join("blender.exe"), None));
+            }
+        }
+    }
+
+    for exe in path_lookup("blender.exe") {
+        candidates.push((exe, None));
+    }
+
+    candidates
+}
+
+#[cfg(not(windows))]
+fn candidate_executables() -> Vec<(PathBuf, Option<String>)> {
+    let home = std::env::var("HOME").map(PathBuf::from).ok();
+    let mut exes: Vec<PathBuf> = Vec::new();
+
+    if cfg!(target_os = "macos") {
+        let mut app_dirs = vec![PathBuf::from("/Applications")];
+        if let Some(home) = &home {
+            app_dirs.push(home.join("Applications"));
+        }
+        for apps in app_dirs {
+            for app in dirs_with_prefix(&apps, "blender") {
+                exes.push(app.join("Contents").join("MacOS").join("Blender"));
+            }
+        }
+    } else {
+        for bin in ["/usr/bin", "/usr/local/bin", "/snap/bin"] {
+            exes.push(PathBuf::from(bin).join("blender"));
+        }
+        let mut parents = vec![PathBuf::from("/opt")];
+        if let Some(home) = &home {
+            parents.push(home.clone());
+            parents.push(home.join("Applications"));
+            parents.push(home.join(".local").join("share"));
+        }
+        for parent in parents {
+            for dir in dirs_with_prefix(&parent, "blender") {
+                exes.push(dir.join("blender"));
+            }
+        }
+        exes.push(PathBuf::from("/var/lib/flatpak/exports/bin/org.blender.Blender"));
+    }
+
+    exes.extend(path_lookup("blender"));
+    exes.into_iter().map(|exe| (exe, None)).collect()
+}
+
+/// Parse `reg query <key> /s` output into one list of (name, data) pairs per key.
+#[cfg(windows)]
+fn reg_query_tree(key: &str) -> Vec<Vec<(String, String)>> {
+    let Ok(output) = hidden_command("reg").args(["query", key, "/s"]).output() else {
+        return Vec::new();
+    };
+    if !output.status.success() {
+        return Vec::new();
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut blocks: Vec<Vec<(String, String)>> = Vec::new();
+    for line in stdout.lines() {
+        if line.starts_with("HKEY_") {
+            blocks.push(Vec::new());
+            continue;
+        }
+        // Value lines look like: "    Name    REG_SZ    Data"
+        let parts: Vec<&str> = line.trim().splitn(3, "    ").collect();
+        if let (Some(block), [name, kind, data]) = (blocks.last_mut(), parts.as_slice()) {
+            if kind.starts_with("REG_") {
+                block.push((name.to_string(), data.trim().to_string()));
+            }
+        }
+    }
+    blocks
+}
+
+#[cfg(windows)]
+fn reg_value(values: &[(String, String)], name: &str) -> Option<String> {
+    values.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)).map(|(_, v)| v.clone())
+}
+
+/// Steam's own folder plus any extra libraries listed in libraryfolders.vdf.
+#[cfg(windows)]
+fn steam_libraries(steam: &Path) -> Vec<PathBuf> {
+    let mut libraries = vec![steam.to_path_buf()];
+    let vdf = steam.join("steamapps").join("libraryfolders.vdf");
+    if let Ok(content) = fs::read_to_string(vdf) {
+        for line in content.lines() {
+            // Format: "path"		"D:\\SteamLibrary"
+            let fields: Vec<&str> = line.split('"').filter(|f| !f.trim().is_empty()).collect();
+            if fields.len() == 2 && fields[0] == "path" {
+                libraries.push(PathBuf::from(fields[1].replace("\\\\", "\\")));
+            }
+        }
+    }
+    libraries
+}
@@ -0,0 +1,104 @@
+// Writing the remote's branch list to a file, for offline reference and reports
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+use crate::divergence::DivergenceCache;
+use crate::{addons, list_branches, remotes, Branch};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BranchExport {
+    pub path: String,
+    pub format: String,
+    pub count: usize,
+}
+
+/// What a JSON export holds: the branches plus where and when they were listed
+#[derive(Debug, Serialize)]
+struct Snapshot<'a> {
+    #[serde(rename = "exportedAt")]
+    exported_at: String,
+    url: &'a str,
+    #[serde(rename = "defaultBranch")]
+    default_branch: Option<&'a str>,
+    branches: &'a [Branch],
+}
+
+/// Write the branches of `remote` (the official repo by default) to `dest_path` as
+/// "json" or "csv". `dest_path` may be an existing folder to put the file in.
+#[tauri::command]
+pub async fn export_branches(
+    dest_path: String,
+    format: String,
+    remote: Option<String>,
+    addon_id: Option<String>,
+    app: AppHandle,
+) -> Result<BranchExport, String> {
+    let format = format.trim().to_lowercase();
+    if format != "json" && format != "csv" {
+        return Err(format!("Unknown export format '{}' (use json or csv)", format));
+    }
+    let addon = addons::resolve(addon_id.as_deref())?;
+    tokio::task::spawn_blocking(move || {
+        let url = remotes::remote_url(&addon, remote.as_deref())?;
+        let list = list_branches(&url, &app.state::<DivergenceCache>())?;
+
+        let mut dest = PathBuf::from(&dest_path);
+        if dest.is_dir() {
+            dest = dest.join(format!("branches-{}.{}", chrono::Local::now().format("%Y%m%d_%H%M%S"), format));
+        }
+        if let Some(parent) = dest.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create destination: {}", e))?;
+        }
+
+        let content = if format == "json" {
+            let snapshot = Snapshot {
+                exported_at: chrono::Local::now().to_rfc3339(),
+                url: &url,
+                default_branch: list.default_branch.as_deref(),
+                branches: &list.branches,
+            };
+            serde_json::to_string_pretty(&snapshot).map_err(|e| format!("Failed to serialize branches: {}", e))?
+        } else {
+            to_csv(&list.branches, list.default_branch.as_deref())
+        };
+        fs::write(&dest, content).map_err(|e| format!("Failed to write {}: {}", dest.display(), e))?;
+
+        Ok(BranchExport {
+            path: dest.to_string_lossy().to_string(),
+            format,
+            count: list.branches.len(),
+        })
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+fn to_csv(branches: &[Branch], default_branch: Option<&str>) -> String {
+    let mut csv = String::from("name,sha,default,ahead_of_default,behind_default,last_commit\n");
+    for branch in branches {
+        let count = |n: Option<u32>| n.map(|n| n.to_string()).unwrap_or_default();
+        let row = [
+            csv_field(&branch.name),
+            branch.sha.clone(),
+            (default_branch == Some(branch.name.as_str())).to_string(),
+            count(branch.ahead_of_default),
+            count(branch.behind_default),
+            csv_field(branch.last_commit.as_deref().unwrap_or("")),
+        ];
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Quoted when it holds a comma, quote or line break, with quotes doubled
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,263 @@
+// Every branch the install knows of, local and remote, side by side - one fetch, then
+// everything else is read from the install's own refs
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use tauri::State;
+
+use crate::ops::{OperationState, Progress};
+use crate::remotes::DEFAULT_REMOTE;
+use crate::{addons, default_clone_depth, git_addon_dir, git_output, load_settings, validate};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BranchSync {
+    pub name: String,
+    /// The remote the branch is on, None for a branch that only exists locally
+    pub remote: Option<String>,
+    /// There's a local branch of this name
+    pub local: bool,
+    #[serde(rename = "checkedOut")]
+    pub checked_out: bool,
+    /// Local commits the remote branch doesn't have; None without both sides to compare
+    pub ahead: Option<u32>,
+    /// Remote commits the local branch doesn't have
+    pub behind: Option<u32>,
+    #[serde(rename = "remoteTip")]
+    pub remote_tip: Option<String>,
+    /// RFC 3339 committer date of the remote tip
+    #[serde(rename = "remoteDate")]
+    pub remote_date: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SyncOverview {
+    pub branches: Vec<BranchSync>,
+    /// The fetch failed, so the remote side is as of the last successful one
+    #[serde(rename = "fetchError")]
+    pub fetch_error: Option<String>,
+    /// The install is shallow, so ahead/behind only count as far back as it reaches
+    pub shallow: bool,
+}
+
+/// One ref as for-each-ref reports it
+struct RefInfo {
+    sha: String,
+    date: String,
+    /// "refs/remotes/origin/main" for a local branch with an upstream
+    upstream: String,
+}
+
+#[tauri::command]
+pub async fn branch_sync_overview(
+    blender_version: String,
+    addon_id: Option<String>,
+    ops: State<'_, OperationState>,
+) -> Result<SyncOverview, String> {
+    validate::blender_version(&blender_version)?;
+    let addon = addons::resolve(addon_id.as_deref())?;
+    let guard = ops.begin("branch_sync_overview", &blender_version)?;
+    tokio::task::spawn_blocking(move || {
+        let result = git_addon_dir(&addon, &blender_version).and_then(|path| overview(&path, &guard.progress()));
+        guard.finish(&result);
+        result
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Every branch of the working copy at `addon_path`, after fetching its remotes
+fn overview(addon_path: &Path, progress: &Progress) -> Result<SyncOverview, String> {
+    let shallow = git_output(addon_path, &["rev-parse", "--is-shallow-repository"]).is_ok_and(|s| s == "true");
+
+    progress.phase("fetching");
+    let fetch_error = fetch_all(addon_path, shallow).err();
+
+    progress.phase("comparing");
+    let listing = git_output(
+        addon_path,
+        &[
+            "for-each-ref",
+            "--format=%(refname)%1f%(objectname)%1f%(committerdate:iso-strict)%1f%(upstream)",
+            "refs/heads",
+            "refs/remotes",
+        ],
+    )?;
+    let mut locals: BTreeMap<String, RefInfo> = BTreeMap::new();
+    // (remote, branch) -> ref
+    let mut remotes: BTreeMap<(String, String), RefInfo> = BTreeMap::new();
+    for line in listing.lines() {
+        let mut fields = line.split('\x1f');
+        let (Some(refname), Some(sha), Some(date), Some(upstream)) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        let info = RefInfo {
+            sha: sha.to_string(),
+            date: date.to_string(),
+            upstream: upstream.to_string(),
+        };
+        if let Some(name) = refname.strip_prefix("refs/heads/") {
+            locals.insert(name.to_string(), info);
+        } else if let Some((remote, name)) = refname.strip_prefix("refs/remotes/").and_then(|r| r.split_once('/')) {
+            if name != "HEAD" {
+                remotes.insert((remote.to_string(), name.to_string()), info);
+            }
+        }
+    }
+    let checked_out = git_output(addon_path, &["symbolic-ref", "--short", "--quiet", "HEAD"]).ok();
+
+    // Each local branch is compared with its upstream, or failing that the same name on origin
+    let counterpart = |name: &str, info: &RefInfo| -> Option<(String, String)> {
+        let tracked = info.upstream.strip_prefix("refs/remotes/").and_then(|r| r.split_once('/'));
+        match tracked {
+            Some((remote, branch)) => Some((remote.to_string(), branch.to_string())),
+            None => Some((DEFAULT_REMOTE.to_string(), name.to_string())),
+        }
+    };
+    let mut branches = Vec::new();
+    let mut paired = Vec::new();
+    for (name, info) in &locals {
+        let pair = counterpart(name, info).filter(|key| remotes.contains_key(key));
+        let remote_ref = pair.as_ref().and_then(|key| remotes.get(key));
+        let counts = remote_ref.and_then(|remote| ahead_behind(addon_path, &info.sha, &remote.sha));
+        branches.push(BranchSync {
+            name: name.clone(),
+            remote: pair.as_ref().map(|(remote, _)| remote.clone()),
+            local: true,
+            checked_out: checked_out.as_deref() == Some(name.as_str()),
+            ahead: counts.map(|c| c.0),
+            behind: counts.map(|c| c.1),
+            remote_tip: remote_ref.map(|r| r.sha.clone()),
+            remote_date: remote_ref.map(|r| r.date.clone()),
+        });
+        paired.extend(pair);
+    }
+    for ((remote, name), info) in &remotes {
+        if paired.iter().any(|(r, n)| r == remote && n == name) {
+            continue;
+        }
+        branches.push(BranchSync {
+            name: name.clone(),
+            remote: Some(remote.clone()),
+            local: false,
+            checked_out: false,
+            ahead: None,
+            behind: None,
+            remote_tip: Some(info.sha.clone()),
+            remote_date: Some(info.date.clone()),
+        });
+    }
+    branches.sort_by(|a, b| (&a.name, &a.remote).cmp(&(&b.name, &b.remote)));
+    Ok(SyncOverview { branches, fetch_error, shallow })
+}
+
+/// One fetch of every configured remote. A single-branch clone only tracks its own branch,
+/// so origin's refspec is widened for this fetch. A shallow install fetches the other
+/// branches only as deep as it already is, rather than their whole history - never less,
+/// since `--depth` would cut the checked-out branch's history down to it too.
+fn fetch_all(addon_path: &Path, shallow: bool) -> Result<(), String> {
+    let mut fetch = vec![
+        "-c",
+        "remote.origin.fetch=+refs/heads/*:refs/remotes/origin/*",
+        "fetch",
+        "--all",
+        "--prune",
+        "--no-tags",
+        "--quiet",
+    ];
+    let configured = load_settings()
+        .map(|s| s.clone_depth)
+        .unwrap_or_else(|_| default_clone_depth())
+        .unwrap_or(1);
+    let reached = git_output(addon_path, &["rev-list", "--count", "HEAD"])
+        .ok()
+        .and_then(|n| n.parse::<u32>().ok())
+        .unwrap_or(1);
+    let depth = configured.max(reached).to_string();
+    if shallow {
+        fetch.extend(["--depth", depth.as_str()]);
+    }
+    git_output(addon_path, &fetch).map(|_| ())
+}
+
+/// (only in `local`, only in `remote`)
+fn ahead_behind(addon_path: &Path, local: &str, remote: &str) -> Option<(u32, u32)> {
+    let range = format!("{}...{}", local, remote);
+    let counts = git_output(addon_path, &["rev-list", "--left-right", "--count", &range]).ok()?;
+    let mut parts = counts.split_whitespace().map(str::parse::<u32>);
+    Some((parts.next()?.ok()?, parts.next()?.ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::{bare_remote, commit, git, scratch};
+    use std::fs;
+
+    fn branch<'a>(overview: &'a SyncOverview, name: &str) -> &'a BranchSync {
+        overview
+            .branches
+            .iter()
+            .find(|b| b.name == name)
+            .unwrap_or_else(|| panic!("no {} in the overview", name))
+    }
+
+    #[test]
+    fn overview_pairs_local_and_remote_branches() {
+        let dir = scratch("branchsync", "overview");
+        let remote_url = bare_remote(&dir).to_string_lossy().to_string();
+        let (seed, install) = (dir.join("seed"), dir.join("install"));
+        git(&dir, &["init", "-q", "-b", "main", &seed.to_string_lossy()]);
+        git(&seed, &["remote", "add", "origin", &remote_url]);
+        commit(&seed, "first.txt", "First");
+        for name in ["ahead", "behind", "remote-only"] {
+            git(&seed, &["branch", name]);
+        }
+        git(&seed, &["push", "-q", "origin", "main", "ahead", "behind", "remote-only"]);
+
+        git(&dir, &["clone", "-q", &remote_url, &install.to_string_lossy()]);
+        git(&install, &["checkout", "-q", "-b", "ahead", "--track", "origin/ahead"]);
+        commit(&install, "local-work.txt", "Local work");
+        git(&install, &["checkout", "-q", "-b", "behind", "--track", "origin/behind"]);
+        git(&install, &["checkout", "-q", "-b", "local-only"]);
+        git(&seed, &["checkout", "-q", "behind"]);
+        commit(&seed, "pushed-later.txt", "Pushed later");
+        git(&seed, &["push", "-q", "origin", "behind"]);
+
+        let overview = overview(&install, &Progress::none()).unwrap();
+        assert_eq!(overview.fetch_error, None);
+        assert!(!overview.shallow);
+        let names: Vec<&str> = overview.branches.iter().map(|b| b.name.as_str()).collect();
+        assert_eq!(names, ["ahead", "behind", "local-only", "main", "remote-only"]);
+
+        let ahead = branch(&overview, "ahead");
+        assert_eq!(ahead.remote.as_deref(), Some("origin"));
+        assert!(ahead.local);
+        assert_eq!((ahead.ahead, ahead.behind), (Some(1), Some(0)));
+
+        let behind = branch(&overview, "behind");
+        assert_eq!((behind.ahead, behind.behind), (Some(0), Some(1)));
+        let tip = git_output(&seed, &["rev-parse", "HEAD"]).unwrap();
+        assert_eq!(behind.remote_tip.as_deref(), Some(tip.as_str()));
+        assert!(behind.remote_date.is_some());
+
+        let main = branch(&overview, "main");
+        assert_eq!((main.ahead, main.behind), (Some(0), Some(0)));
+        assert!(!main.checked_out);
+
+        let local_only = branch(&overview, "local-only");
+        assert!(local_only.local && local_only.checked_out);
+        assert_eq!(local_only.remote, None);
+        assert_eq!((local_only.ahead, local_only.behind), (None, None));
+        assert_eq!(local_only.remote_tip, None);
+
+        let remote_only = branch(&overview, "remote-only");
+        assert!(!remote_only.local);
+        assert_eq!(remote_only.remote.as_deref(), Some("origin"));
+        assert_eq!((remote_only.ahead, remote_only.behind), (None, None));
+        assert!(remote_only.remote_tip.is_some());
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,82 @@
+// Watching the official repo for branches being created or deleted
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Emitter};
+
+use crate::addons::Addon;
+use crate::{applog, data_dir, load_settings, notify, remote_branch_names};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BranchChanges {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+/// Last branch set we saw, kept on disk so a relaunch doesn't announce every branch again
+fn cache_path() -> Result<PathBuf, String> {
+    Ok(data_dir()?.join("known_branches.json"))
+}
+
+/// Remote branches as of the last background check
+pub fn known_branches() -> Option<BTreeSet<String>> {
+    let content = fs::read_to_string(cache_path().ok()?).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn save_known(branches: &BTreeSet<String>) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(branches).map_err(|e| format!("Failed to serialize branches: {}", e))?;
+    fs::write(cache_path()?, content).map_err(|e| format!("Failed to save known branches: {}", e))
+}
+
+/// Compare the remote's branches with the cached set and announce any difference.
+/// Doesn't touch an install, so it runs without the operation lock and whether or not
+/// anything is installed. The very first run only records what's there.
+pub fn check_new_branches(addon: &Addon, app: &AppHandle) -> Result<Option<BranchChanges>, String> {
+    let current: BTreeSet<String> = remote_branch_names(addon)?.into_iter().collect();
+    if current.is_empty() {
+        // An empty listing is far more likely a hiccup than every branch being deleted
+        return Ok(None);
+    }
+
+    let Some(known) = known_branches() else {
+        save_known(&current)?;
+        return Ok(None);
+    };
+    if known == current {
+        return Ok(None);
+    }
+
+    let changes = BranchChanges {
+        added: current.difference(&known).cloned().collect(),
+        removed: known.difference(&current).cloned().collect(),
+    };
+    save_known(&current)?;
+
+    applog::info(&format!(
+        "Remote branches changed: added [{}], removed [{}]",
+        changes.added.join(", "),
+        changes.removed.join(", ")
+    ));
+    app.emit("serpens://new-branches", &changes).ok();
+    if load_settings().map(|s| s.new_branch_notifications).unwrap_or(true) {
+        if let Some(body) = summary(&changes) {
+            notify::show(app, "Serpens branches changed", &body);
+        }
+    }
+    Ok(Some(changes))
+}
+
+/// "New: a, b. Removed: c"
+fn summary(changes: &BranchChanges) -> Option<String> {
+    let mut parts = Vec::new();
+    if !changes.added.is_empty() {
+        parts.push(format!("New: {}", changes.added.join(", ")));
+    }
+    if !changes.removed.is_empty() {
+        parts.push(format!("Removed: {}", changes.removed.join(", ")));
+    }
+    (!parts.is_empty()).then(|| parts.join(". "))
+}
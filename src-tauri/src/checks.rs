@@ -0,0 +1,621 @@
+// Quality checks run against the installed addon
+
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, State};
+
+use crate::addons::Addon;
+use crate::blender::blender_for_version;
+use crate::ops::{OperationGuard, OperationState};
+use crate::{addon_dir, addons, hidden_command, load_settings};
+
+/// Blender test suites can be slow to boot and run, so be generous.
+const TEST_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+/// Enabling the addon is one import and one register() call; anything longer is a hang
+const VERIFY_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+/// Printed by the verify script so its verdict can't be confused with the addon's output
+const VERIFY_TAG: &str = "SERPENS_VERIFY:";
+/// Starts each line the syntax check prints for a file that doesn't compile
+const SYNTAX_TAG: &str = "SERPENS_SYNTAX:";
+/// Python that reads the values passed after `--` into `args`. Blender and `python -c`
+/// both leave them in sys.argv, so scripts never need Rust strings spliced into their source
+const SCRIPT_ARGS: &str = "import sys\nargs = sys.argv[sys.argv.index('--') + 1:]\n";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OutputLine {
+    pub stream: String,
+    pub line: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TestRunResult {
+    /// "passed", "failed", "no_tests", "cancelled" or "timed_out"
+    pub status: String,
+    pub passed: u32,
+    pub failed: u32,
+    pub skipped: u32,
+    #[serde(rename = "exitCode")]
+    pub exit_code: Option<i32>,
+    pub log: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LoadCheckResult {
+    /// "loaded", "failed", "cancelled" or "timed_out"
+    pub status: String,
+    /// True only when the addon imported and register() returned without raising
+    pub loaded: bool,
+    #[serde(rename = "exitCode")]
+    pub exit_code: Option<i32>,
+    /// Blender's combined stdout/stderr, with any traceback from the addon
+    pub log: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SyntaxCheckResult {
+    pub ok: bool,
+    /// "blender" or "python" - whose Python compiled the files
+    pub interpreter: String,
+    /// "path:line: message" for each file that doesn't compile
+    pub errors: Vec<String>,
+    pub log: String,
+}
+
+/// Keep the payload to the UI bounded on addons with thousands of findings.
+const MAX_FINDINGS: usize = 500;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LintFinding {
+    pub file: String,
+    pub line: u32,
+    pub code: String,
+    pub message: String,
+    /// "error", "warning" or "info"
+    pub severity: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LintResult {
+    /// "ruff" or "flake8", or None when neither could be found
+    pub linter: Option<String>,
+    pub findings: Vec<LintFinding>,
+    /// Findings at or above the requested severity, before the cap
+    pub total: usize,
+    pub truncated: bool,
+    pub message: Option<String>,
+}
+
+enum TestEntry {
+    Script(PathBuf),
+    Pytest(PathBuf),
+}
+
+#[tauri::command]
+pub async fn run_addon_tests(
+    blender_version: String,
+    addon_id: Option<String>,
+    app: AppHandle,
+    ops: State<'_, OperationState>,
+) -> Result<TestRunResult, String> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    let guard = ops.begin("run_addon_tests", &blender_version)?.cancellable();
+    tokio::task::spawn_blocking(move || {
+        let result = run_tests(&addon, &blender_version, &app, &guard);
+        guard.finish(&result);
+        result
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+fn run_tests(
+    addon: &Addon,
+    blender_version: &str,
+    app: &AppHandle,
+    guard: &OperationGuard,
+) -> Result<TestRunResult, String> {
+    let addon_path = addon_dir(addon, blender_version)?;
+    if !addon_path.exists() {
+        return Err("No installation found".to_string());
+    }
+
+    let Some(entry) = find_test_entry(&addon_path) else {
+        return Ok(TestRunResult {
+            status: "no_tests".to_string(),
+            passed: 0,
+            failed: 0,
+            skipped: 0,
+            exit_code: None,
+            log: String::new(),
+        });
+    };
+
+    let blender = blender_for_version(blender_version)?;
+    let mut cmd = hidden_command(&blender);
+    cmd.args(["--background", "--python-exit-code", "1"]);
+    match &entry {
+        TestEntry::Script(script) => {
+            cmd.arg("--python").arg(script);
+        }
+        TestEntry::Pytest(target) => {
+            let expr = format!("{}import pytest\nsys.exit(pytest.main([args[0], '-q']))\n", SCRIPT_ARGS);
+            cmd.args(["--python-expr", &expr, "--"]).arg(target);
+        }
+    }
+    cmd.current_dir(&addon_path);
+
+    let run = run_streaming(cmd, app, "serpens://test-output", guard, TEST_TIMEOUT)?;
+    let (passed, failed, skipped) = parse_test_counts(&run.log);
+    let status = if run.cancelled {
+        "cancelled"
+    } else if run.timed_out {
+        "timed_out"
+    } else if run.exit_code == Some(0) && failed == 0 {
+        "passed"
+    } else {
+        "failed"
+    };
+
+    Ok(TestRunResult {
+        status: status.to_string(),
+        passed,
+        failed,
+        skipped,
+        exit_code: run.exit_code,
+        log: run.log,
+    })
+}
+
+/// Start the matching Blender headless and enable the installed addon, to catch branches
+/// that import fine but break in register(). Slow - it boots a whole Blender, typically
+/// 5-30 seconds - so it only runs on request and never as part of a switch.
+#[tauri::command]
+pub async fn verify_loads(
+    blender_version: String,
+    addon_id: Option<String>,
+    app: AppHandle,
+    ops: State<'_, OperationState>,
+) -> Result<LoadCheckResult, String> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    let guard = ops.begin("verify_loads", &blender_version)?.cancellable();
+    tokio::task::spawn_blocking(move || {
+        let result = load_check(&addon, &blender_version, &app, &guard);
+        guard.finish(&result);
+        result
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// `verify_loads` inside an operation that's already running
+pub fn load_check(
+    addon: &Addon,
+    blender_version: &str,
+    app: &AppHandle,
+    guard: &OperationGuard,
+) -> Result<LoadCheckResult, String> {
+    let addon_path = addon_dir(addon, blender_version)?;
+    if !addon_path.exists() {
+        return Err("No installation found".to_string());
+    }
+    let blender = blender_for_version(blender_version)?;
+
+    // handle_error collects instead of raising, since addon_utils swallows exceptions
+    // from it; --factory-startup keeps the user's other addons out of the picture
+    let expr = format!(
+        "{args}\
+         import traceback, addon_utils\n\
+         module, tag = args\n\
+         errors = []\n\
+         def handle(ex):\n    traceback.print_exception(type(ex), ex, ex.__traceback__)\n    errors.append(ex)\n\
+         mod = addon_utils.enable(module, default_set=False, handle_error=handle)\n\
+         ok = mod is not None and not errors\n\
+         print(tag, 'loaded' if ok else 'failed', flush=True)\n\
+         sys.exit(0 if ok else 1)\n",
+        args = SCRIPT_ARGS,
+    );
+    let mut cmd = hidden_command(&blender);
+    cmd.args(["--background", "--factory-startup", "--python-exit-code", "1", "--python-expr", &expr]);
+    cmd.args(["--", &addon.folder_name, VERIFY_TAG]);
+
+    let run = run_streaming(cmd, app, "serpens://verify-output", guard, VERIFY_TIMEOUT)?;
+    let verdict = run.log.lines().rev().find_map(|l| l.trim().strip_prefix(VERIFY_TAG)).map(str::trim);
+    let status = if run.cancelled {
+        "cancelled"
+    } else if run.timed_out {
+        "timed_out"
+    } else if verdict == Some("loaded") && run.exit_code == Some(0) {
+        "loaded"
+    } else {
+        "failed"
+    };
+
+    Ok(LoadCheckResult {
+        status: status.to_string(),
+        loaded: status == "loaded",
+        exit_code: run.exit_code,
+        log: run.log,
+    })
+}
+
+/// Compile every .py file in the install without running or caching anything. Uses the
+/// matching Blender's Python so newer syntax is judged by the version that will load
+/// it, falling back to a Python on PATH. None when neither is available.
+pub fn syntax_check(
+    addon: &Addon,
+    blender_version: &str,
+    app: &AppHandle,
+    guard: &OperationGuard,
+) -> Result<Option<SyntaxCheckResult>, String> {
+    let addon_path = addon_dir(addon, blender_version)?;
+    let script = format!(
+        "{args}\
+         import os\n\
+         root, tag = args\n\
+         bad = 0\n\
+         for base, dirs, files in os.walk(root):\n    \
+             dirs[:] = [d for d in dirs if d not in ('.git', '__pycache__')]\n    \
+             for name in files:\n        \
+                 if not name.endswith('.py'):\n            continue\n        \
+                 path = os.path.join(base, name)\n        \
+                 try:\n            \
+                     with open(path, 'rb') as f:\n                compile(f.read(), path, 'exec')\n        \
+                 except (SyntaxError, ValueError) as e:\n            \
+                     bad += 1\n            \
+                     print(tag, '%s:%s: %s' % (os.path.relpath(path, root), getattr(e, 'lineno', 0) or 0, getattr(e, 'msg', e)), flush=True)\n\
+         sys.exit(1 if bad else 0)\n",
+        args = SCRIPT_ARGS,
+    );
+    let (interpreter, mut cmd) = match blender_for_version(blender_version) {
+        Ok(blender) => {
+            let mut cmd = hidden_command(&blender);
+            cmd.args(["--background", "--factory-startup", "--python-exit-code", "1", "--python-expr", &script]);
+            ("blender", cmd)
+        }
+        Err(_) => match find_python() {
+            Some(python) => {
+                let mut cmd = hidden_command(&python);
+                cmd.args(["-c", &script]);
+                ("python", cmd)
+            }
+            None => return Ok(None),
+        },
+    };
+    cmd.arg("--").arg(&addon_path).arg(SYNTAX_TAG);
+    cmd.stdin(Stdio::null());
+
+    let run = run_streaming(cmd, app, "serpens://verify-output", guard, VERIFY_TIMEOUT)?;
+    let errors: Vec<String> = run
+        .log
+        .lines()
+        .filter_map(|l| l.trim().strip_prefix(SYNTAX_TAG))
+        .map(|l| l.trim().to_string())
+        .collect();
+    Ok(Some(SyntaxCheckResult {
+        ok: errors.is_empty() && run.exit_code == Some(0),
+        interpreter: interpreter.to_string(),
+        errors,
+        log: run.log,
+    }))
+}
+
+/// A Python 3 on PATH that actually runs - Windows' Store alias doesn't
+fn find_python() -> Option<String> {
+    ["python3", "python"].into_iter().map(str::to_string).find(|python| {
+        hidden_command(python)
+            .arg("--version")
+            .stdin(Stdio::null())
+            .output()
+            .is_ok_and(|o| o.status.success() && String::from_utf8_lossy(&o.stdout).starts_with("Python 3"))
+    })
+}
+
+#[tauri::command]
+pub async fn lint_addon(
+    blender_version: String,
+    min_severity: Option<String>,
+    addon_id: Option<String>,
+) -> Result<LintResult, String> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    tokio::task::spawn_blocking(move || {
+        let addon_path = addon_dir(&addon, &blender_version)?;
+        if !addon_path.exists() {
+            return Err("No installation found".to_string());
+        }
+
+        let configured = load_settings().map(|s| s.linter_path).unwrap_or_default();
+        let Some((kind, exe)) = find_linter(&configured) else {
+            return Ok(LintResult {
+                linter: None,
+                findings: Vec::new(),
+                total: 0,
+                truncated: false,
+                message: Some(
+                    "No linter found. Install ruff (pip install ruff) or flake8, or set its location in Settings."
+                        .to_string(),
+                ),
+            });
+        };
+
+        let mut cmd = hidden_command(&exe);
+        cmd.current_dir(&addon_path);
+        if kind == "ruff" {
+            cmd.args(["check", "--output-format", "json", "--exit-zero"]);
+            if let Some(config) = first_existing(&addon_path, &["ruff.toml", ".ruff.toml"]) {
+                cmd.arg("--config").arg(config);
+            }
+        } else {
+            cmd.args(["--exit-zero", "--format=%(path)s|%(row)d|%(code)s|%(text)s"]);
+            if let Some(config) = first_existing(&addon_path, &[".flake8", "setup.cfg", "tox.ini"]) {
+                cmd.arg("--config").arg(config);
+            }
+        }
+        cmd.arg(".");
+
+        let output = cmd.output().map_err(|e| format!("Failed to run {}: {}", kind, e))?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let findings = if kind == "ruff" { parse_ruff(&stdout, &addon_path)? } else { parse_flake8(&stdout) };
+        if findings.is_empty() && !output.status.success() {
+            return Err(format!("{} failed: {}", kind, String::from_utf8_lossy(&output.stderr).trim()));
+        }
+
+        let threshold = severity_rank(min_severity.as_deref().unwrap_or("info"));
+        let mut findings: Vec<LintFinding> =
+            findings.into_iter().filter(|f| severity_rank(&f.severity) >= threshold).collect();
+        let total = findings.len();
+        findings.truncate(MAX_FINDINGS);
+
+        Ok(LintResult {
+            linter: Some(kind.to_string()),
+            findings,
+            total,
+            truncated: total > MAX_FINDINGS,
+            message: None,
+        })
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// ruff is preferred over flake8. A configured path may be the executable itself or its folder.
+fn find_linter(configured: &str) -> Option<(&'static str, PathBuf)> {
+    let exe_names = |name: &str| [name.to_string(), format!("{}.exe", name)];
+
+    if !configured.is_empty() {
+        let path = PathBuf::from(configured);
+        if path.is_file() {
+            let stem = path.file_stem()?.to_string_lossy().to_lowercase();
+            let kind = if stem.contains("flake8") { "flake8" } else { "ruff" };
+            return Some((kind, path));
+        }
+        for kind in ["ruff", "flake8"] {
+            if let Some(exe) = exe_names(kind).iter().map(|n| path.join(n)).find(|p| p.is_file()) {
+                return Some((kind, exe));
+            }
+        }
+    }
+
+    let dirs: Vec<PathBuf> = std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).collect())
+        .unwrap_or_default();
+    for kind in ["ruff", "flake8"] {
+        for dir in &dirs {
+            if let Some(exe) = exe_names(kind).iter().map(|n| dir.join(n)).find(|p| p.is_file()) {
+                return Some((kind, exe));
+            }
+        }
+    }
+    None
+}
+
+fn first_existing(dir: &Path, names: &[&str]) -> Option<PathBuf> {
+    names.iter().map(|n| dir.join(n)).find(|p| p.is_file())
+}
+
+fn severity_rank(severity: &str) -> u8 {
+    match severity {
+        "error" => 2,
+        "warning" => 1,
+        _ => 0,
+    }
+}
+
+/// Syntax errors and undefined names break the addon at import time; the rest is style.
+fn severity_for(code: &str) -> &'static str {
+    const ERRORS: &[&str] = &["E9", "F63", "F7", "F82"];
+    if code.is_empty() || ERRORS.iter().any(|p| code.starts_with(p)) {
+        "error"
+    } else if code.starts_with('E') || code.starts_with('W') || code.starts_with('F') {
+        "warning"
+    } else {
+        "info"
+    }
+}
+
+fn parse_ruff(stdout: &str, addon_path: &Path) -> Result<Vec<LintFinding>, String> {
+    if stdout.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let items: Vec<serde_json::Value> =
+        serde_json::from_str(stdout).map_err(|e| format!("Failed to parse ruff output: {}", e))?;
+    Ok(items
+        .iter()
+        .map(|item| {
+            // ruff reports syntax errors with a null code
+            let code = item["code"].as_str().unwrap_or("").to_string();
+            let filename = item["filename"].as_str().unwrap_or("");
+            let file = Path::new(filename)
+                .strip_prefix(addon_path)
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_else(|_| filename.to_string());
+            LintFinding {
+                file,
+                line: item["location"]["row"].as_u64().unwrap_or(0) as u32,
+                severity: severity_for(&code).to_string(),
+                message: item["message"].as_str().unwrap_or("").to_string(),
+                code,
+            }
+        })
+        .collect())
+}
+
+fn parse_flake8(stdout: &str) -> Vec<LintFinding> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(4, '|');
+            let file = parts.next()?.trim_start_matches("./").trim_start_matches(".\\");
+            let line_no = parts.next()?.parse().ok()?;
+            let code = parts.next()?.to_string();
+            let message = parts.next()?.to_string();
+            Some(LintFinding {
+                file: file.to_string(),
+                line: line_no,
+                severity: severity_for(&code).to_string(),
+                code,
+                message,
+            })
+        })
+        .collect()
+}
+
+/// A run_tests.py script wins over pytest discovery, since it's what the branch author wrote for Blender.
+fn find_test_entry(addon_path: &Path) -> Option<TestEntry> {
+    for script in [addon_path.join("run_tests.py"), addon_path.join("tests").join("run_tests.py")] {
+        if script.is_file() {
+            return Some(TestEntry::Script(script));
+        }
+    }
+
+    let has_pytest_config = addon_path.join("pytest.ini").is_file()
+        || addon_path.join("conftest.py").is_file()
+        || addon_path.join("tests").join("conftest.py").is_file()
+        || file_contains(&addon_path.join("pyproject.toml"), "[tool.pytest")
+        || file_contains(&addon_path.join("setup.cfg"), "[tool:pytest]");
+    if has_pytest_config {
+        let tests = addon_path.join("tests");
+        let target = if tests.is_dir() { tests } else { addon_path.to_path_buf() };
+        return Some(TestEntry::Pytest(target));
+    }
+    None
+}
+
+fn file_contains(path: &Path, needle: &str) -> bool {
+    std::fs::read_to_string(path).map(|content| content.contains(needle)).unwrap_or(false)
+}
+
+/// Pull pass/fail/skip counts out of either a pytest summary
+/// ("2 failed, 10 passed, 1 skipped in 3.2s") or unittest output ("Ran 12 tests" / "FAILED (failures=2)").
+fn parse_test_counts(log: &str) -> (u32, u32, u32) {
+    let count_before = |line: &str, word: &str| -> u32 {
+        let words: Vec<&str> = line.split(|c: char| c.is_whitespace() || c == ',').collect();
+        words
+            .windows(2)
+            .filter(|w| w[1].starts_with(word))
+            .filter_map(|w| w[0].parse::<u32>().ok())
+            .sum()
+    };
+    let count_after = |line: &str, key: &str| -> u32 {
+        line.split(|c: char| c == '(' || c == ')' || c == ',' || c.is_whitespace())
+            .filter_map(|part| part.strip_prefix(key))
+            .filter_map(|n| n.parse::<u32>().ok())
+            .sum()
+    };
+
+    // pytest prints its summary last
+    if let Some(line) = log.lines().rev().find(|l| l.contains(" passed") || l.contains(" failed")) {
+        if line.contains(" in ") {
+            let failed = count_before(line, "failed") + count_before(line, "error");
+            return (count_before(line, "passed"), failed, count_before(line, "skipped"));
+        }
+    }
+
+    let ran = log
+        .lines()
+        .rev()
+        .find_map(|l| l.trim().strip_prefix("Ran ").and_then(|r| r.split_whitespace().next()))
+        .and_then(|n| n.parse::<u32>().ok());
+    if let Some(ran) = ran {
+        let result = log.lines().rev().find(|l| l.starts_with("FAILED") || l.starts_with("OK")).unwrap_or("");
+        let failed = count_after(result, "failures=") + count_after(result, "errors=");
+        let skipped = count_after(result, "skipped=");
+        return (ran.saturating_sub(failed + skipped), failed, skipped);
+    }
+
+    (0, 0, 0)
+}
+
+pub struct StreamedRun {
+    pub exit_code: Option<i32>,
+    pub log: String,
+    pub cancelled: bool,
+    pub timed_out: bool,
+}
+
+/// Run `cmd`, emitting every stdout/stderr line as an `OutputLine` event while collecting
+/// the combined log. Kills the child when the operation is cancelled or `timeout` passes.
+pub fn run_streaming(
+    mut cmd: std::process::Command,
+    app: &AppHandle,
+    event: &str,
+    guard: &OperationGuard,
+    timeout: Duration,
+) -> Result<StreamedRun, String> {
+    let mut child = cmd
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to start process: {}", e))?;
+
+    let log = Arc::new(Mutex::new(String::new()));
+    let mut readers = Vec::new();
+    let streams: [(&str, Option<Box<dyn Read + Send>>); 2] = [
+        ("stdout", child.stdout.take().map(|s| Box::new(s) as Box<dyn Read + Send>)),
+        ("stderr", child.stderr.take().map(|s| Box::new(s) as Box<dyn Read + Send>)),
+    ];
+    for (name, stream) in streams {
+        let Some(stream) = stream else { continue };
+        let (app, event, log, name) = (app.clone(), event.to_string(), log.clone(), name.to_string());
+        readers.push(thread::spawn(move || {
+            for line in BufReader::new(stream).lines().map_while(Result::ok) {
+                {
+                    let mut log = log.lock().unwrap_or_else(|e| e.into_inner());
+                    log.push_str(&line);
+                    log.push('\n');
+                }
+                app.emit(&event, OutputLine { stream: name.clone(), line }).ok();
+            }
+        }));
+    }
+
+    let started = Instant::now();
+    let (mut cancelled, mut timed_out) = (false, false);
+    let status = loop {
+        if let Some(status) = child.try_wait().map_err(|e| format!("Failed to wait for process: {}", e))? {
+            break Some(status);
+        }
+        if guard.cancelled() {
+            cancelled = true;
+        } else if started.elapsed() > timeout {
+            timed_out = true;
+        }
+        if cancelled || timed_out {
+            child.kill().ok();
+            child.wait().ok();
+            break None;
+        }
+        thread::sleep(Duration::from_millis(100));
+    };
+
+    for reader in readers {
+        reader.join().ok();
+    }
+    let log = log.lock().unwrap_or_else(|e| e.into_inner()).clone();
+
+    Ok(StreamedRun { exit_code: status.and_then(|s| s.code()), log, cancelled, timed_out })
+}
@@ -0,0 +1,255 @@
+// Headless mode - run the everyday operations from a script without opening a window
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::addons::Addon;
+use crate::ops::{OperationState, Progress};
+use crate::updates::fetch_update_status;
+use crate::{
+    addon_dir, create_backup, git_addon_dir, git_pull, install_branch, installation_status, load_settings,
+    refuse_unpushed, SwitchError,
+};
+
+const USAGE: &str = "\
+Usage: serpens-dev-manager [options]
+
+  --backup                 Back up the current install
+  --switch <branch>        Install a fresh clone of <branch>
+  --force                  Switch even if the install has commits no remote has
+  --pull                   Pull the latest commits into the install
+  --check                  Report the install and whether updates are available
+  --blender-version <v>    Blender version to act on (defaults to the saved setting)
+  --json                   Print results as JSON
+
+Actions run in the order backup, switch, pull, check.
+
+Exit codes: 0 success, 1 operation failed, 2 bad arguments,
+            3 nothing installed (--check), 4 updates available (--check)";
+
+const EXIT_FAILED: i32 = 1;
+const EXIT_USAGE: i32 = 2;
+const EXIT_NOT_INSTALLED: i32 = 3;
+const EXIT_UPDATES: i32 = 4;
+
+#[derive(Default)]
+struct Args {
+    backup: bool,
+    switch: Option<String>,
+    force: bool,
+    pull: bool,
+    check: bool,
+    blender_version: Option<String>,
+    json: bool,
+}
+
+impl Args {
+    fn has_action(&self) -> bool {
+        self.backup || self.switch.is_some() || self.pull || self.check
+    }
+}
+
+/// Run headless if the command line asks for it. Returns the process exit code, or
+/// `None` when the GUI should start as usual.
+pub fn run() -> Option<i32> {
+    let raw: Vec<String> = std::env::args().skip(1).collect();
+    // No arguments (or only the ones the startup entry passes) means a normal launch
+    if raw.iter().all(|a| a == crate::autostart::MINIMIZED_ARG) {
+        return None;
+    }
+    // A clicked serpens-dev:// link launches us with the URL as the only argument
+    if let [url] = raw.as_slice() {
+        if url.starts_with(&format!("{}:", crate::deeplink::SCHEME)) {
+            return None;
+        }
+    }
+
+    #[cfg(windows)]
+    attach_console();
+
+    if raw.iter().any(|a| a == "--help" || a == "-h") {
+        println!("{}", USAGE);
+        return Some(0);
+    }
+    let args = match parse(&raw) {
+        Ok(args) if args.has_action() => args,
+        Ok(_) => {
+            eprintln!("No action given\n\n{}", USAGE);
+            return Some(EXIT_USAGE);
+        }
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return Some(EXIT_USAGE);
+        }
+    };
+
+    let blender_version = match args.blender_version.clone() {
+        Some(v) => v,
+        None => match load_settings() {
+            Ok(settings) => settings.blender_version,
+            Err(e) => return Some(fail(&args, "settings", &e)),
+        },
+    };
+
+    // The command line only knows Serpens
+    let addon = &Addon::builtin();
+    // This process's own lock, so each action is begun, finished and logged as in the window
+    let ops = OperationState::default();
+    let mut results = Map::new();
+    let mut code = 0;
+
+    if args.backup {
+        match step(&ops, "backup_installation", &blender_version, |progress| {
+            create_backup(addon, &blender_version, progress)
+        }) {
+            Ok(path) => report(&args, &mut results, "backup", &path, &format!("Backup: {}", path)),
+            Err(e) => return Some(fail(&args, "backup", &e)),
+        }
+    }
+    if let Some(branch) = &args.switch {
+        // The same refusal as a switch from the window, before anything is deleted
+        let refused = addon_dir(addon, &blender_version)
+            .map_err(SwitchError::from)
+            .and_then(|addon_path| refuse_unpushed(&addon_path, args.force));
+        if let Err(refusal) = refused {
+            return Some(refuse(&args, &refusal));
+        }
+        match step(&ops, "switch_branch", &blender_version, |progress| {
+            install_branch(addon, branch, &blender_version, progress)
+        }) {
+            Ok(()) => report(&args, &mut results, "switch", branch, &format!("Switched to {}", branch)),
+            Err(e) => return Some(fail(&args, "switch", &e)),
+        }
+    }
+    if args.pull {
+        match step(&ops, "pull_latest", &blender_version, |progress| git_pull(addon, &blender_version, progress)) {
+            Ok(()) => report(&args, &mut results, "pull", &true, "Pulled latest"),
+            Err(e) => return Some(fail(&args, "pull", &e)),
+        }
+    }
+    if args.check {
+        let status = match installation_status(addon, blender_version.clone()) {
+            Ok(status) => status,
+            Err(e) => return Some(fail(&args, "check", &e)),
+        };
+        let summary = match (&status.branch, &status.last_updated) {
+            _ if !status.installed => "Not installed".to_string(),
+            (Some(branch), Some(updated)) => format!("Installed: {} (last commit {})", branch, updated),
+            (Some(branch), None) => format!("Installed: {}", branch),
+            _ => "Installed (not a git clone)".to_string(),
+        };
+        report(&args, &mut results, "installation", &status, &summary);
+
+        if !status.installed {
+            code = EXIT_NOT_INSTALLED;
+        } else if let Ok(addon_path) = git_addon_dir(addon, &blender_version) {
+            match fetch_update_status(&addon_path, None) {
+                Ok(update) => {
+                    let summary = if update.update_available {
+                        format!("{} update(s) available on {}", update.pending, update.branch)
+                    } else {
+                        "Up to date".to_string()
+                    };
+                    if update.update_available {
+                        code = EXIT_UPDATES;
+                    }
+                    report(&args, &mut results, "updates", &update, &summary);
+                }
+                Err(e) => return Some(fail(&args, "check", &e)),
+            }
+        }
+    }
+
+    if args.json {
+        println!("{}", Value::Object(results));
+    }
+    Some(code)
+}
+
+fn parse(raw: &[String]) -> Result<Args, String> {
+    let mut args = Args::default();
+    let mut iter = raw.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--backup" => args.backup = true,
+            "--pull" => args.pull = true,
+            "--check" => args.check = true,
+            "--json" => args.json = true,
+            "--force" => args.force = true,
+            "--switch" => args.switch = Some(iter.next().ok_or("--switch needs a branch name")?.clone()),
+            "--blender-version" => {
+                args.blender_version = Some(iter.next().ok_or("--blender-version needs a version")?.clone())
+            }
+            a if a == crate::autostart::MINIMIZED_ARG => {}
+            other => return Err(format!("Unknown argument: {}", other)),
+        }
+    }
+    Ok(args)
+}
+
+/// Run one action under the operation lock
+fn step<T>(
+    ops: &OperationState,
+    kind: &str,
+    blender_version: &str,
+    run: impl FnOnce(&Progress) -> Result<T, String>,
+) -> Result<T, String> {
+    let guard = ops.begin(kind, blender_version)?;
+    let result = run(&guard.progress());
+    guard.finish(&result);
+    result
+}
+
+/// Collect a result for `--json`, or print its one-line summary
+fn report<T: Serialize>(args: &Args, results: &mut Map<String, Value>, key: &str, value: &T, summary: &str) {
+    if args.json {
+        results.insert(key.to_string(), serde_json::to_value(value).unwrap_or(Value::Null));
+    } else {
+        println!("{}", summary);
+    }
+}
+
+/// Print the error the GUI would have received for the failing step
+fn fail(args: &Args, step: &str, error: &str) -> i32 {
+    if args.json {
+        eprintln!("{}", serde_json::json!({ "step": step, "error": error }));
+    } else {
+        eprintln!("{} failed: {}", step, error);
+    }
+    EXIT_FAILED
+}
+
+/// A switch refused for unpushed commits: what they are, and how to keep them
+fn refuse(args: &Args, refusal: &SwitchError) -> i32 {
+    if args.json {
+        eprintln!("{}", serde_json::json!({ "step": "switch", "error": refusal }));
+        return EXIT_FAILED;
+    }
+    eprintln!("switch refused: {}", refusal.message);
+    for commit in &refusal.commits {
+        eprintln!("  {}", commit);
+    }
+    if !refusal.suggestions.is_empty() {
+        eprintln!("Keep them first with one of:");
+        for suggestion in &refusal.suggestions {
+            eprintln!("  {}", suggestion);
+        }
+    }
+    eprintln!("or run again with --force to switch anyway");
+    EXIT_FAILED
+}
+
+/// Release builds use the GUI subsystem, so stdout/stderr go nowhere unless we borrow
+/// the console of whatever shell started us.
+#[cfg(windows)]
+fn attach_console() {
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+    // Not fatal - a scheduler with redirected handles works without a console
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
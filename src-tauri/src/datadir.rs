@@ -0,0 +1,189 @@
+// Moving the manager's data folder (settings, logs, backups) off the system drive. The
+// default folder keeps a small pointer file saying where the data went.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+use crate::ops::{OperationState, Progress};
+use crate::{applog, copy_dir_all, default_data_dir, dir_size, remove_dir_robust, settingsdir, CopyOptions};
+
+/// In the default folder, naming the real one; the only file left there after a move
+const POINTER_FILE: &str = "location.json";
+/// Room to spare on the new disk beyond the data itself
+const SPACE_MARGIN: u64 = 64 * 1024 * 1024;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Pointer {
+    #[serde(rename = "dataDir")]
+    data_dir: PathBuf,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Relocation {
+    pub from: String,
+    pub to: String,
+    pub bytes: u64,
+    /// The old folder couldn't be fully removed; the data is safely in the new one
+    pub leftover: Option<String>,
+}
+
+/// Where the data lives: `default`, unless its pointer file says otherwise
+pub fn resolve(default: PathBuf) -> Result<PathBuf, String> {
+    let Ok(content) = fs::read_to_string(default.join(POINTER_FILE)) else { return Ok(default) };
+    let pointer: Pointer = serde_json::from_str(&content)
+        .map_err(|e| format!("Invalid {} in {}: {}", POINTER_FILE, default.display(), e))?;
+    // A missing folder on a present drive is recreated; a missing drive isn't
+    if pointer.data_dir.parent().is_some_and(|parent| !parent.exists()) {
+        return Err(format!(
+            "The data folder {} isn't available - reconnect its drive, or delete {} to go back to the default",
+            pointer.data_dir.display(),
+            default.join(POINTER_FILE).display()
+        ));
+    }
+    Ok(pointer.data_dir)
+}
+
+/// Move settings, logs and backups to `new_path`, which must be empty or not exist yet.
+/// The default location is accepted too, to move the data back.
+#[tauri::command]
+pub async fn relocate_data_dir(new_path: String, ops: State<'_, OperationState>) -> Result<Relocation, String> {
+    let target = PathBuf::from(new_path.trim());
+    if !target.is_absolute() {
+        return Err(format!("'{}' isn't a full path", target.display()));
+    }
+    // Nothing else may write to the data folder while it's copied
+    let guard = ops.begin("relocate_data_dir", "")?;
+    tokio::task::spawn_blocking(move || {
+        let result = relocate(&target, &guard.progress());
+        guard.finish(&result);
+        result
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+fn relocate(target: &Path, progress: &Progress) -> Result<Relocation, String> {
+    progress.phase("validating");
+    let default = default_data_dir()?;
+    let current = resolve(default.clone())?;
+    let target = normalize(target);
+    if same_path(&target, &current) {
+        return Err(format!("The data is already in {}", current.display()));
+    }
+    if target.starts_with(&current) || (current.starts_with(&target) && !same_path(&target, &default)) {
+        return Err("The new folder can't be inside the current one, or the other way round".to_string());
+    }
+    let to_default = same_path(&target, &default);
+    if let Ok(entries) = fs::read_dir(&target) {
+        let occupied = entries.flatten().any(|e| !(to_default && e.file_name() == POINTER_FILE));
+        if occupied {
+            return Err(format!("{} isn't empty - pick an empty or new folder", target.display()));
+        }
+    }
+
+    fs::create_dir_all(&target).map_err(|e| format!("Failed to create {}: {}", target.display(), e))?;
+    let check = settingsdir::check(&target);
+    if let Some(error) = check.error.filter(|_| !check.writable) {
+        return Err(error);
+    }
+    let bytes = dir_size(&current);
+    let free = fs2::available_space(&target).map_err(|e| format!("Couldn't check free space: {}", e))?;
+    if free < bytes + SPACE_MARGIN {
+        return Err(format!(
+            "Not enough space on the new drive: the data needs {} MB and {} MB is free",
+            (bytes + SPACE_MARGIN) / (1024 * 1024),
+            free / (1024 * 1024)
+        ));
+    }
+
+    progress.phase("copying files");
+    let copied =
+        if current.exists() { copy_dir_all(&current, &target, CopyOptions::from_settings(), progress) } else { Ok(()) };
+    if let Err(e) = copied {
+        // Put the folder back the way it was found, pointer and all
+        if to_default {
+            clear_except_pointer(&target);
+        } else {
+            remove_dir_robust(&target).ok();
+        }
+        return Err(format!("Failed to copy the data: {}", e));
+    }
+    // The pointer only ever belongs in the default folder
+    fs::remove_file(target.join(POINTER_FILE)).ok();
+
+    progress.phase("switching");
+    if to_default {
+        fs::remove_file(default.join(POINTER_FILE)).map_err(|e| format!("Failed to remove {}: {}", POINTER_FILE, e))?;
+    } else {
+        write_pointer(&default, &target)?;
+    }
+
+    progress.phase("removing");
+    let leftover =
+        if same_path(&current, &default) { clear_except_pointer(&default) } else { remove_dir_robust(&current).err() };
+    applog::info(&format!("Moved the data folder from {} to {}", current.display(), target.display()));
+    if let Some(leftover) = &leftover {
+        applog::warn(leftover);
+    }
+    Ok(Relocation {
+        from: current.to_string_lossy().to_string(),
+        to: target.to_string_lossy().to_string(),
+        bytes,
+        leftover,
+    })
+}
+
+/// Written beside and then renamed over, so a crash leaves the old pointer or the new one
+fn write_pointer(default: &Path, target: &Path) -> Result<(), String> {
+    fs::create_dir_all(default).map_err(|e| format!("Failed to create {}: {}", default.display(), e))?;
+    let content = serde_json::to_string_pretty(&Pointer { data_dir: target.to_path_buf() })
+        .map_err(|e| format!("Failed to serialize {}: {}", POINTER_FILE, e))?;
+    let temp = default.join(format!("{}.tmp", POINTER_FILE));
+    fs::write(&temp, content).map_err(|e| format!("Failed to write {}: {}", POINTER_FILE, e))?;
+    fs::rename(&temp, default.join(POINTER_FILE)).map_err(|e| format!("Failed to write {}: {}", POINTER_FILE, e))
+}
+
+/// Empty the default folder apart from the pointer that now leads away from it
+fn clear_except_pointer(default: &Path) -> Option<String> {
+    let entries = fs::read_dir(default).ok()?;
+    let errors: Vec<String> = entries
+        .flatten()
+        .filter(|e| e.file_name() != POINTER_FILE)
+        .filter_map(|e| {
+            let path = e.path();
+            let removed = if e.file_type().is_ok_and(|t| t.is_dir()) {
+                remove_dir_robust(&path)
+            } else {
+                fs::remove_file(&path).map_err(|err| format!("Failed to remove {}: {}", path.display(), err))
+            };
+            removed.err()
+        })
+        .collect();
+    (!errors.is_empty()).then(|| errors.join("; "))
+}
+
+/// The path as entered, with "." and ".." resolved and any trailing separator dropped
+fn normalize(path: &Path) -> PathBuf {
+    let mut normal = PathBuf::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir => {
+                normal.pop();
+            }
+            other => normal.push(other),
+        }
+    }
+    normal
+}
+
+/// Windows paths compare without regard to case
+fn same_path(a: &Path, b: &Path) -> bool {
+    if cfg!(windows) {
+        a.to_string_lossy().to_lowercase() == b.to_string_lossy().to_lowercase()
+    } else {
+        a == b
+    }
+}
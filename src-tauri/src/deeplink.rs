@@ -0,0 +1,95 @@
+// serpens-dev:// links - one-click installs from a chat message or web page
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, State, Url};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+use crate::{applog, tray, validate};
+
+pub const SCHEME: &str = "serpens-dev";
+
+/// A validated install request, waiting for the user to confirm it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InstallLink {
+    pub branch: String,
+    /// Blender version to install for; the frontend falls back to the current setting
+    pub version: Option<String>,
+}
+
+/// The last link that arrived, kept for a frontend that wasn't listening yet (cold start)
+#[derive(Default)]
+pub struct DeepLinkState(pub Mutex<Option<InstallLink>>);
+
+#[tauri::command]
+pub fn take_pending_deep_link(state: State<'_, DeepLinkState>) -> Option<InstallLink> {
+    state.0.lock().unwrap_or_else(|e| e.into_inner()).take()
+}
+
+/// Hook up link handling for both a running app and a link that launched it.
+pub fn setup(app: &AppHandle) {
+    // Installers register the scheme; dev builds and unpacked AppImages have to do it themselves
+    if cfg!(debug_assertions) || cfg!(target_os = "linux") {
+        if let Err(e) = app.deep_link().register_all() {
+            applog::warn(&format!("Could not register {}:// links: {}", SCHEME, e));
+        }
+    }
+
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            handle_url(&handle, &url);
+        }
+    });
+
+    if let Ok(Some(urls)) = app.deep_link().get_current() {
+        for url in urls {
+            handle_url(app, &url);
+        }
+    }
+}
+
+fn handle_url(app: &AppHandle, url: &Url) {
+    match parse(url) {
+        Ok(link) => {
+            applog::info(&format!("Deep link install request: {}", url));
+            *app.state::<DeepLinkState>().0.lock().unwrap_or_else(|e| e.into_inner()) = Some(link.clone());
+            tray::show_window(app);
+            app.emit("serpens://deep-link-install", &link).ok();
+        }
+        Err(e) => {
+            applog::warn(&format!("Rejected deep link {}: {}", url, e));
+            app.emit("serpens://deep-link-rejected", &e).ok();
+        }
+    }
+}
+
+/// `serpens-dev://install?branch=<name>[&version=<x.y>]` - anything else is refused.
+pub fn parse(url: &Url) -> Result<InstallLink, String> {
+    if url.scheme() != SCHEME {
+        return Err(format!("unexpected scheme '{}'", url.scheme()));
+    }
+    if url.host_str() != Some("install") || !matches!(url.path(), "" | "/") {
+        return Err("unknown action".to_string());
+    }
+
+    let mut branch = None;
+    let mut version = None;
+    for (key, value) in url.query_pairs() {
+        let slot = match key.as_ref() {
+            "branch" => &mut branch,
+            "version" => &mut version,
+            other => return Err(format!("unknown parameter '{}'", other)),
+        };
+        if slot.replace(value.to_string()).is_some() {
+            return Err(format!("parameter '{}' given twice", key));
+        }
+    }
+
+    let branch = branch.ok_or("missing branch")?;
+    validate::branch_name(&branch)?;
+    if let Some(version) = &version {
+        validate::blender_version(version)?;
+    }
+    Ok(InstallLink { branch, version })
+}
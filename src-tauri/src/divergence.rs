@@ -0,0 +1,119 @@
+// How far each branch has drifted from the default branch, for the branch list
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::appupdate::github_api;
+use crate::{data_dir, git_output, gitbin, giturl, load_settings};
+
+/// (ahead, behind) by (default tip, branch tip). Tips pin the answer, so entries never
+/// go stale - a moved branch just looks up a new pair.
+#[derive(Default)]
+pub struct DivergenceCache(pub Mutex<HashMap<(String, String), (u32, u32)>>);
+
+/// The remote's default branch and its tip, from where its HEAD points
+pub fn default_branch(url: &str) -> Option<(String, String)> {
+    let output = gitbin::git_command().args(["ls-remote", "--symref", "--", url, "HEAD"]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let listing = String::from_utf8_lossy(&output.stdout);
+    let mut name = None;
+    let mut sha = None;
+    for line in listing.lines() {
+        if let Some(target) = line.strip_prefix("ref: ") {
+            name = target.split('\t').next().and_then(|r| r.strip_prefix("refs/heads/")).map(str::to_string);
+        } else if let Some((tip, "HEAD")) = line.split_once('\t') {
+            sha = Some(tip.to_string());
+        }
+    }
+    Some((name?, sha?))
+}
+
+/// Ahead/behind the default tip for every (branch, tip) in `heads`. With a GitHub token
+/// the compare API answers; otherwise the commits are fetched into a local cache repo
+/// and counted there. Anything that can't be worked out is left out.
+pub fn against_default(
+    url: &str,
+    default_sha: &str,
+    heads: &[(String, String)],
+    cache: &DivergenceCache,
+) -> HashMap<String, (u32, u32)> {
+    let mut known = cache.0.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    let missing: Vec<&str> = heads
+        .iter()
+        .map(|(_, sha)| sha.as_str())
+        .filter(|sha| !known.contains_key(&(default_sha.to_string(), sha.to_string())))
+        .collect();
+
+    if !missing.is_empty() {
+        let token = load_settings().map(|s| s.github_token).unwrap_or_default();
+        let found = match giturl::github_path(url) {
+            Some(repo) if !token.trim().is_empty() => via_compare_api(&repo, default_sha, &missing),
+            _ => via_cache_repo(url, default_sha, &missing),
+        };
+        let mut cached = cache.0.lock().unwrap_or_else(|e| e.into_inner());
+        for (sha, counts) in found {
+            cached.insert((default_sha.to_string(), sha.clone()), counts);
+            known.insert((default_sha.to_string(), sha), counts);
+        }
+    }
+
+    heads
+        .iter()
+        .filter_map(|(name, sha)| known.get(&(default_sha.to_string(), sha.clone())).map(|c| (name.clone(), *c)))
+        .collect()
+}
+
+fn via_compare_api(repo: &str, default_sha: &str, shas: &[&str]) -> Vec<(String, (u32, u32))> {
+    shas.iter()
+        .filter_map(|sha| {
+            let body = github_api(
+                &format!("https://api.github.com/repos/{}/compare/{}...{}", repo, default_sha, sha),
+                "Commit not found",
+            )
+            .ok()?;
+            let comparison: serde_json::Value = serde_json::from_str(&body).ok()?;
+            let ahead = comparison["ahead_by"].as_u64()?;
+            let behind = comparison["behind_by"].as_u64()?;
+            Some((sha.to_string(), (ahead as u32, behind as u32)))
+        })
+        .collect()
+}
+
+/// Commit history only (no trees or files), kept between runs so later fetches are small
+pub fn cache_repo() -> Result<PathBuf, String> {
+    let path = data_dir()?.join("branch-cache.git");
+    if !path.join("HEAD").exists() {
+        fs::create_dir_all(&path).map_err(|e| format!("Failed to create branch cache: {}", e))?;
+        git_output(&path, &["init", "--bare", "--quiet"])?;
+    }
+    Ok(path)
+}
+
+/// (only in sha, only in `base`) for each of `shas`, counted in the cache repo after
+/// fetching whichever commits it doesn't have yet from `url`
+pub fn via_cache_repo(url: &str, base: &str, shas: &[&str]) -> Vec<(String, (u32, u32))> {
+    let Ok(repo) = cache_repo() else { return Vec::new() };
+    let has = |sha: &str| git_output(&repo, &["cat-file", "-e", &format!("{}^{{commit}}", sha)]).is_ok();
+    let mut wanted: Vec<&str> = shas.iter().copied().filter(|sha| !has(sha)).collect();
+    if !has(base) {
+        wanted.push(base);
+    }
+    if !wanted.is_empty() {
+        let mut fetch = vec!["fetch", "--quiet", "--filter=tree:0", "--", url];
+        fetch.extend(&wanted);
+        // A failed fetch still leaves whatever was cached before to count against
+        git_output(&repo, &fetch).ok();
+    }
+    shas.iter()
+        .filter_map(|sha| {
+            let counts =
+                git_output(&repo, &["rev-list", "--left-right", "--count", &format!("{}...{}", sha, base)]).ok()?;
+            let mut parts = counts.split_whitespace().map(str::parse::<u32>);
+            Some((sha.to_string(), (parts.next()?.ok()?, parts.next()?.ok()?)))
+        })
+        .collect()
+}
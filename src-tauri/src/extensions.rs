@@ -0,0 +1,285 @@
+// Blender 4.2+ extensions: installs under extensions/<repo>/ instead of scripts/addons
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::State;
+
+use crate::addons::{Addon, DEFAULT_ADDON};
+use crate::blender::{blender_for_version, version_key};
+use crate::marker::{read_marker, InstallMarker};
+use crate::ops::{OperationState, Progress, Timed};
+use crate::{
+    addon_dir, addons, clone_install_at, default_clone_depth, hidden_command, load_settings, refuse_unpushed, userdirs,
+    validate, SwitchError,
+};
+
+/// The repository Blender installs local extensions into
+pub const DEFAULT_REPO: &str = "user_default";
+const MANIFEST_FILE: &str = "blender_manifest.toml";
+/// Marks the line with the repo list among Blender's startup output
+const REPOS_TAG: &str = "SERPENS_REPOS:";
+/// Blender with the user's preferences and add-ons can take a while to start
+const QUERY_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ExtensionRepo {
+    /// Blender's module name for the repo - "user_default", "blender_org", ...
+    pub id: String,
+    pub name: String,
+    /// Where its extensions live, including custom directories
+    pub path: String,
+    pub enabled: bool,
+    /// Synced from a remote URL; Blender manages what's in it
+    pub remote: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExtensionRepos {
+    pub repos: Vec<ExtensionRepo>,
+    /// "blender" when Blender listed its configured repos, "folders" when Blender couldn't
+    /// be run and the extensions folder was looked at instead (custom directories missed)
+    pub source: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExtensionInstall {
+    pub path: String,
+    /// The `id` from blender_manifest.toml - Blender names the folder after it
+    #[serde(rename = "manifestId")]
+    pub manifest_id: Option<String>,
+    /// Things that will stop Blender from loading it as intended
+    pub warnings: Vec<String>,
+}
+
+/// Blender 4.2 moved add-ons to the extensions system
+pub fn uses_extensions(blender_version: &str) -> bool {
+    version_key(blender_version) >= vec![4, 2]
+}
+
+/// The install in any repo under extensions/ (custom directories aren't searched, since
+/// finding those means starting Blender)
+pub fn find_installed(addon: &Addon, blender_version: &str) -> Option<PathBuf> {
+    if !uses_extensions(blender_version) {
+        return None;
+    }
+    let root = userdirs::extensions_dir(blender_version).ok()?;
+    fs::read_dir(root)
+        .ok()?
+        .flatten()
+        .filter(|repo| repo.path().is_dir())
+        .find_map(|repo| installed_in(addon, &repo.path()))
+}
+
+/// The install in one repo: a folder the manager marked as `addon`'s, or one
+/// under the usual name
+fn installed_in(addon: &Addon, repo_dir: &Path) -> Option<PathBuf> {
+    let ours = |marker: InstallMarker| marker.addon.as_deref().unwrap_or(DEFAULT_ADDON) == addon.id;
+    fs::read_dir(repo_dir).ok()?.flatten().map(|entry| entry.path()).find(|path| {
+        path.is_dir()
+            && (path.file_name().is_some_and(|n| *n == *addon.folder_name) || read_marker(path).is_some_and(ours))
+    })
+}
+
+/// The extension repositories a version has - asked of Blender itself, since they live
+/// in userpref.blend, falling back to the folders under extensions/
+#[tauri::command]
+pub async fn detect_extension_repos(blender_version: String) -> Result<ExtensionRepos, String> {
+    validate::blender_version(&blender_version)?;
+    if !uses_extensions(&blender_version) {
+        return Err(format!("Blender {} predates extensions (4.2+)", blender_version));
+    }
+    tokio::task::spawn_blocking(move || detect_repos(&blender_version))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+}
+
+pub fn detect_repos(blender_version: &str) -> Result<ExtensionRepos, String> {
+    if let Some(repos) = blender_for_version(blender_version).ok().and_then(|b| query_blender(&b)) {
+        return Ok(ExtensionRepos { repos, source: "blender".to_string() });
+    }
+    Ok(ExtensionRepos { repos: scan_folders(blender_version)?, source: "folders".to_string() })
+}
+
+fn query_blender(blender: &Path) -> Option<Vec<ExtensionRepo>> {
+    let expr = format!(
+        "import bpy, json\n\
+         repos = [dict(id=r.module, name=r.name, path=r.directory, enabled=r.enabled, remote=r.use_remote_url)\n\
+                  for r in bpy.context.preferences.extensions.repos]\n\
+         print({tag:?} + json.dumps(repos), flush=True)\n",
+        tag = REPOS_TAG,
+    );
+    let mut child = hidden_command(blender)
+        .args(["--background", "--python-expr", &expr])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    let mut pipe = child.stdout.take()?;
+    let reader = thread::spawn(move || {
+        let mut text = String::new();
+        pipe.read_to_string(&mut text).ok();
+        text
+    });
+    let started = Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(_)) => break,
+            Ok(None) if started.elapsed() < QUERY_TIMEOUT => thread::sleep(Duration::from_millis(200)),
+            _ => {
+                child.kill().ok();
+                child.wait().ok();
+                break;
+            }
+        }
+    }
+    let output = reader.join().ok()?;
+    let json = output.lines().find_map(|line| line.trim().strip_prefix(REPOS_TAG))?;
+    serde_json::from_str(json).ok()
+}
+
+/// Every folder under extensions/ is a repo named after it; Blender keeps its own data
+/// in the dot-folders
+fn scan_folders(blender_version: &str) -> Result<Vec<ExtensionRepo>, String> {
+    let root = userdirs::extensions_dir(blender_version)?;
+    let mut repos: Vec<ExtensionRepo> = fs::read_dir(&root)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.path().is_dir())
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .filter(|id| !id.starts_with('.'))
+                .map(|id| ExtensionRepo {
+                    name: id.clone(),
+                    path: root.join(&id).to_string_lossy().to_string(),
+                    enabled: true,
+                    remote: id == "blender_org",
+                    id,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    // Blender creates user_default on first use, so it counts even before it exists
+    if !repos.iter().any(|r| r.id == DEFAULT_REPO) {
+        repos.push(ExtensionRepo {
+            id: DEFAULT_REPO.to_string(),
+            name: "User Default".to_string(),
+            path: root.join(DEFAULT_REPO).to_string_lossy().to_string(),
+            enabled: true,
+            remote: false,
+        });
+    }
+    repos.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(repos)
+}
+
+/// Clone `branch` as an extension in `repo_id` (user_default by default), named the way
+/// Blender expects from its manifest. A reinstall over a clone with unpushed commits is
+/// refused unless `force` is set.
+#[tauri::command]
+pub async fn install_as_extension(
+    branch: String,
+    blender_version: String,
+    repo_id: Option<String>,
+    force: Option<bool>,
+    addon_id: Option<String>,
+    ops: State<'_, OperationState>,
+) -> Result<Timed<ExtensionInstall>, SwitchError> {
+    validate::branch_name(&branch)?;
+    validate::blender_version(&blender_version)?;
+    if !uses_extensions(&blender_version) {
+        return Err(format!("Blender {} predates extensions (4.2+) - install it as an add-on", blender_version).into());
+    }
+    let repo_id = repo_id.unwrap_or_else(|| DEFAULT_REPO.to_string());
+    if repo_id.is_empty() || !repo_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(format!("Invalid extensions repository '{}'", repo_id).into());
+    }
+
+    let addon = addons::resolve(addon_id.as_deref())?;
+    let guard = ops.begin("install_as_extension", &blender_version)?;
+    tokio::task::spawn_blocking(move || {
+        let result = install(&addon, &branch, &blender_version, &repo_id, force.unwrap_or(false), &guard.progress());
+        guard.finish(&result.as_ref().map_err(|e| e.message.clone()));
+        result.map(|result| guard.timed(result))
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+fn install(
+    addon: &Addon,
+    branch: &str,
+    blender_version: &str,
+    repo_id: &str,
+    force: bool,
+    progress: &Progress,
+) -> Result<ExtensionInstall, SwitchError> {
+    progress.phase("finding extension repo");
+    let repos = detect_repos(blender_version)?;
+    let repo = repos.repos.iter().find(|r| r.id == repo_id).ok_or_else(|| {
+        let known: Vec<&str> = repos.repos.iter().map(|r| r.id.as_str()).collect();
+        format!("No extension repository '{}' (this Blender has: {})", repo_id, known.join(", "))
+    })?;
+    if repo.remote {
+        return Err(format!(
+            "'{}' is synced from the internet and Blender manages its contents - pick a local repository",
+            repo.id
+        )
+        .into());
+    }
+    let repo_dir = PathBuf::from(&repo.path);
+    let depth = load_settings().map(|s| s.clone_depth).unwrap_or_else(|_| default_clone_depth());
+    // Reinstalls go where the last one ended up, which may carry the manifest's name
+    let mut path = installed_in(addon, &repo_dir).unwrap_or_else(|| repo_dir.join(&addon.folder_name));
+    refuse_unpushed(&path, force)?;
+    clone_install_at(addon, &path, &addon.url(), depth, branch, None, blender_version, progress)?;
+
+    let mut warnings = Vec::new();
+    let manifest_id = manifest_id(&path);
+    match &manifest_id {
+        None => warnings.push(format!(
+            "The branch has no {} - Blender won't list it as an extension; install it as an add-on instead",
+            MANIFEST_FILE
+        )),
+        Some(id) if !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') => {
+            warnings.push(format!("The manifest id '{}' isn't a valid folder name, so it was left alone", id));
+        }
+        Some(id) if path.file_name().is_some_and(|name| name != id.as_str()) => {
+            let named = repo_dir.join(id);
+            if named.exists() {
+                warnings.push(format!("{} already exists, so the install keeps its current name", named.display()));
+            } else {
+                progress.phase("renaming");
+                fs::rename(&path, &named).map_err(|e| format!("Failed to rename to {}: {}", id, e))?;
+                path = named;
+            }
+        }
+        Some(_) => {}
+    }
+    if !repo.enabled {
+        warnings.push(format!("The '{}' repository is disabled in Blender's preferences", repo.id));
+    }
+    if addon_dir(addon, blender_version).is_ok_and(|legacy| legacy.exists()) {
+        warnings.push(
+            "The add-on is also installed in scripts/addons - remove one so Blender doesn't load both".to_string(),
+        );
+    }
+    Ok(ExtensionInstall { path: path.to_string_lossy().to_string(), manifest_id, warnings })
+}
+
+/// `id = "..."` from the manifest's top level
+fn manifest_id(path: &Path) -> Option<String> {
+    let manifest = fs::read_to_string(path.join(MANIFEST_FILE)).ok()?;
+    manifest
+        .lines()
+        .take_while(|line| !line.trim_start().starts_with('['))
+        .filter_map(|line| line.split_once('='))
+        .find(|(key, _)| key.trim() == "id")
+        .map(|(_, value)| value.trim().trim_matches('"').trim_matches('\'').to_string())
+        .filter(|id| !id.is_empty())
+}
@@ -0,0 +1,127 @@
+// Cheap change detection for installs without relying on git
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use crate::backups::META_FILE;
+use crate::marker::MARKER_FILE;
+use crate::{addon_dir, addons};
+
+/// Per-file SHA-256 of how an install looked when the manager put it there, for installs
+/// that have no git index to check against
+pub const MANIFEST_FILE: &str = ".serpens_manifest.json";
+
+/// Our own bookkeeping files, which never count as part of the addon
+pub fn is_manager_file(name: &str) -> bool {
+    name == MARKER_FILE || name == MANIFEST_FILE || name == META_FILE
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Fingerprint {
+    pub hash: String,
+    #[serde(rename = "fileCount")]
+    pub file_count: u64,
+    #[serde(rename = "totalSize")]
+    pub total_size: u64,
+}
+
+#[tauri::command]
+pub async fn install_fingerprint(blender_version: String, addon_id: Option<String>) -> Result<Fingerprint, String> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    tokio::task::spawn_blocking(move || {
+        let addon_path = addon_dir(&addon, &blender_version)?;
+        if !addon_path.exists() {
+            return Err("No installation found".to_string());
+        }
+        fingerprint(&addon_path).map_err(|e| format!("Failed to fingerprint install: {}", e))
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Merkle-style hash over relative path, size and mtime of every file. Git metadata,
+/// bytecode caches and our own marker are skipped since they change without the addon changing.
+pub fn fingerprint(root: &Path) -> io::Result<Fingerprint> {
+    let mut stats = Fingerprint { hash: String::new(), file_count: 0, total_size: 0 };
+    let digest = hash_dir(root, "", &mut stats)?;
+    stats.hash = to_hex(&digest);
+    Ok(stats)
+}
+
+fn hash_dir(dir: &Path, rel: &str, stats: &mut Fingerprint) -> io::Result<Vec<u8>> {
+    let mut entries: Vec<_> = fs::read_dir(dir)?.collect::<Result<_, _>>()?;
+    entries.sort_by_key(|e| e.file_name());
+
+    let mut hasher = Sha256::new();
+    for entry in entries {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name == ".git" || name == "__pycache__" || (rel.is_empty() && is_manager_file(&name)) {
+            continue;
+        }
+        let child_rel = if rel.is_empty() { name.clone() } else { format!("{}/{}", rel, name) };
+        let meta = entry.metadata()?;
+        let child = if meta.is_dir() {
+            hash_dir(&entry.path(), &child_rel, stats)?
+        } else {
+            stats.file_count += 1;
+            stats.total_size += meta.len();
+            let mtime = meta
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_nanos())
+                .unwrap_or(0);
+            Sha256::digest(format!("{}\0{}\0{}", child_rel, meta.len(), mtime)).to_vec()
+        };
+        hasher.update(name.as_bytes());
+        hasher.update(&child);
+    }
+    Ok(hasher.finalize().to_vec())
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Content hash of every addon file under `root`, keyed by "/"-separated relative path
+pub fn file_hashes(root: &Path) -> io::Result<BTreeMap<String, String>> {
+    let mut hashes = BTreeMap::new();
+    collect_hashes(root, "", &mut hashes)?;
+    Ok(hashes)
+}
+
+fn collect_hashes(dir: &Path, rel: &str, hashes: &mut BTreeMap<String, String>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name == ".git" || name == "__pycache__" || (rel.is_empty() && is_manager_file(&name)) {
+            continue;
+        }
+        let child_rel = if rel.is_empty() { name } else { format!("{}/{}", rel, name) };
+        if entry.file_type()?.is_dir() {
+            collect_hashes(&entry.path(), &child_rel, hashes)?;
+        } else {
+            let mut hasher = Sha256::new();
+            io::copy(&mut File::open(entry.path())?, &mut hasher)?;
+            hashes.insert(child_rel, to_hex(&hasher.finalize()));
+        }
+    }
+    Ok(())
+}
+
+/// Record the install's file hashes so `verify_installation` can check it later
+pub fn write_manifest(root: &Path) -> Result<(), String> {
+    let hashes = file_hashes(root).map_err(|e| format!("Failed to hash installed files: {}", e))?;
+    let content = serde_json::to_string(&hashes).map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+    fs::write(root.join(MANIFEST_FILE), content).map_err(|e| format!("Failed to write manifest: {}", e))
+}
+
+pub fn read_manifest(root: &Path) -> Option<BTreeMap<String, String>> {
+    let content = fs::read_to_string(root.join(MANIFEST_FILE)).ok()?;
+    serde_json::from_str(&content).ok()
+}
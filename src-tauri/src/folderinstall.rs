@@ -0,0 +1,122 @@
+// Installing the addon from an unpacked folder, e.g. a build on a USB stick
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+use crate::addons::Addon;
+use crate::archive::read_bl_info_version;
+use crate::fingerprint;
+use crate::marker::{read_marker, write_marker, InstallMarker};
+use crate::ops::{OperationState, Progress};
+use crate::{
+    addon_dir, addons, addons_dir, copy_dir_except, git_output, pre_switch_backup, refuse_unpushed, swap_into_place,
+    CopyOptions, StagingDir, SwitchError,
+};
+
+/// Left behind rather than copied: bytecode Blender regenerates anyway, and git history,
+/// which would make a copied folder look like a checkout the manager can pull
+const SKIPPED: &[&str] = &["__pycache__", ".git"];
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FolderAddon {
+    /// The folder holding `__init__.py` - the one picked, or its single addon subfolder
+    #[serde(rename = "addonPath")]
+    pub addon_path: String,
+    pub version: Option<String>,
+}
+
+/// Find the addon in `source`: an `__init__.py` right there, or in exactly one of its
+/// subfolders.
+pub fn inspect(source: &Path) -> Result<FolderAddon, String> {
+    if !source.is_dir() {
+        return Err(format!("{} is not a folder", source.display()));
+    }
+    let root = if source.join("__init__.py").is_file() {
+        source.to_path_buf()
+    } else {
+        let candidates: Vec<PathBuf> = fs::read_dir(source)
+            .map_err(|e| format!("Failed to read {}: {}", source.display(), e))?
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir() && path.join("__init__.py").is_file())
+            .collect();
+        match candidates.as_slice() {
+            [one] => one.clone(),
+            [] => return Err("No __init__.py found - this doesn't look like a Blender addon".to_string()),
+            _ => return Err("This folder holds several addons - pick the one to install".to_string()),
+        }
+    };
+    Ok(FolderAddon {
+        addon_path: root.to_string_lossy().to_string(),
+        version: read_bl_info_version(&root),
+    })
+}
+
+/// Replace the install with a copy of a local folder, refused like a switch when that would
+/// lose unpushed commits
+#[tauri::command]
+pub fn install_from_folder(
+    blender_version: String,
+    source_path: String,
+    force: Option<bool>,
+    addon_id: Option<String>,
+    ops: State<'_, OperationState>,
+) -> Result<bool, SwitchError> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    let guard = ops.begin("install_from_folder", &blender_version)?;
+    let result = addon_dir(&addon, &blender_version)
+        .map_err(SwitchError::from)
+        .and_then(|addon_path| refuse_unpushed(&addon_path, force.unwrap_or(false)))
+        .and_then(|_| Ok(install_folder(&addon, &PathBuf::from(source_path), &blender_version, &guard.progress())?));
+    guard.finish(&result.as_ref().map_err(|e| e.message.clone()));
+    result.map(|_| true)
+}
+
+fn install_folder(addon: &Addon, source: &Path, blender_version: &str, progress: &Progress) -> Result<(), String> {
+    progress.phase("checking folder");
+    let found = inspect(source)?;
+    let root =
+        fs::canonicalize(&found.addon_path).map_err(|e| format!("Failed to resolve {}: {}", source.display(), e))?;
+
+    let addons_path = addons_dir(blender_version)?;
+    fs::create_dir_all(&addons_path).map_err(|e| format!("Failed to create addons dir: {}", e))?;
+    let addon_path = addons_path.join(&addon.folder_name);
+
+    // The staging folder lives in the addons dir, so a source containing it would be
+    // copied into itself; a source inside the install would be deleted by the swap
+    let addons_real = fs::canonicalize(&addons_path).unwrap_or_else(|_| addons_path.clone());
+    let addon_real = fs::canonicalize(&addon_path).unwrap_or_else(|_| addon_path.clone());
+    if root.starts_with(&addon_real) {
+        return Err("That folder is the current installation - it can't be installed over itself".to_string());
+    }
+    if addons_real.starts_with(&root) {
+        return Err("That folder contains Blender's addons directory - pick the addon folder itself".to_string());
+    }
+
+    let previous_branch = read_marker(&addon_path)
+        .and_then(|m| m.branch)
+        .or_else(|| git_output(&addon_path, &["rev-parse", "--abbrev-ref", "HEAD"]).ok());
+
+    pre_switch_backup(addon, &addon_path, blender_version, progress)?;
+
+    let staging = StagingDir::new(&addons_path, "folder");
+    progress.phase("copying files");
+    let skipped = |rel: &Path| rel.file_name().is_some_and(|name| SKIPPED.iter().any(|s| name == *s));
+    copy_dir_except(&root, staging.path(), &skipped, CopyOptions::from_settings(), progress)
+        .map_err(|e| format!("Failed to copy: {}", e))?;
+    write_marker(
+        addon,
+        staging.path(),
+        &InstallMarker {
+            installed_at: Some(chrono::Local::now().to_rfc3339()),
+            previous_branch,
+            source_path: Some(root.to_string_lossy().to_string()),
+            ..Default::default()
+        },
+    )?;
+    fingerprint::write_manifest(staging.path())?;
+    progress.phase("swapping");
+    swap_into_place(&staging, &addon_path)
+}
@@ -0,0 +1,125 @@
+// Which git executable to run: the gitPath setting, then a portable git shipped next to
+// the manager, then whatever PATH finds. Locked-down machines often have git only
+// somewhere off PATH.
+
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+
+use crate::{hidden_command, load_settings};
+
+/// Where a portable git may sit beside the manager's executable, in PortableGit's layout
+#[cfg(windows)]
+const BUNDLED: &[&str] = &["git/cmd/git.exe", "git/bin/git.exe"];
+#[cfg(not(windows))]
+const BUNDLED: &[&str] = &["git/bin/git"];
+
+/// Looked up once and reused until settings are saved, since every git call goes through here
+static RESOLVED: Mutex<Option<GitBinary>> = Mutex::new(None);
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct GitBinary {
+    /// A full path, or just "git" when PATH decides
+    pub path: PathBuf,
+    /// "setting", "bundled" or "path"
+    pub source: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitInfo {
+    pub path: String,
+    pub source: String,
+    /// `git --version`, or None when it doesn't run
+    pub version: Option<String>,
+    pub error: Option<String>,
+}
+
+/// A `git` command using the resolved executable, with no console window on Windows
+pub fn git_command() -> Command {
+    hidden_command(resolve().path)
+}
+
+pub fn resolve() -> GitBinary {
+    let mut cached = RESOLVED.lock().unwrap_or_else(|e| e.into_inner());
+    cached.get_or_insert_with(find).clone()
+}
+
+/// Drop the cached lookup, for when the setting may have changed
+pub fn forget() {
+    *RESOLVED.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+fn find() -> GitBinary {
+    let configured = load_settings().map(|s| s.git_path).unwrap_or_default();
+    if !configured.trim().is_empty() {
+        return GitBinary { path: PathBuf::from(configured.trim()), source: "setting".to_string() };
+    }
+    if let Some(bundled) = bundled() {
+        return GitBinary { path: bundled, source: "bundled".to_string() };
+    }
+    GitBinary { path: PathBuf::from("git"), source: "path".to_string() }
+}
+
+fn bundled() -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    let dir = exe.parent()?;
+    BUNDLED.iter().map(|rel| dir.join(rel)).find(|path| path.is_file())
+}
+
+/// "Failed to run git", naming the executable when it isn't simply the one on PATH - a
+/// gitPath left behind by an uninstalled git otherwise fails with no clue why
+pub fn run_error(e: &io::Error) -> String {
+    let git = resolve();
+    match git.source.as_str() {
+        "setting" => format!(
+            "Failed to run git at {} (the Git Path setting): {} - correct or clear the setting",
+            git.path.display(),
+            e
+        ),
+        "bundled" => format!("Failed to run the bundled git at {}: {}", git.path.display(), e),
+        _ => format!("Failed to run git: {}", e),
+    }
+}
+
+/// A gitPath about to be saved must name a git that runs
+pub fn check_path(path: &str) -> Result<(), String> {
+    let path = Path::new(path.trim());
+    if !path.is_file() {
+        return Err(format!("Git Path {} doesn't exist or isn't a file", path.display()));
+    }
+    version(path).map(|_| ()).map_err(|e| format!("Git Path {}: {}", path.display(), e))
+}
+
+fn version(path: &Path) -> Result<String, String> {
+    let output = hidden_command(path)
+        .arg("--version")
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("couldn't run it: {}", e))?;
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if !output.status.success() || !stdout.starts_with("git version") {
+        return Err(format!(
+            "\"--version\" didn't answer like git ({})",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(stdout)
+}
+
+/// The git the manager is using and whether it runs, for settings and issue reports
+#[tauri::command]
+pub fn git_info() -> GitInfo {
+    let git = resolve();
+    let (version, error) = match version(&git.path) {
+        Ok(version) => (Some(version), None),
+        Err(e) => (None, Some(e)),
+    };
+    GitInfo {
+        path: git.path.to_string_lossy().to_string(),
+        source: git.source,
+        version,
+        error,
+    }
+}
@@ -0,0 +1,235 @@
+// Reading git's --progress output ("Receiving objects:  37% (457/1234), 1.20 MiB | 2.40 MiB/s")
+// into numbers the UI can draw a bar from
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// How far back the computed rate looks, smoothing over git's bursty output
+const RATE_WINDOW: Duration = Duration::from_secs(3);
+
+/// git's wording for each phase, including the older names, and the key reported for it
+const PHASES: &[(&str, &str)] = &[
+    ("Enumerating objects", "counting"),
+    ("Counting objects", "counting"),
+    ("Compressing objects", "compressing"),
+    ("Receiving objects", "receiving"),
+    ("Unpacking objects", "receiving"),
+    ("Resolving deltas", "resolving"),
+    ("Updating files", "checkout"),
+    ("Checking out files", "checkout"),
+];
+
+/// Where each phase sits in the overall bar; receiving is what takes the time
+const SPANS: &[(&str, f32, f32)] = &[
+    ("counting", 0.0, 5.0),
+    ("compressing", 5.0, 10.0),
+    ("receiving", 10.0, 80.0),
+    ("resolving", 80.0, 95.0),
+    ("checkout", 95.0, 100.0),
+];
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ProgressUpdate {
+    /// "counting", "compressing", "receiving", "resolving" or "checkout"
+    pub phase: String,
+    /// Within this phase; None while git is only counting
+    pub percent: Option<f32>,
+    #[serde(rename = "objectsDone")]
+    pub objects_done: Option<u64>,
+    #[serde(rename = "objectsTotal")]
+    pub objects_total: Option<u64>,
+    /// Downloaded so far, while receiving
+    #[serde(rename = "bytesReceived")]
+    pub bytes_received: Option<u64>,
+    /// The whole download, extrapolated from the bytes per object so far
+    #[serde(rename = "totalBytesEstimate")]
+    pub total_bytes_estimate: Option<u64>,
+    /// git's figure, or one worked out from recent updates when it doesn't print one
+    #[serde(rename = "bytesPerSecond")]
+    pub bytes_per_second: Option<u64>,
+    /// git printed ", done." - the phase is over
+    pub done: bool,
+}
+
+impl ProgressUpdate {
+    /// This phase's position on a single 0-100 bar covering the whole transfer
+    pub fn overall_percent(&self) -> Option<f32> {
+        let (_, start, end) = SPANS.iter().find(|(phase, _, _)| *phase == self.phase)?;
+        let within = if self.done { 100.0 } else { self.percent? };
+        Some(start + (end - start) * within / 100.0)
+    }
+}
+
+/// One progress line, as separated by git's `\r`s; None for anything else it prints
+pub fn parse(line: &str) -> Option<ProgressUpdate> {
+    let line = line.trim();
+    let line = line.strip_prefix("remote:").unwrap_or(line).trim_start();
+    let (title, rest) = line.split_once(": ")?;
+    let (_, phase) = PHASES.iter().find(|(name, _)| *name == title)?;
+    let mut update = ProgressUpdate { phase: phase.to_string(), ..Default::default() };
+
+    for part in rest.split(", ").map(str::trim) {
+        if part == "done." || part == "done" {
+            update.done = true;
+        } else if let Some((size, rate)) = part.split_once(" | ") {
+            update.bytes_received = parse_size(size);
+            update.bytes_per_second = rate.strip_suffix("/s").and_then(parse_size);
+        } else if let Some((percent, counts)) = part.split_once('%') {
+            update.percent = percent.trim().parse().ok();
+            // " (457/1234)"
+            let counts = counts.trim().trim_start_matches('(').trim_end_matches(')');
+            if let Some((done, total)) = counts.split_once('/') {
+                update.objects_done = done.parse().ok();
+                update.objects_total = total.parse().ok();
+            }
+        } else if let Ok(count) = part.parse() {
+            // "Enumerating objects: 1234" has no total yet
+            update.objects_done = Some(count);
+        } else if let Some(size) = parse_size(part) {
+            // Some versions print the size without a rate
+            update.bytes_received = Some(size);
+        }
+    }
+    if let (Some(bytes), Some(done), Some(total)) = (update.bytes_received, update.objects_done, update.objects_total) {
+        if done > 0 {
+            update.total_bytes_estimate = Some((bytes as f64 * total as f64 / done as f64) as u64);
+        }
+    }
+    Some(update)
+}
+
+/// Download rate from the byte counts seen over the last few seconds
+#[derive(Default)]
+pub struct RateMeter {
+    samples: VecDeque<(Instant, u64)>,
+}
+
+impl RateMeter {
+    pub fn sample(&mut self, bytes: u64) -> Option<u64> {
+        let now = Instant::now();
+        self.samples.push_back((now, bytes));
+        while self.samples.front().is_some_and(|(at, _)| now.duration_since(*at) > RATE_WINDOW) {
+            self.samples.pop_front();
+        }
+        let (first_at, first_bytes) = *self.samples.front()?;
+        let elapsed = now.duration_since(first_at).as_secs_f64();
+        (elapsed > 0.2).then(|| (bytes.saturating_sub(first_bytes) as f64 / elapsed) as u64)
+    }
+}
+
+/// "1.20 MiB" -> bytes
+fn parse_size(text: &str) -> Option<u64> {
+    let (number, unit) = text.trim().split_once(' ')?;
+    let number: f64 = number.parse().ok()?;
+    let scale = match unit {
+        "bytes" | "byte" => 1.0,
+        "KiB" | "KB" | "kB" => 1024.0,
+        "MiB" | "MB" => 1024.0 * 1024.0,
+        "GiB" | "GB" => 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    Some((number * scale) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIB: f64 = 1024.0 * 1024.0;
+
+    fn close(actual: Option<f32>, expected: f32) -> bool {
+        actual.is_some_and(|actual| (actual - expected).abs() < 0.01)
+    }
+
+    #[test]
+    fn enumerating_has_a_count_but_no_total() {
+        let update = parse("remote: Enumerating objects: 1234, done.").unwrap();
+        assert_eq!(update.phase, "counting");
+        assert_eq!(update.percent, None);
+        assert_eq!(update.objects_done, Some(1234));
+        assert_eq!(update.objects_total, None);
+        assert!(update.done);
+        assert!(close(update.overall_percent(), 5.0));
+
+        let update = parse("remote: Enumerating objects: 87").unwrap();
+        assert_eq!(update.objects_done, Some(87));
+        assert!(!update.done);
+        assert_eq!(update.overall_percent(), None);
+    }
+
+    #[test]
+    fn counting_and_compressing_on_the_remote() {
+        let update = parse("remote: Counting objects:  45% (556/1234)").unwrap();
+        assert_eq!(update.phase, "counting");
+        assert!(close(update.percent, 45.0));
+        assert_eq!((update.objects_done, update.objects_total), (Some(556), Some(1234)));
+        assert!(!update.done);
+        assert!(close(update.overall_percent(), 2.25));
+
+        let update = parse("remote: Compressing objects: 100% (800/800), done.").unwrap();
+        assert_eq!(update.phase, "compressing");
+        assert_eq!((update.objects_done, update.objects_total), (Some(800), Some(800)));
+        assert!(update.done);
+        assert!(close(update.overall_percent(), 10.0));
+    }
+
+    #[test]
+    fn receiving_reads_bytes_and_rate() {
+        let update = parse("Receiving objects:  37% (457/1234), 1.20 MiB | 2.40 MiB/s").unwrap();
+        assert_eq!(update.phase, "receiving");
+        assert!(close(update.percent, 37.0));
+        assert_eq!((update.objects_done, update.objects_total), (Some(457), Some(1234)));
+        let bytes = (1.2 * MIB) as u64;
+        assert_eq!(update.bytes_received, Some(bytes));
+        assert_eq!(update.bytes_per_second, Some((2.4 * MIB) as u64));
+        assert_eq!(update.total_bytes_estimate, Some((bytes as f64 * 1234.0 / 457.0) as u64));
+        assert!(!update.done);
+        assert!(close(update.overall_percent(), 35.9));
+
+        let update = parse("Receiving objects: 100% (1234/1234), 3.35 MiB | 2.10 MiB/s, done.").unwrap();
+        assert_eq!(update.bytes_received, Some((3.35 * MIB) as u64));
+        assert_eq!(update.bytes_per_second, Some((2.1 * MIB) as u64));
+        assert!(update.done);
+        assert!(close(update.overall_percent(), 80.0));
+    }
+
+    #[test]
+    fn receiving_in_kib_and_bytes() {
+        let update = parse("Receiving objects:   5% (62/1234), 412.00 KiB | 815.00 KiB/s").unwrap();
+        assert_eq!(update.bytes_received, Some(412 * 1024));
+        assert_eq!(update.bytes_per_second, Some(815 * 1024));
+
+        let update = parse("Receiving objects:   1% (3/300), 900 bytes | 900.00 bytes/s").unwrap();
+        assert_eq!(update.bytes_received, Some(900));
+        assert_eq!(update.bytes_per_second, Some(900));
+    }
+
+    #[test]
+    fn resolving_deltas_and_updating_files() {
+        let update = parse("Resolving deltas: 100% (512/512), done.").unwrap();
+        assert_eq!(update.phase, "resolving");
+        assert!(close(update.percent, 100.0));
+        assert_eq!((update.objects_done, update.objects_total), (Some(512), Some(512)));
+        assert_eq!(update.bytes_received, None);
+        assert!(update.done);
+        assert!(close(update.overall_percent(), 95.0));
+
+        let update = parse("Resolving deltas:  50% (256/512)").unwrap();
+        assert!(close(update.overall_percent(), 87.5));
+
+        let update = parse("Updating files:  60% (300/500)\r").unwrap();
+        assert_eq!(update.phase, "checkout");
+        assert_eq!((update.objects_done, update.objects_total), (Some(300), Some(500)));
+        assert!(!update.done);
+        assert!(close(update.overall_percent(), 98.0));
+    }
+
+    #[test]
+    fn other_output_is_not_progress() {
+        assert!(parse("Cloning into 'scripting_nodes'...").is_none());
+        assert!(parse("remote: Total 1234 (delta 512), reused 1100 (delta 400), pack-reused 0").is_none());
+        assert!(parse("fatal: unable to access 'https://github.com/': Could not resolve host").is_none());
+        assert!(parse("").is_none());
+    }
+}
@@ -0,0 +1,66 @@
+// GitHub remotes in their https and SSH forms. URLs the manager builds itself - the
+// official repo, "owner/name" shorthand, forks picked from the list - follow the
+// `preferredProtocol` setting; a URL typed in full is used exactly as given.
+
+use crate::addons::Addon;
+use crate::load_settings;
+
+/// What git accepts for a github.com repo, longest first so "ssh://git@" wins over "ssh://"
+const GITHUB_PREFIXES: &[&str] = &[
+    "git+ssh://git@github.com/",
+    "ssh://git@github.com/",
+    "https://github.com/",
+    "http://github.com/",
+    "git@github.com:",
+];
+
+pub const HTTPS: &str = "https";
+pub const SSH: &str = "ssh";
+
+/// `url` in the preferred form when it's on GitHub, as is otherwise. Only for URLs the
+/// manager derived - never for one the user entered.
+pub fn preferred(url: &str) -> String {
+    let protocol = load_settings().map(|s| s.preferred_protocol).unwrap_or_else(|_| HTTPS.to_string());
+    convert(url, &protocol)
+}
+
+/// `https://github.com/owner/repo.git` <-> `git@github.com:owner/repo.git`
+pub fn convert(url: &str, protocol: &str) -> String {
+    match github_path(url) {
+        Some(path) if protocol == SSH => format!("git@github.com:{}.git", path),
+        Some(path) => format!("https://github.com/{}.git", path),
+        None => url.to_string(),
+    }
+}
+
+/// "owner/name" for a github.com URL in any of its forms
+pub fn github_path(url: &str) -> Option<String> {
+    let url = url.trim();
+    let rest = GITHUB_PREFIXES.iter().find_map(|prefix| {
+        url.get(..prefix.len())
+            .filter(|head| head.eq_ignore_ascii_case(prefix))
+            .map(|_| &url[prefix.len()..])
+    })?;
+    let repo = rest.trim_end_matches('/').trim_end_matches(".git");
+    let valid = repo
+        .split_once('/')
+        .is_some_and(|(owner, name)| !owner.is_empty() && !name.is_empty() && !name.contains('/'));
+    valid.then(|| repo.to_string())
+}
+
+/// Whether two remote URLs name the same repo - the https and SSH forms of a GitHub repo
+/// count as the same, as do a trailing ".git" or slash and differences in case
+pub fn same_repo(a: &str, b: &str) -> bool {
+    match (github_path(a), github_path(b)) {
+        (Some(a), Some(b)) => a.eq_ignore_ascii_case(&b),
+        _ => {
+            let normalize = |url: &str| url.trim().trim_end_matches('/').trim_end_matches(".git").to_lowercase();
+            normalize(a) == normalize(b)
+        }
+    }
+}
+
+/// `url` is `addon`'s own repo, in either form
+pub fn is_official(addon: &Addon, url: &str) -> bool {
+    same_repo(url, &addon.url())
+}
@@ -0,0 +1,114 @@
+// Where the installed commit stands against commits published as known-good, so support
+// can say "you're two commits past a good one" instead of guessing from dates
+
+use serde::{Deserialize, Serialize};
+
+use crate::addons::Addon;
+use crate::{addons, divergence, git_addon_dir, git_output, validate};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct KnownGoodDistance {
+    pub commit: String,
+    /// Commits the install has that the known-good one doesn't
+    pub ahead: u32,
+    /// Commits the known-good one has that the install doesn't
+    pub behind: u32,
+    /// "same", "ahead" (built on top of it), "behind" (it's newer) or "diverged"
+    pub relation: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KnownGoodReport {
+    /// The installed commit, in full
+    pub commit: String,
+    /// The known-good commit the install is exactly on, if any
+    pub matched: Option<String>,
+    /// The closest known-good commit by ahead + behind, newer ones winning ties
+    pub nearest: Option<KnownGoodDistance>,
+    /// Every known-good commit whose distance could be worked out, closest first
+    pub distances: Vec<KnownGoodDistance>,
+    /// Commits neither the install nor the remote has - a typo, or history rewritten since
+    pub unknown: Vec<String>,
+}
+
+/// Compare the install's HEAD against `commits`. Counted in the install when it has the
+/// full history, otherwise in the commit-only cache repo, fetching what's missing.
+#[tauri::command]
+pub async fn check_known_good(
+    blender_version: String,
+    commits: Vec<String>,
+    addon_id: Option<String>,
+) -> Result<KnownGoodReport, String> {
+    validate::blender_version(&blender_version)?;
+    let addon = addons::resolve(addon_id.as_deref())?;
+    let mut wanted: Vec<String> = Vec::new();
+    for commit in &commits {
+        let commit = commit.trim().to_ascii_lowercase();
+        if !(7..=40).contains(&commit.len()) || !commit.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("'{}' isn't a commit SHA: expected 7-40 hex characters", commit));
+        }
+        if !wanted.contains(&commit) {
+            wanted.push(commit);
+        }
+    }
+    if wanted.is_empty() {
+        return Err("No known-good commits given".to_string());
+    }
+    tokio::task::spawn_blocking(move || check(&addon, &blender_version, &wanted))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+}
+
+fn check(addon: &Addon, blender_version: &str, wanted: &[String]) -> Result<KnownGoodReport, String> {
+    let addon_path = git_addon_dir(addon, blender_version)?;
+    let head = git_output(&addon_path, &["rev-parse", "HEAD"])?;
+    // Short SHAs become full ones where the install knows them; the remote only
+    // answers fetches for full ones
+    let resolve =
+        |sha: &str| git_output(&addon_path, &["rev-parse", "--verify", "--quiet", &format!("{}^{{commit}}", sha)]).ok();
+    let full: Vec<String> = wanted.iter().map(|sha| resolve(sha).unwrap_or_else(|| sha.clone())).collect();
+
+    let shallow = git_output(&addon_path, &["rev-parse", "--is-shallow-repository"]).is_ok_and(|s| s == "true");
+    let all_local = full.iter().all(|sha| resolve(sha).is_some());
+    let shas: Vec<&str> = full.iter().map(String::as_str).collect();
+    let counts = if !shallow && all_local {
+        shas.iter()
+            .filter_map(|sha| {
+                let counts =
+                    git_output(&addon_path, &["rev-list", "--left-right", "--count", &format!("{}...{}", sha, head)])
+                        .ok()?;
+                let mut parts = counts.split_whitespace().map(str::parse::<u32>);
+                Some((sha.to_string(), (parts.next()?.ok()?, parts.next()?.ok()?)))
+            })
+            .collect()
+    } else {
+        let url = git_output(&addon_path, &["remote", "get-url", "origin"])?;
+        divergence::via_cache_repo(&url, &head, &shas)
+    };
+
+    let mut distances: Vec<KnownGoodDistance> = counts
+        .into_iter()
+        .map(|(commit, (only_good, only_installed))| KnownGoodDistance {
+            relation: match (only_installed, only_good) {
+                (0, 0) => "same",
+                (_, 0) => "ahead",
+                (0, _) => "behind",
+                _ => "diverged",
+            }
+            .to_string(),
+            commit,
+            ahead: only_installed,
+            behind: only_good,
+        })
+        .collect();
+    // Equal distances favour the newer known-good commit - the one to switch to
+    distances.sort_by_key(|d| (d.ahead + d.behind, d.ahead));
+    let unknown = full.iter().filter(|sha| !distances.iter().any(|d| &d.commit == *sha)).cloned().collect();
+    Ok(KnownGoodReport {
+        matched: distances.iter().find(|d| d.relation == "same").map(|d| d.commit.clone()),
+        nearest: distances.first().cloned(),
+        commit: head,
+        distances,
+        unknown,
+    })
+}
@@ -0,0 +1,187 @@
+// Which programs have files open - Windows' Restart Manager knows exactly, elsewhere
+// lsof does when it's installed
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Files looked up at most; enough to catch Blender holding an addon's libraries without
+/// handing the Restart Manager a whole git history
+const MAX_PATHS: usize = 2000;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct LockingProcess {
+    pub pid: u32,
+    pub name: String,
+}
+
+/// "Blender (PID 1234) and explorer.exe (PID 567)"
+pub fn describe(processes: &[LockingProcess]) -> String {
+    let names: Vec<String> = processes.iter().map(|p| format!("{} (PID {})", p.name, p.pid)).collect();
+    match names.as_slice() {
+        [] => "another program".to_string(),
+        [one] => one.clone(),
+        [rest @ .., last] => format!("{} and {}", rest.join(", "), last),
+    }
+}
+
+/// The processes with any of `paths` open, or None when that can't be found out here
+pub fn holders(paths: &[PathBuf]) -> Option<Vec<LockingProcess>> {
+    if paths.is_empty() {
+        return Some(Vec::new());
+    }
+    query(&paths[..paths.len().min(MAX_PATHS)])
+}
+
+/// `holders` for every file under `root`
+pub fn tree_holders(root: &Path) -> Option<Vec<LockingProcess>> {
+    let mut files = Vec::new();
+    collect_files(root, &mut files);
+    holders(&files)
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        if files.len() >= MAX_PATHS {
+            return;
+        }
+        let path = entry.path();
+        match entry.file_type() {
+            // .git is only held open by git itself
+            Ok(t) if t.is_dir() && entry.file_name() != ".git" => collect_files(&path, files),
+            Ok(t) if t.is_file() => files.push(path),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(windows)]
+fn query(paths: &[PathBuf]) -> Option<Vec<LockingProcess>> {
+    use std::os::windows::ffi::OsStrExt;
+
+    const CCH_RM_SESSION_KEY: usize = 32;
+    const CCH_RM_MAX_APP_NAME: usize = 255;
+    const CCH_RM_MAX_SVC_NAME: usize = 63;
+    const ERROR_MORE_DATA: u32 = 234;
+
+    #[repr(C)]
+    struct RmUniqueProcess {
+        process_id: u32,
+        // FILETIME
+        start_low: u32,
+        start_high: u32,
+    }
+
+    #[repr(C)]
+    struct RmProcessInfo {
+        process: RmUniqueProcess,
+        app_name: [u16; CCH_RM_MAX_APP_NAME + 1],
+        service_short_name: [u16; CCH_RM_MAX_SVC_NAME + 1],
+        application_type: i32,
+        app_status: u32,
+        ts_session_id: u32,
+        restartable: i32,
+    }
+
+    #[link(name = "rstrtmgr")]
+    extern "system" {
+        fn RmStartSession(session: *mut u32, flags: u32, key: *mut u16) -> u32;
+        fn RmRegisterResources(
+            session: u32,
+            file_count: u32,
+            files: *const *const u16,
+            app_count: u32,
+            apps: *const RmUniqueProcess,
+            service_count: u32,
+            services: *const *const u16,
+        ) -> u32;
+        fn RmGetList(
+            session: u32,
+            needed: *mut u32,
+            count: *mut u32,
+            apps: *mut RmProcessInfo,
+            reboot_reasons: *mut u32,
+        ) -> u32;
+        fn RmEndSession(session: u32) -> u32;
+    }
+
+    let wide: Vec<Vec<u16>> = paths.iter().map(|p| p.as_os_str().encode_wide().chain(Some(0)).collect()).collect();
+    let pointers: Vec<*const u16> = wide.iter().map(|w| w.as_ptr()).collect();
+    let mut session = 0u32;
+    let mut key = [0u16; CCH_RM_SESSION_KEY + 1];
+    // Safety: every buffer handed over outlives the session, which is always ended, and
+    // `infos` is sized to the count passed with it
+    unsafe {
+        if RmStartSession(&mut session, 0, key.as_mut_ptr()) != 0 {
+            return None;
+        }
+        let result = (|| {
+            let registered = RmRegisterResources(
+                session,
+                pointers.len() as u32,
+                pointers.as_ptr(),
+                0,
+                std::ptr::null(),
+                0,
+                std::ptr::null(),
+            );
+            if registered != 0 {
+                return None;
+            }
+            let mut infos: Vec<RmProcessInfo> = Vec::new();
+            // The list can grow between asking its size and fetching it
+            for _ in 0..3 {
+                let mut needed = 0u32;
+                let mut count = infos.len() as u32;
+                let mut reasons = 0u32;
+                let status = RmGetList(session, &mut needed, &mut count, infos.as_mut_ptr(), &mut reasons);
+                if status == 0 {
+                    infos.truncate(count as usize);
+                    return Some(infos);
+                }
+                if status != ERROR_MORE_DATA {
+                    return None;
+                }
+                infos = (0..needed).map(|_| std::mem::zeroed()).collect();
+            }
+            None
+        })();
+        RmEndSession(session);
+        let infos = result?;
+        let mut processes: Vec<LockingProcess> = infos
+            .iter()
+            .map(|info| {
+                let len = info.app_name.iter().position(|&c| c == 0).unwrap_or(info.app_name.len());
+                LockingProcess {
+                    pid: info.process.process_id,
+                    name: String::from_utf16_lossy(&info.app_name[..len]),
+                }
+            })
+            .collect();
+        processes.sort_by_key(|p| p.pid);
+        processes.dedup_by_key(|p| p.pid);
+        Some(processes)
+    }
+}
+
+#[cfg(not(windows))]
+fn query(paths: &[PathBuf]) -> Option<Vec<LockingProcess>> {
+    // -F pc: one "p<pid>" then one "c<command>" line per process
+    let output = crate::hidden_command("lsof").arg("-Fpc").arg("--").args(paths).output().ok()?;
+    // lsof exits 1 both when nothing has the files open and when it fails; only
+    // trust an empty answer that came with no complaint
+    if !output.status.success() && !output.stderr.is_empty() && output.stdout.is_empty() {
+        return None;
+    }
+    let mut processes: Vec<LockingProcess> = Vec::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if let Some(pid) = line.strip_prefix('p').and_then(|p| p.parse().ok()) {
+            processes.push(LockingProcess { pid, name: String::new() });
+        } else if let (Some(name), Some(last)) = (line.strip_prefix('c'), processes.last_mut()) {
+            last.name = name.to_string();
+        }
+    }
+    processes.sort_by_key(|p| p.pid);
+    processes.dedup_by_key(|p| p.pid);
+    Some(processes)
+}
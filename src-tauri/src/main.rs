@@ -2,10 +2,82 @@
 
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod addons;
+mod appinfo;
+mod applog;
+mod appupdate;
+mod archive;
+mod autostart;
+mod backups;
+mod blender;
+mod branchexport;
+mod branchsync;
+mod branchwatch;
+mod checks;
+mod cli;
+mod credentials;
+mod datadir;
+mod deeplink;
+mod divergence;
+mod extensions;
+mod fingerprint;
+mod folderinstall;
+mod gitbin;
+mod gitprogress;
+mod giturl;
+mod issuereport;
+mod knowngood;
+mod lockers;
+mod marker;
+mod mirror;
+mod nesting;
+mod netcheck;
+mod notices;
+mod notify;
+mod ops;
+mod permissions;
+mod profiles;
+mod pycache;
+mod remote;
+mod remotes;
+mod repo;
+mod restorepoints;
+mod retry;
+mod safeinstall;
+mod selfupdate;
+mod settingsdir;
+mod sharedinstall;
+mod spec;
+mod stash;
+mod suspend;
+mod switchpreview;
+mod syncfolders;
+mod tempcleanup;
+#[cfg(test)]
+mod testutil;
+mod tray;
+mod uninstall;
+mod updates;
+mod userdirs;
+mod validate;
+mod verify;
+mod window_state;
+mod zipinstall;
+
 use serde::{Deserialize, Serialize};
-use std::process::Command;
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::ffi::OsStr;
 use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Condvar, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+use addons::Addon;
+use marker::{detect_origin, read_marker, write_marker, InstallMarker, InstallOrigin};
+use ops::{OperationState, Progress, Timed};
 
 #[cfg(windows)]
 use std::os::windows::process::CommandExt;
@@ -13,20 +85,170 @@ use std::os::windows::process::CommandExt;
 #[cfg(windows)]
 const CREATE_NO_WINDOW: u32 = 0x08000000;
 
+/// A `Command` that won't flash a console window on Windows.
+fn hidden_command<S: AsRef<OsStr>>(program: S) -> Command {
+    #[allow(unused_mut)]
+    let mut cmd = Command::new(program);
+    #[cfg(windows)]
+    cmd.creation_flags(CREATE_NO_WINDOW);
+    cmd
+}
+
+/// Run git in `dir` and return its trimmed stdout, or stderr as the error.
+fn git_output(dir: &Path, args: &[&str]) -> Result<String, String> {
+    let output = gitbin::git_command()
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .map_err(|e| gitbin::run_error(&e))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+const ADDON_FOLDER_NAME: &str = "scripting_nodes";
+/// The single "Backup Your Serpens" copy, kept next to the install
+const ORIGINAL_BACKUP_FOLDER: &str = "_serpens_original_backup";
+const REPO_URL: &str = "https://github.com/CoreyCorza/scripting_nodes.git";
+
+/// Top-level entries inside the addon folder that hold the user's own data
+/// (installed package nodes, saved snippets) rather than anything from the repo.
+const USER_DATA_PRESERVE: &[&str] = &["packages", "snippets"];
+
+/// Blender's per-user config folder, holding one subfolder per version ("4.2", "5.0")
+fn blender_config_root() -> Result<PathBuf, String> {
+    let appdata = std::env::var("APPDATA").map_err(|_| "APPDATA not found")?;
+    Ok(PathBuf::from(&appdata).join("Blender Foundation").join("Blender"))
+}
+
+/// The Blender addons folder for a given version, wherever the environment or the
+/// customPath setting moved it
+fn addons_dir(blender_version: &str) -> Result<PathBuf, String> {
+    userdirs::addons_dir(blender_version)
+}
+
+/// `addon`'s install for a given version
+fn addon_dir(addon: &Addon, blender_version: &str) -> Result<PathBuf, String> {
+    Ok(addons_dir(blender_version)?.join(&addon.folder_name))
+}
+
+/// The install for a given version, which must be a git clone
+fn git_addon_dir(addon: &Addon, blender_version: &str) -> Result<PathBuf, String> {
+    let addon_path = addon_dir(addon, blender_version)?;
+    if !addon_path.join(".git").exists() {
+        return Err("Not a git repository - please switch to a branch first".to_string());
+    }
+    Ok(addon_path)
+}
+
+/// The manager's own data folder (settings, logs, backups), wherever it was moved to
+fn data_dir() -> Result<PathBuf, String> {
+    datadir::resolve(default_data_dir()?)
+}
+
+/// Where the data folder is unless moved, and where the pointer to a moved one lives
+fn default_data_dir() -> Result<PathBuf, String> {
+    let appdata = std::env::var("APPDATA").map_err(|_| "APPDATA not found")?;
+    Ok(PathBuf::from(&appdata).join("SerpensDevManager"))
+}
+
+/// Snapshot of the install taken right before the last branch switch
+fn pre_switch_backup_dir(addon: &Addon, blender_version: &str) -> Result<PathBuf, String> {
+    Ok(addon.data_root()?.join("backups").join(blender_version).join("pre_switch"))
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct InstallStatus {
     installed: bool,
     path: String,
     branch: Option<String>,
+    /// When the installed commit was made, relative ("3 days ago")
     #[serde(rename = "lastUpdated")]
     last_updated: Option<String>,
+    /// The same as an RFC 3339 committer date, for sorting and local display
+    #[serde(rename = "lastUpdatedIso")]
+    last_updated_iso: Option<String>,
+    /// HEAD points at a commit rather than a branch, so pulls have nothing to follow
+    detached: bool,
+    /// How the install came to be, so the UI only offers what works for it
+    origin: Option<InstallOrigin>,
+    /// A pull stopped mid-merge or mid-rebase; `abort_merge` gets it back to a clean state
+    #[serde(rename = "mergeInProgress")]
+    merge_in_progress: bool,
+    /// Tracked files have local edits
+    dirty: bool,
+    /// Files that only look modified because of the executable bit - an install moved
+    /// between Windows and Linux; `set_file_mode` makes git stop counting them
+    #[serde(rename = "modeOnlyChanges")]
+    mode_only_changes: usize,
+    /// "owner/name" when the install was cloned from a fork rather than the official repo
+    fork: Option<String>,
+    /// "addons" (scripts/addons) or "extensions" (a Blender 4.2+ extensions repo)
+    location: String,
+    /// The addons folder is inside OneDrive, Dropbox or Google Drive, which makes
+    /// switches slower and prone to locked files
+    #[serde(rename = "syncFolder")]
+    sync_folder: Option<syncfolders::SyncFolder>,
+    #[serde(rename = "hasBackup")]
+    has_backup: bool,
+    /// RFC 3339
+    #[serde(rename = "newestBackupDate")]
+    newest_backup_date: Option<String>,
+    #[serde(rename = "backupCount")]
+    backup_count: usize,
+    /// The addon sits one folder too deep for Blender to find, at this path relative to
+    /// the addons folder; `fix_nested_install` moves it up
+    #[serde(rename = "nestedAddon")]
+    nested_addon: Option<String>,
+    /// Other Blender versions whose install is this same folder on disk, so anything done
+    /// to this one happens to them too
+    #[serde(rename = "sharedWith")]
+    shared_with: Vec<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 struct Branch {
     name: String,
+    /// The tip commit's SHA
+    sha: String,
     #[serde(rename = "lastCommit")]
     last_commit: Option<String>,
+    /// Commits on this branch the default branch doesn't have, and the other way round;
+    /// None for the default branch itself or when it couldn't be worked out
+    #[serde(rename = "aheadOfDefault")]
+    ahead_of_default: Option<u32>,
+    #[serde(rename = "behindDefault")]
+    behind_default: Option<u32>,
+}
+
+/// Group for branches without a `prefix/`
+const UNGROUPED: &str = "";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BranchList {
+    branches: Vec<Branch>,
+    /// "feature" -> feature/..., by the first path segment only; branches without a
+    /// slash are under ""
+    groups: BTreeMap<String, Vec<Branch>>,
+    /// Where the remote's HEAD points, when it could be read
+    #[serde(rename = "defaultBranch")]
+    default_branch: Option<String>,
+}
+
+impl BranchList {
+    fn new(branches: Vec<Branch>, default_branch: Option<String>) -> BranchList {
+        let mut groups: BTreeMap<String, Vec<Branch>> = BTreeMap::new();
+        for branch in &branches {
+            let group = match branch.name.split_once('/') {
+                Some((prefix, _)) => prefix,
+                None => UNGROUPED,
+            };
+            groups.entry(group.to_string()).or_default().push(branch.clone());
+        }
+        BranchList { branches, groups, default_branch }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -37,363 +259,2198 @@ struct Settings {
     custom_path: String,
     #[serde(rename = "autoBackup")]
     auto_backup: bool,
+    /// ruff/flake8 executable (or folder containing one) when it isn't on PATH
+    #[serde(rename = "linterPath", default)]
+    linter_path: String,
+    /// Identity written into fresh clones so commits work out of the box
+    #[serde(rename = "gitUserName", default)]
+    git_user_name: String,
+    #[serde(rename = "gitUserEmail", default)]
+    git_user_email: String,
+    /// Official repo for fork syncing - empty means REPO_URL
+    #[serde(rename = "upstreamUrl", default)]
+    upstream_url: String,
+    /// Closing the window hides it to the tray instead of quitting
+    #[serde(rename = "minimizeToTray", default)]
+    minimize_to_tray: bool,
+    /// Background update check interval; 0 turns it off
+    #[serde(rename = "updateCheckMinutes", default = "default_update_check_minutes")]
+    update_check_minutes: u32,
+    /// Scheduled backup interval; 0 turns scheduled backups off
+    #[serde(rename = "autoBackupIntervalHours", default)]
+    auto_backup_interval_hours: u32,
+    /// Timestamped backups to keep per Blender version; 0 keeps everything
+    #[serde(rename = "maxBackups", default = "default_max_backups")]
+    max_backups: u32,
+    /// Desktop notifications when long operations finish while the window is in the background
+    #[serde(rename = "notifications", default = "default_true")]
+    notifications: bool,
+    /// Start with the OS, minimized to the tray
+    #[serde(rename = "launchOnStartup", default)]
+    launch_on_startup: bool,
+    /// Commits of history to fetch when cloning; null means the full history
+    #[serde(rename = "cloneDepth", default = "default_clone_depth")]
+    clone_depth: Option<u32>,
+    /// GitHub "owner/name" to look for manager releases in
+    #[serde(rename = "managerRepo", default = "default_manager_repo")]
+    manager_repo: String,
+    /// Desktop notification when branches are added to or removed from the remote
+    #[serde(rename = "newBranchNotifications", default = "default_true")]
+    new_branch_notifications: bool,
+    /// Make the installed files readable after a clone, for systems where they arrive
+    /// with restrictive permissions or hidden attributes
+    #[serde(rename = "fixPermissions", default)]
+    fix_permissions: bool,
+    /// Check the manager's releases for a newer build once a day
+    #[serde(rename = "appUpdateCheck", default = "default_true")]
+    app_update_check: bool,
+    /// Offer pre-release builds of the manager too
+    #[serde(rename = "includePrereleases", default)]
+    include_prereleases: bool,
+    /// Allow downloading and installing manager updates; off for locked-down machines
+    #[serde(rename = "selfUpdate", default = "default_true")]
+    self_update: bool,
+    /// Forks to list and install branches from, besides the official repo
+    #[serde(default)]
+    remotes: Vec<remotes::Remote>,
+    /// Addons to manage besides Serpens, which is always there
+    #[serde(default)]
+    addons: Vec<addons::Addon>,
+    /// Named repo, branch and Blender version setups, applied with `apply_profile`
+    #[serde(default)]
+    profiles: Vec<profiles::Profile>,
+    /// Check the installed files against git after every switch or update
+    #[serde(rename = "verifyAfterUpdate", default)]
+    verify_after_update: bool,
+    /// Personal access token for GitHub API calls - higher rate limits, private forks
+    #[serde(rename = "githubToken", default)]
+    github_token: String,
+    /// The git executable to run, for machines where it isn't on PATH; empty to use a
+    /// bundled portable git or PATH
+    #[serde(rename = "gitPath", default)]
+    git_path: String,
+    /// Retry a clone that failed on the network as a one-commit blobless clone, rather
+    /// than failing outright
+    #[serde(rename = "minimalCloneFallback", default = "default_true")]
+    minimal_clone_fallback: bool,
+    /// Copy symlinks inside an install as links in backups and restores; off skips them
+    #[serde(rename = "recreateSymlinks", default)]
+    recreate_symlinks: bool,
+    /// Files only in the cloud (OneDrive "online-only"): "download" them while copying
+    /// a backup, or "skip" them and log which were left out
+    #[serde(rename = "cloudPlaceholders", default = "default_cloud_placeholders")]
+    cloud_placeholders: String,
+    /// Check the install for updates as soon as the app opens. Off means no network
+    /// traffic until asked for, or until the first periodic check is due.
+    #[serde(rename = "checkOnLaunch", default = "default_true")]
+    check_on_launch: bool,
+    /// "https" or "ssh": the form of GitHub URLs the manager builds itself. URLs entered
+    /// in full are never rewritten.
+    #[serde(rename = "preferredProtocol", default = "default_preferred_protocol")]
+    preferred_protocol: String,
+    /// Delete the install's compiled Python after a switch or pull, so Blender can't
+    /// keep running bytecode from the previous branch
+    #[serde(rename = "clearPycache", default = "default_true")]
+    clear_pycache: bool,
+    /// Clone through the bare mirror in the data folder, so branches switched to before
+    /// only download what changed
+    #[serde(rename = "mirrorCache", default = "default_true")]
+    mirror_cache: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            blender_version: "5.0".to_string(),
+            custom_path: "".to_string(),
+            auto_backup: true,
+            linter_path: "".to_string(),
+            git_user_name: "".to_string(),
+            git_user_email: "".to_string(),
+            upstream_url: "".to_string(),
+            minimize_to_tray: false,
+            update_check_minutes: default_update_check_minutes(),
+            auto_backup_interval_hours: 0,
+            max_backups: default_max_backups(),
+            notifications: true,
+            launch_on_startup: false,
+            clone_depth: default_clone_depth(),
+            manager_repo: default_manager_repo(),
+            new_branch_notifications: true,
+            fix_permissions: false,
+            app_update_check: true,
+            include_prereleases: false,
+            self_update: true,
+            remotes: Vec::new(),
+            addons: Vec::new(),
+            profiles: Vec::new(),
+            verify_after_update: false,
+            github_token: "".to_string(),
+            git_path: String::new(),
+            minimal_clone_fallback: true,
+            recreate_symlinks: false,
+            cloud_placeholders: default_cloud_placeholders(),
+            mirror_cache: true,
+            preferred_protocol: default_preferred_protocol(),
+            check_on_launch: true,
+            clear_pycache: true,
+        }
+    }
+}
+
+fn default_preferred_protocol() -> String {
+    giturl::HTTPS.to_string()
+}
+
+fn default_cloud_placeholders() -> String {
+    "download".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_update_check_minutes() -> u32 {
+    30
+}
+
+fn default_max_backups() -> u32 {
+    5
+}
+
+fn default_clone_depth() -> Option<u32> {
+    Some(1)
+}
+
+fn default_manager_repo() -> String {
+    appupdate::DEFAULT_MANAGER_REPO.to_string()
+}
+
+#[tauri::command]
+fn check_installation(
+    blender_version: String,
+    addon_id: Option<String>,
+    app: AppHandle,
+) -> Result<InstallStatus, String> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    notices::refresh(&addon, &app, &blender_version);
+    installation_status(&addon, blender_version)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct VersionInstall {
+    #[serde(rename = "blenderVersion")]
+    blender_version: String,
+    status: Option<InstallStatus>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct AllInstallations {
+    installations: Vec<VersionInstall>,
+    /// Versions whose installs are one folder on disk, by where it really is
+    shared: Vec<sharedinstall::SharedInstall>,
+}
+
+/// The status of every Blender version with a config folder, and which of them share one
+/// install through a link
+#[tauri::command]
+async fn check_all_installations(addon_id: Option<String>) -> Result<AllInstallations, String> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    tokio::task::spawn_blocking(move || {
+        let versions = sharedinstall::known_versions();
+        let installations = versions
+            .iter()
+            .map(|version| {
+                let status = installation_status(&addon, version.clone());
+                VersionInstall {
+                    blender_version: version.clone(),
+                    error: status.as_ref().err().cloned(),
+                    status: status.ok(),
+                }
+            })
+            .collect();
+        Ok(AllInstallations {
+            installations,
+            shared: sharedinstall::shared_installs(&addon, &versions),
+        })
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// "just now", "5 minutes ago", "3 days ago", "2 months ago" - git's buckets, worded in
+/// English whatever git's locale is
+fn relative_time<Tz: chrono::TimeZone>(then: chrono::DateTime<Tz>, now: chrono::DateTime<chrono::Utc>) -> String {
+    let seconds = (now - then.with_timezone(&chrono::Utc)).num_seconds();
+    // A commit from a clock running ahead is still "just now"
+    if seconds < 60 {
+        return "just now".to_string();
+    }
+    let minutes = seconds / 60;
+    let hours = minutes / 60;
+    let days = hours / 24;
+    let (count, unit) = if minutes < 60 {
+        (minutes, "minute")
+    } else if hours < 24 {
+        (hours, "hour")
+    } else if days < 14 {
+        (days, "day")
+    } else if days < 70 {
+        (days / 7, "week")
+    } else if days < 365 {
+        (days / 30, "month")
+    } else {
+        (days / 365, "year")
+    };
+    format!("{} {}{} ago", count, unit, if count == 1 { "" } else { "s" })
 }
 
+/// Manager, git, archive or symlink - or None when nothing is installed
 #[tauri::command]
-fn check_installation(blender_version: String) -> Result<InstallStatus, String> {
+fn install_origin(blender_version: String, addon_id: Option<String>) -> Result<Option<InstallOrigin>, String> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    Ok(detect_origin(&addon_dir(&addon, &blender_version)?))
+}
+
+fn installation_status(addon: &Addon, blender_version: String) -> Result<InstallStatus, String> {
     // Direct implementation without Python for better performance
-    let appdata = std::env::var("APPDATA").map_err(|_| "APPDATA not found")?;
-    let addons_path = PathBuf::from(&appdata)
-        .join("Blender Foundation")
-        .join("Blender")
-        .join(&blender_version)
-        .join("scripts")
-        .join("addons");
-    
-    let addon_path = addons_path.join("scripting_nodes");
-    
+    let mut addons_path = addons_dir(&blender_version)?;
+    let mut addon_path = addons_path.join(&addon.folder_name);
+    let mut location = "addons";
+    // 4.2+ can have it installed as an extension instead
+    if !addon_path.exists() {
+        if let Some(extension) = extensions::find_installed(addon, &blender_version) {
+            addons_path = extension.parent().map(Path::to_path_buf).unwrap_or_else(|| addons_path.clone());
+            addon_path = extension;
+            location = "extensions";
+        }
+    }
+
+    let backups = backups::scan(addon, &blender_version);
     let mut status = InstallStatus {
         installed: addon_path.exists(),
         path: addons_path.to_string_lossy().to_string(),
         branch: None,
         last_updated: None,
+        last_updated_iso: None,
+        detached: false,
+        origin: detect_origin(&addon_path),
+        merge_in_progress: false,
+        dirty: false,
+        mode_only_changes: 0,
+        fork: None,
+        location: location.to_string(),
+        sync_folder: syncfolders::sync_root(&addons_path),
+        has_backup: backups.count > 0,
+        newest_backup_date: backups.newest.map(|t| chrono::DateTime::<chrono::Local>::from(t).to_rfc3339()),
+        backup_count: backups.count,
+        nested_addon: nesting::nested_root(&addon_path)
+            .map(|inner| inner.strip_prefix(&addons_path).unwrap_or(&inner).to_string_lossy().replace('\\', "/")),
+        shared_with: Vec::new(),
     };
-    
+
     if status.installed {
+        status.shared_with = sharedinstall::shared_with(addon, &blender_version);
         // Check for git repo
         let git_dir = addon_path.join(".git");
         if git_dir.exists() {
             // Get current branch
-            #[cfg(windows)]
-            let cmd_result = Command::new("git")
-                .args(["rev-parse", "--abbrev-ref", "HEAD"])
-                .current_dir(&addon_path)
-                .creation_flags(CREATE_NO_WINDOW)
-                .output();
-            #[cfg(not(windows))]
-            let cmd_result = Command::new("git")
+            let cmd_result = gitbin::git_command()
                 .args(["rev-parse", "--abbrev-ref", "HEAD"])
                 .current_dir(&addon_path)
                 .output();
-            if let Ok(output) = cmd_result
-            {
+            if let Ok(output) = cmd_result {
                 if output.status.success() {
-                    status.branch = Some(String::from_utf8_lossy(&output.stdout).trim().to_string());
+                    let branch = String::from_utf8_lossy(&output.stdout).trim().to_string();
+                    status.detached = branch == "HEAD";
+                    status.branch = Some(branch);
                 }
             }
-            
-            // Get last commit date
-            #[cfg(windows)]
-            let cmd_result2 = Command::new("git")
-                .args(["log", "-1", "--format=%cd", "--date=relative"])
-                .current_dir(&addon_path)
-                .creation_flags(CREATE_NO_WINDOW)
-                .output();
-            #[cfg(not(windows))]
-            let cmd_result2 = Command::new("git")
-                .args(["log", "-1", "--format=%cd", "--date=relative"])
-                .current_dir(&addon_path)
-                .output();
-            if let Ok(output) = cmd_result2
-            {
-                if output.status.success() {
-                    status.last_updated = Some(String::from_utf8_lossy(&output.stdout).trim().to_string());
+
+            // Get last commit date - ISO from git, worded here so it doesn't follow git's locale
+            if let Ok(date) = git_output(&addon_path, &["log", "-1", "--format=%cI"]) {
+                if let Ok(when) = chrono::DateTime::parse_from_rfc3339(&date) {
+                    status.last_updated = Some(relative_time(when, chrono::Utc::now()));
+                    status.last_updated_iso = Some(date);
                 }
             }
+            status.merge_in_progress = repo::interrupted_pull(&addon_path).is_some();
+            let changes = repo::worktree_changes(&addon_path);
+            status.dirty = changes.dirty;
+            status.mode_only_changes = changes.mode_only;
+            status.fork = remotes::fork_of_install(addon, &addon_path);
         }
     }
-    
+
     Ok(status)
 }
 
 #[tauri::command]
-async fn fetch_branches() -> Result<Vec<Branch>, String> {
-    // Use git ls-remote instead of GitHub API - no rate limits!
-    tokio::task::spawn_blocking(|| {
-        #[cfg(windows)]
-        let output = Command::new("git")
-            .args(["ls-remote", "--heads", "https://github.com/CoreyCorza/scripting_nodes.git"])
-            .creation_flags(CREATE_NO_WINDOW)
-            .output()
-            .map_err(|e| format!("Failed to run git: {}", e))?;
-        #[cfg(not(windows))]
-        let output = Command::new("git")
-            .args(["ls-remote", "--heads", "https://github.com/CoreyCorza/scripting_nodes.git"])
-            .output()
-            .map_err(|e| format!("Failed to run git: {}", e))?;
-        
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(format!("Git error: {}", stderr.trim()));
-        }
-        
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let branches: Vec<Branch> = stdout
-            .lines()
-            .filter_map(|line| {
-                // Format: "sha1\trefs/heads/branch-name"
-                let parts: Vec<&str> = line.split('\t').collect();
-                if parts.len() == 2 {
-                    let branch_name = parts[1]
-                        .strip_prefix("refs/heads/")
-                        .unwrap_or(parts[1]);
-                    Some(Branch {
-                        name: branch_name.to_string(),
-                        last_commit: None,
-                    })
-                } else {
-                    None
-                }
-            })
-            .collect();
-        
-        if branches.is_empty() {
-            return Err("No branches found".to_string());
-        }
-        
-        Ok(branches)
+async fn fetch_branches(
+    remote: Option<String>,
+    addon_id: Option<String>,
+    app: AppHandle,
+) -> Result<BranchList, String> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    tokio::task::spawn_blocking(move || {
+        let url = remotes::remote_url(&addon, remote.as_deref())?;
+        list_branches(&url, &app.state::<divergence::DivergenceCache>())
     })
     .await
     .map_err(|e| format!("Task failed: {}", e))?
 }
 
+/// Every branch on `url` with its tip and how far it is from the default branch
+fn list_branches(url: &str, cache: &divergence::DivergenceCache) -> Result<BranchList, String> {
+    let heads = remote_branch_heads_at(url)?;
+
+    if heads.is_empty() {
+        return Err("No branches found".to_string());
+    }
+
+    let (default_name, divergence) = match divergence::default_branch(url) {
+        Some((name, sha)) => {
+            let others: Vec<(String, String)> = heads.iter().filter(|(n, _)| *n != name).cloned().collect();
+            (Some(name), divergence::against_default(url, &sha, &others, cache))
+        }
+        None => (None, Default::default()),
+    };
+    let branches = heads
+        .into_iter()
+        .map(|(name, sha)| {
+            let counts = divergence.get(&name).copied();
+            let (ahead_of_default, behind_default) = match counts {
+                Some((ahead, behind)) if Some(&name) != default_name.as_ref() => (Some(ahead), Some(behind)),
+                _ => (None, None),
+            };
+            Branch { name, sha, last_commit: None, ahead_of_default, behind_default }
+        })
+        .collect();
+    Ok(BranchList::new(branches, default_name))
+}
+
+/// Branch names on the official repo
+fn remote_branch_names(addon: &Addon) -> Result<Vec<String>, String> {
+    remote_branch_names_at(&addon.url())
+}
+
+fn remote_branch_names_at(url: &str) -> Result<Vec<String>, String> {
+    Ok(remote_branch_heads_at(url)?.into_iter().map(|(name, _)| name).collect())
+}
+
+/// (branch, tip sha) for every branch on `url`
+fn remote_branch_heads_at(url: &str) -> Result<Vec<(String, String)>, String> {
+    // Use git ls-remote instead of GitHub API - no rate limits!
+    let output = gitbin::git_command()
+        .args(["ls-remote", "--heads", "--", url])
+        .output()
+        .map_err(|e| gitbin::run_error(&e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Git error: {}", stderr.trim()));
+    }
+
+    // Format: "sha1\trefs/heads/branch-name"
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .map(|(sha, name)| (name.strip_prefix("refs/heads/").unwrap_or(name).to_string(), sha.to_string()))
+        .collect())
+}
+
 #[tauri::command]
-fn backup_installation(blender_version: String) -> Result<String, String> {
-    let appdata = std::env::var("APPDATA").map_err(|_| "APPDATA not found")?;
-    let addons_path = PathBuf::from(&appdata)
-        .join("Blender Foundation")
-        .join("Blender")
-        .join(&blender_version)
-        .join("scripts")
-        .join("addons");
-    
-    let addon_path = addons_path.join("scripting_nodes");
-    let backup_dest = addons_path.join("_serpens_original_backup");
-    
+fn backup_installation(
+    blender_version: String,
+    addon_id: Option<String>,
+    app: AppHandle,
+    ops: State<'_, OperationState>,
+) -> Result<Timed<String>, String> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    let guard = ops.begin("backup_installation", &blender_version)?;
+    let result = guard.finish_timed(create_backup(&addon, &blender_version, &guard.progress()));
+    let summary = result.as_ref().map(|timed| {
+        if timed.result.starts_with("Backup already exists") {
+            timed.result.clone()
+        } else {
+            format!("Saved to {}", timed.result)
+        }
+    });
+    notify::operation_finished(&app, "Backup", &summary.map_err(Clone::clone));
+    notices::refresh(&addon, &app, &blender_version);
+    result
+}
+
+fn create_backup(addon: &Addon, blender_version: &str, progress: &Progress) -> Result<String, String> {
+    progress.phase("validating");
+    let addons_path = addons_dir(blender_version)?;
+    let addon_path = addons_path.join(&addon.folder_name);
+    let backup_dest = addons_path.join(addon.original_backup_folder());
+
     if !addon_path.exists() {
         return Err("No installation found to backup".to_string());
     }
-    
+
     // Only create ONE backup - skip if it already exists
     if backup_dest.exists() {
         return Ok(format!("Backup already exists: {}", backup_dest.to_string_lossy()));
     }
-    
+
     // Copy directory recursively
-    copy_dir_all(&addon_path, &backup_dest).map_err(|e| format!("Failed to copy: {}", e))?;
-    
+    progress.phase("copying files");
+    copy_dir_all(&addon_path, &backup_dest, CopyOptions::from_settings(), progress)
+        .map_err(|e| format!("Failed to copy: {}", e))?;
+    backups::write_meta(&addon_path, &backup_dest)?;
+
     Ok(backup_dest.to_string_lossy().to_string())
 }
 
-fn copy_dir_all(src: &PathBuf, dst: &PathBuf) -> std::io::Result<()> {
-    fs::create_dir_all(dst)?;
-    for entry in fs::read_dir(src)? {
-        let entry = entry?;
-        let ty = entry.file_type()?;
-        if ty.is_dir() {
-            copy_dir_all(&entry.path(), &dst.join(entry.file_name()))?;
-        } else {
-            fs::copy(entry.path(), dst.join(entry.file_name()))?;
-        }
+/// Threads copying files at once; small files are latency-bound, so a few more than
+/// the disk would suggest still help
+const COPY_THREADS: usize = 8;
+/// Failed files named in the error; the rest are only counted
+const COPY_ERRORS_SHOWN: usize = 5;
+
+/// Copy a tree, keeping modification times and permissions so tools that go by mtime
+/// see the same files. Folders are created first, then the files are copied on a few
+/// threads, reporting progress and stopping early when the operation is cancelled. A
+/// file whose metadata can't be kept is still copied; those are counted and logged
+/// instead of failing the copy.
+///
+/// Symlinks inside the tree are never followed, so a copy can't pull in a tree from
+/// elsewhere or loop on a circular link. They're skipped with a warning, or recreated
+/// as links with `options.recreate_links`.
+fn copy_dir_all(src: &Path, dst: &Path, options: CopyOptions, progress: &Progress) -> std::io::Result<()> {
+    copy_dir_except(src, dst, &|_| false, options, progress)
+}
+
+/// How a copy treats links and cloud-only files. Both come from settings, read once by
+/// the operation that copies rather than by the copy itself.
+#[derive(Debug, Clone, Copy, Default)]
+struct CopyOptions {
+    /// Recreate symlinks as links instead of leaving them out (`recreateSymlinks`)
+    recreate_links: bool,
+    /// Leave cloud placeholders out instead of downloading them (`cloudPlaceholders`)
+    skip_placeholders: bool,
+}
+
+impl CopyOptions {
+    fn from_settings() -> CopyOptions {
+        load_settings()
+            .map(|s| CopyOptions {
+                recreate_links: s.recreate_symlinks,
+                skip_placeholders: s.cloud_placeholders == "skip",
+            })
+            .unwrap_or_default()
     }
-    Ok(())
 }
 
-#[tauri::command]
-fn restore_backup(blender_version: String) -> Result<bool, String> {
-    let appdata = std::env::var("APPDATA").map_err(|_| "APPDATA not found")?;
-    let addons_path = PathBuf::from(&appdata)
-        .join("Blender Foundation")
-        .join("Blender")
-        .join(&blender_version)
-        .join("scripts")
-        .join("addons");
-    
-    let addon_path = addons_path.join("scripting_nodes");
-    let backup_path = addons_path.join("_serpens_original_backup");
-    
-    if !backup_path.exists() {
-        return Err("No backup found. Click 'Backup Your Serpens' first!".to_string());
+/// `copy_dir_all` leaving out whatever `skip` picks, by its path relative to `src`; a
+/// skipped folder's contents are left out with it
+fn copy_dir_except(
+    src: &Path,
+    dst: &Path,
+    skip: &dyn Fn(&Path) -> bool,
+    options: CopyOptions,
+    progress: &Progress,
+) -> std::io::Result<()> {
+    let mut tree = Tree { dirs: vec![PathBuf::new()], ..Default::default() };
+    let mut seen = std::collections::HashSet::new();
+    list_tree(src, Path::new(""), skip, &mut tree, &mut seen)?;
+    let Tree { dirs, files, links } = tree;
+    // Parents come before their children in `dirs`
+    for rel in &dirs {
+        fs::create_dir_all(dst.join(rel))?;
     }
-    
-    // Remove current installation
-    if addon_path.exists() {
-        fs::remove_dir_all(&addon_path).map_err(|e| format!("Failed to remove current: {}", e))?;
+    copy_links(src, dst, &links, options.recreate_links);
+
+    // Reading a cloud placeholder downloads it, which is slow but gives a complete copy
+    let placeholders = Mutex::new(Vec::new());
+    let next = AtomicUsize::new(0);
+    let done = AtomicUsize::new(0);
+    let unkept = AtomicUsize::new(0);
+    let errors = Mutex::new(Vec::new());
+    let threads = std::thread::available_parallelism().map_or(4, |n| n.get()).clamp(2, COPY_THREADS);
+    std::thread::scope(|scope| {
+        for _ in 0..threads.min(files.len().max(1)) {
+            scope.spawn(|| loop {
+                if progress.cancelled() {
+                    break;
+                }
+                let i = next.fetch_add(1, Ordering::SeqCst);
+                let Some(rel) = files.get(i) else { break };
+                let (from, to) = (src.join(rel), dst.join(rel));
+                if options.skip_placeholders && syncfolders::is_placeholder(&from) {
+                    placeholders.lock().unwrap_or_else(|e| e.into_inner()).push(rel.clone());
+                    done.fetch_add(1, Ordering::SeqCst);
+                    continue;
+                }
+                // fs::copy carries the permissions over, read-only attribute included -
+                // remove_dir_robust clears that again when the copy is deleted
+                match syncfolders::retry_locked(&to, || fs::copy(&from, &to)) {
+                    Ok(_) if keep_mtime(&from, &to).is_err() => {
+                        unkept.fetch_add(1, Ordering::SeqCst);
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        errors.lock().unwrap_or_else(|e| e.into_inner()).push(format!("{}: {}", rel.display(), e))
+                    }
+                }
+                let finished = done.fetch_add(1, Ordering::SeqCst) + 1;
+                if finished.is_multiple_of(200) {
+                    progress.percent(finished as f32 * 100.0 / files.len() as f32);
+                }
+            });
+        }
+    });
+    if progress.cancelled() {
+        return Err(std::io::Error::new(std::io::ErrorKind::Interrupted, "Cancelled"));
     }
-    
-    // Restore from backup
-    copy_dir_all(&backup_path, &addon_path).map_err(|e| format!("Failed to restore: {}", e))?;
-    
-    Ok(true)
-}
 
-#[tauri::command]
-fn switch_branch(branch_name: String, blender_version: String) -> Result<bool, String> {
-    let appdata = std::env::var("APPDATA").map_err(|_| "APPDATA not found")?;
-    let addons_path = PathBuf::from(&appdata)
-        .join("Blender Foundation")
-        .join("Blender")
-        .join(&blender_version)
-        .join("scripts")
-        .join("addons");
-    
-    let addon_path = addons_path.join("scripting_nodes");
-    let addon_path_str = addon_path.to_string_lossy().to_string();
-    
-    // Ensure addons directory exists
-    fs::create_dir_all(&addons_path).map_err(|e| format!("Failed to create addons dir: {}", e))?;
-    
-    // Remove existing installation
-    if addon_path.exists() {
-        fs::remove_dir_all(&addon_path).map_err(|e| format!("Failed to remove existing: {}", e))?;
+    let placeholders = placeholders.into_inner().unwrap_or_else(|e| e.into_inner());
+    if !placeholders.is_empty() {
+        let names: Vec<String> = placeholders.iter().take(COPY_ERRORS_SHOWN).map(|p| p.display().to_string()).collect();
+        applog::warn(&format!(
+            "Left {} cloud-only file{} out of {} ({}{})",
+            placeholders.len(),
+            if placeholders.len() == 1 { "" } else { "s" },
+            dst.display(),
+            names.join(", "),
+            if placeholders.len() > COPY_ERRORS_SHOWN { ", ..." } else { "" }
+        ));
     }
-    
-    // Clone the specific branch - call git directly with separate args
-    #[cfg(windows)]
-    let output = Command::new("git")
-        .arg("clone")
-        .arg("--branch")
-        .arg(&branch_name)
-        .arg("--single-branch")
-        .arg("--depth")
-        .arg("1")
-        .arg("https://github.com/CoreyCorza/scripting_nodes.git")
-        .arg(&addon_path_str)
-        .current_dir(&addons_path)
-        .creation_flags(CREATE_NO_WINDOW)
-        .output()
-        .map_err(|e| format!("Failed to run git: {}", e))?;
-    #[cfg(not(windows))]
-    let output = Command::new("git")
-        .arg("clone")
-        .arg("--branch")
-        .arg(&branch_name)
-        .arg("--single-branch")
-        .arg("--depth")
-        .arg("1")
-        .arg("https://github.com/CoreyCorza/scripting_nodes.git")
-        .arg(&addon_path_str)
-        .current_dir(&addons_path)
-        .output()
-        .map_err(|e| format!("Failed to run git: {}", e))?;
-    
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    
-    if !output.status.success() {
-        return Err(format!("Git clone failed:\n{}\n{}", stdout, stderr));
+
+    let errors = errors.into_inner().unwrap_or_else(|e| e.into_inner());
+    if !errors.is_empty() {
+        let shown = errors[..errors.len().min(COPY_ERRORS_SHOWN)].join("; ");
+        let mut message = format!("{} of {} files couldn't be copied: {}", errors.len(), files.len(), shown);
+        if errors.len() > COPY_ERRORS_SHOWN {
+            message.push_str(&format!("; and {} more", errors.len() - COPY_ERRORS_SHOWN));
+        }
+        return Err(std::io::Error::other(message));
     }
-    
-    // Verify files were actually cloned
-    let init_file = addon_path.join("__init__.py");
-    if !init_file.exists() {
-        return Err(format!(
-            "Clone completed but __init__.py not found. The branch '{}' may not contain the addon.\nPath: {}\nGit output:\n{}{}",
-            branch_name, addon_path_str, stdout, stderr
+
+    // Last and deepest first, since filling a directory bumps its own times. Only Unix
+    // gets the directory's mode: a read-only folder on Windows can't be deleted afterwards.
+    let mut unkept = unkept.into_inner();
+    for rel in dirs.iter().rev() {
+        let (from, to) = (src.join(rel), dst.join(rel));
+        #[cfg(unix)]
+        let kept = fs::metadata(&from).and_then(|meta| fs::set_permissions(&to, meta.permissions()));
+        #[cfg(not(unix))]
+        let kept = Ok(());
+        if kept.and_then(|_| keep_mtime(&from, &to)).is_err() {
+            unkept += 1;
+        }
+    }
+    if unkept > 0 {
+        applog::warn(&format!(
+            "Copied {} but couldn't keep the timestamps or permissions of {} item{}",
+            dst.display(),
+            unkept,
+            if unkept == 1 { "" } else { "s" }
         ));
     }
-    
-    Ok(true)
+    Ok(())
 }
 
-#[tauri::command]
-fn pull_latest(blender_version: String) -> Result<bool, String> {
-    let appdata = std::env::var("APPDATA").map_err(|_| "APPDATA not found")?;
-    let addon_path = PathBuf::from(&appdata)
-        .join("Blender Foundation")
-        .join("Blender")
-        .join(&blender_version)
-        .join("scripts")
-        .join("addons")
-        .join("scripting_nodes");
-    
-    if !addon_path.exists() {
-        return Err("No installation found".to_string());
+/// A tree to copy, every path relative to its root
+#[derive(Default)]
+struct Tree {
+    /// In creation order
+    dirs: Vec<PathBuf>,
+    files: Vec<PathBuf>,
+    links: Vec<PathBuf>,
+}
+
+/// `seen` holds the folders already listed, by real path - a guard against cycles that
+/// don't show up as links, such as bind mounts
+fn list_tree(
+    root: &Path,
+    rel: &Path,
+    skip: &dyn Fn(&Path) -> bool,
+    tree: &mut Tree,
+    seen: &mut std::collections::HashSet<PathBuf>,
+) -> std::io::Result<()> {
+    let dir = root.join(rel);
+    if !seen.insert(fs::canonicalize(&dir).unwrap_or(dir.clone())) {
+        applog::warn(&format!("Not copying {} again - it loops back on itself", dir.display()));
+        return Ok(());
     }
-    
-    let output = Command::new("git")
-        .args(["pull"])
-        .current_dir(&addon_path)
-        .output()
-        .map_err(|e| format!("Failed to run git: {}", e))?;
-    
-    if output.status.success() {
-        Ok(true)
-    } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
+    for entry in fs::read_dir(&dir)? {
+        let entry = entry?;
+        let child = rel.join(entry.file_name());
+        if skip(&child) {
+            continue;
+        }
+        // The entry's own type: a link (Windows junctions included) isn't followed
+        let file_type = entry.file_type()?;
+        if file_type.is_symlink() {
+            tree.links.push(child);
+        } else if file_type.is_dir() {
+            tree.dirs.push(child.clone());
+            list_tree(root, &child, skip, tree, seen)?;
+        } else {
+            tree.files.push(child);
+        }
     }
+    Ok(())
 }
 
-#[tauri::command]
-fn open_folder(blender_version: String) -> Result<bool, String> {
-    let appdata = std::env::var("APPDATA").map_err(|_| "APPDATA not found")?;
-    let addons_path = PathBuf::from(&appdata)
-        .join("Blender Foundation")
-        .join("Blender")
-        .join(&blender_version)
-        .join("scripts")
-        .join("addons");
-    
-    fs::create_dir_all(&addons_path).ok();
-    
-    Command::new("explorer")
-        .arg(&addons_path)
-        .spawn()
-        .map_err(|e| format!("Failed to open explorer: {}", e))?;
-    
-    Ok(true)
+/// Recreate the links as links, when that's wanted and the platform allows it; the rest
+/// are left out and logged
+fn copy_links(src: &Path, dst: &Path, links: &[PathBuf], recreate: bool) {
+    for rel in links {
+        let (from, to) = (src.join(rel), dst.join(rel));
+        let made = recreate && fs::read_link(&from).and_then(|target| make_link(&target, &from, &to)).is_ok();
+        if !made {
+            applog::warn(&format!("Skipped the link {} while copying to {}", rel.display(), dst.display()));
+        }
+    }
 }
 
-#[tauri::command]
-fn load_settings() -> Result<Settings, String> {
-    let appdata = std::env::var("APPDATA").map_err(|_| "APPDATA not found")?;
-    let settings_path = PathBuf::from(&appdata)
-        .join("SerpensDevManager")
-        .join("settings.json");
-    
-    if settings_path.exists() {
-        let content = fs::read_to_string(&settings_path)
-            .map_err(|e| format!("Failed to read settings: {}", e))?;
-        serde_json::from_str(&content).map_err(|e| format!("Failed to parse settings: {}", e))
+#[cfg(unix)]
+fn make_link(target: &Path, _original: &Path, link: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+/// Windows needs to know whether the link is to a folder; creating one also needs
+/// Developer Mode or admin rights, and fails otherwise
+#[cfg(windows)]
+fn make_link(target: &Path, original: &Path, link: &Path) -> std::io::Result<()> {
+    if fs::metadata(original).is_ok_and(|meta| meta.is_dir()) {
+        std::os::windows::fs::symlink_dir(target, link)
     } else {
-        Ok(Settings {
-            blender_version: "5.0".to_string(),
-            custom_path: "".to_string(),
-            auto_backup: true,
-        })
+        std::os::windows::fs::symlink_file(target, link)
     }
 }
 
-#[tauri::command]
-fn save_settings(settings: Settings) -> Result<bool, String> {
-    let appdata = std::env::var("APPDATA").map_err(|_| "APPDATA not found")?;
-    let settings_dir = PathBuf::from(&appdata).join("SerpensDevManager");
-    let settings_path = settings_dir.join("settings.json");
-    
-    fs::create_dir_all(&settings_dir).map_err(|e| format!("Failed to create settings dir: {}", e))?;
-    
-    let content = serde_json::to_string_pretty(&settings)
-        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
-    
-    fs::write(&settings_path, content).map_err(|e| format!("Failed to write settings: {}", e))?;
-    
-    Ok(true)
+fn keep_mtime(src: &Path, dst: &Path) -> std::io::Result<()> {
+    let meta = fs::metadata(src)?;
+    filetime::set_file_mtime(dst, filetime::FileTime::from_last_modification_time(&meta))
 }
 
-fn main() {
-    tauri::Builder::default()
-        .plugin(tauri_plugin_shell::init())
-        .invoke_handler(tauri::generate_handler![
-            check_installation,
-            fetch_branches,
-            backup_installation,
-            restore_backup,
-            switch_branch,
-            pull_latest,
-            open_folder,
-            load_settings,
-            save_settings
-        ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+/// A scratch directory under the system temp dir, removed again when dropped
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(tag: &str) -> Result<TempDir, String> {
+        let stamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let path = std::env::temp_dir().join(format!("serpens_{}_{}_{}", tag, std::process::id(), stamp));
+        fs::create_dir_all(&path).map_err(|e| format!("Failed to create temp dir: {}", e))?;
+        Ok(TempDir(path))
+    }
+
+    fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        fs::remove_dir_all(&self.0).ok();
+    }
+}
+
+/// Start of every staging folder's name; anything else in the addons dir is never swept
+const STAGING_PREFIX: &str = ".serpens_tmp_";
+
+/// Where an install's replacement is assembled: a hidden folder in the addons dir, so
+/// moving it into place is a rename on the same volume. Removed on drop unless swapped in.
+struct StagingDir(PathBuf);
+
+impl StagingDir {
+    fn new(addons_path: &Path, tag: &str) -> StagingDir {
+        // Whatever an earlier install that never finished left behind goes first
+        tempcleanup::clean_dir(addons_path, &mut tempcleanup::TempCleanup::default());
+        let stamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        StagingDir(addons_path.join(format!("{}{}_{}", STAGING_PREFIX, tag, stamp)))
+    }
+
+    fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for StagingDir {
+    fn drop(&mut self) {
+        if self.0.exists() {
+            fs::remove_dir_all(&self.0).ok();
+        }
+    }
+}
+
+/// Replace `target` with the staged folder. The old install is only deleted once the new
+/// one is in place, and is put back if the final rename fails.
+fn swap_into_place(staged: &StagingDir, target: &Path) -> Result<(), String> {
+    let old = staged.path().with_file_name(format!(
+        "{}_old",
+        staged.path().file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default()
+    ));
+    if target.exists() {
+        syncfolders::retry_locked(target, || fs::rename(target, &old))
+            .map_err(|e| format!("Failed to move the current install aside (is Blender using it?): {}", e))?;
+    }
+    if let Err(e) = syncfolders::retry_locked(target, || fs::rename(staged.path(), target)) {
+        if old.exists() {
+            fs::rename(&old, target).ok();
+        }
+        return Err(format!("Failed to move the new install into place: {}", e));
+    }
+    // The new install is in place either way; a leftover is swept up by tempcleanup later
+    if let Err(e) = remove_dir_checked(&old) {
+        applog::warn(&format!("Left the previous install behind: {}", e.message));
+    }
+    Ok(())
+}
+
+/// Locked files named in a delete error; the rest are counted
+const DELETE_PATHS_SHOWN: usize = 10;
+
+/// A delete that left something behind. `code` is "FILES_IN_USE" when every leftover was
+/// held open by another process (Blender, an indexer, a virus scanner), else "DELETE_FAILED".
+#[derive(Debug, Serialize, Deserialize)]
+struct DeleteError {
+    code: String,
+    message: String,
+    /// What couldn't be removed
+    paths: Vec<String>,
+    /// Part of the folder is already gone, so it stays incomplete until a delete succeeds
+    partial: bool,
+    /// Programs that had the paths open, when the system can tell
+    processes: Vec<lockers::LockingProcess>,
+}
+
+impl From<String> for DeleteError {
+    fn from(message: String) -> DeleteError {
+        DeleteError {
+            code: "DELETE_FAILED".to_string(),
+            message,
+            paths: Vec::new(),
+            partial: false,
+            processes: Vec::new(),
+        }
+    }
+}
+
+impl From<DeleteError> for String {
+    fn from(error: DeleteError) -> String {
+        error.message
+    }
+}
+
+/// `remove_dir_all` that copes with what git and Windows leave behind: read-only pack
+/// files, and handles a virus scanner, indexer or sync client holds open for a moment.
+/// A symlinked install loses only the link, never the folder it points to.
+fn remove_dir_robust(path: &Path) -> Result<(), String> {
+    remove_dir_checked(path).map_err(String::from)
+}
+
+/// `remove_dir_robust`, saying exactly which files were still in use
+fn remove_dir_checked(path: &Path) -> Result<(), DeleteError> {
+    let Ok(meta) = fs::symlink_metadata(path) else { return Ok(()) };
+    if meta.file_type().is_symlink() {
+        // Windows directory links are removed as directories
+        return fs::remove_file(path)
+            .or_else(|_| fs::remove_dir(path))
+            .map_err(|e| format!("Failed to remove link {}: {}", path.display(), e).into());
+    }
+    match fs::remove_dir_all(path) {
+        Ok(()) => return Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(_) => {}
+    }
+    // Something is read-only or held open: go file by file so only those wait
+    let mut delete = TreeDelete {
+        attempts: syncfolders::delete_attempts(path),
+        failed: Vec::new(),
+        removed_any: false,
+    };
+    delete.remove_tree(path);
+    delete.remove(path, |p| fs::remove_dir(p));
+    if delete.failed.is_empty() {
+        return Ok(());
+    }
+    Err(delete.into_error(path))
+}
+
+/// One file-by-file delete: what was removed and what wouldn't go
+struct TreeDelete {
+    /// Tries per path, dropped to one once a path has used them all up - whatever holds
+    /// that file most likely holds the rest too
+    attempts: u64,
+    failed: Vec<(PathBuf, std::io::Error)>,
+    removed_any: bool,
+}
+
+impl TreeDelete {
+    fn remove_tree(&mut self, dir: &Path) {
+        let Ok(entries) = fs::read_dir(dir) else { return };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if entry.file_type().is_ok_and(|t| t.is_dir()) {
+                self.remove_tree(&path);
+                self.remove(&path, |p| fs::remove_dir(p));
+            } else {
+                // Links too: on Windows a directory link only goes with remove_dir
+                self.remove(&path, |p| fs::remove_file(p).or_else(|e| fs::remove_dir(p).map_err(|_| e)));
+            }
+        }
+    }
+
+    /// Retry a locked path with backoff (about 1.5s, longer in a sync folder), clearing
+    /// its read-only flag after the first failure
+    fn remove(&mut self, path: &Path, op: impl Fn(&Path) -> std::io::Result<()>) {
+        // A folder whose contents failed can't go either, and isn't worth listing
+        if self.failed.iter().any(|(failed, _)| failed.starts_with(path)) {
+            return;
+        }
+        let mut wait = std::time::Duration::from_millis(100);
+        for attempt in 1..=self.attempts {
+            match op(path) {
+                Ok(()) => {
+                    self.removed_any = true;
+                    return;
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => return,
+                Err(e) if attempt == self.attempts || !syncfolders::is_in_use(&e) => {
+                    if syncfolders::is_in_use(&e) {
+                        self.attempts = 1;
+                    }
+                    self.failed.push((path.to_path_buf(), e));
+                    return;
+                }
+                Err(_) => {
+                    if attempt == 1 {
+                        make_writable(path);
+                    }
+                    std::thread::sleep(wait);
+                    wait *= 2;
+                }
+            }
+        }
+    }
+
+    fn into_error(self, root: &Path) -> DeleteError {
+        let in_use = self.failed.iter().all(|(_, e)| syncfolders::is_in_use(e));
+        let count = self.failed.len();
+        let mut names: Vec<String> = self
+            .failed
+            .iter()
+            .take(DELETE_PATHS_SHOWN)
+            .map(|(p, _)| p.strip_prefix(root).unwrap_or(p).display().to_string())
+            .collect();
+        if count > DELETE_PATHS_SHOWN {
+            names.push(format!("and {} more", count - DELETE_PATHS_SHOWN));
+        }
+        let failed: Vec<PathBuf> = self.failed.iter().map(|(p, _)| p.clone()).collect();
+        let processes = if in_use { lockers::holders(&failed).unwrap_or_default() } else { Vec::new() };
+        let error = &self.failed[0].1;
+        let reason = match syncfolders::describe_cloud_error(error) {
+            Some(cloud) => cloud.to_string(),
+            None if in_use && !processes.is_empty() => format!(
+                "{} in use by {} - close {} and try again",
+                if count == 1 { "it's" } else { "they're" },
+                lockers::describe(&processes),
+                if processes.len() == 1 { "it" } else { "them" }
+            ),
+            None if in_use => format!(
+                "{} in use by another program - close Blender (and anything else with the addon open) and try again",
+                if count == 1 { "it's" } else { "they're" }
+            ),
+            None => error.to_string(),
+        };
+        let mut message = format!(
+            "Couldn't remove {} item{} in {} - {}: {}",
+            count,
+            if count == 1 { "" } else { "s" },
+            root.display(),
+            reason,
+            names.join(", ")
+        );
+        if self.removed_any {
+            message.push_str(". The rest was already deleted, so the folder is incomplete until this is run again.");
+        }
+        DeleteError {
+            code: if in_use { "FILES_IN_USE" } else { "DELETE_FAILED" }.to_string(),
+            message,
+            paths: failed.iter().map(|p| p.to_string_lossy().to_string()).collect(),
+            partial: self.removed_any,
+            processes,
+        }
+    }
+}
+
+/// Clear the read-only flag that stops Windows deleting a file
+fn make_writable(path: &Path) {
+    let Ok(meta) = fs::symlink_metadata(path) else { return };
+    let mut perms = meta.permissions();
+    if perms.readonly() && !meta.file_type().is_symlink() {
+        #[allow(clippy::permissions_set_readonly_false)]
+        perms.set_readonly(false);
+        fs::set_permissions(path, perms).ok();
+    }
+}
+
+/// Total size in bytes of everything under `path`
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else { return 0 };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(ty) if ty.is_dir() => dir_size(&entry.path()),
+            _ => entry.metadata().map(|m| m.len()).unwrap_or(0),
+        })
+        .sum()
+}
+
+#[tauri::command]
+fn restore_backup(
+    blender_version: String,
+    addon_id: Option<String>,
+    app: AppHandle,
+    ops: State<'_, OperationState>,
+) -> Result<Timed<bool>, String> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    let guard = ops.begin("restore_backup", &blender_version)?;
+    let result = guard.finish_timed(restore_from_backup(&addon, &blender_version, &guard.progress()).map(|_| true));
+    let summary = result
+        .as_ref()
+        .map(|_| format!("Restored your backup{}", notify::commit_suffix(&addon, &blender_version)));
+    notify::operation_finished(&app, "Restore", &summary.map_err(Clone::clone));
+    notices::refresh(&addon, &app, &blender_version);
+    result
+}
+
+fn restore_from_backup(addon: &Addon, blender_version: &str, progress: &Progress) -> Result<(), String> {
+    progress.phase("validating");
+    let addons_path = addons_dir(blender_version)?;
+    let addon_path = addons_path.join(&addon.folder_name);
+    let backup_path = addons_path.join(addon.original_backup_folder());
+
+    if !backup_path.exists() {
+        return Err("No backup found. Click 'Backup Your Serpens' first!".to_string());
+    }
+
+    // Remove current installation
+    progress.phase("removing");
+    remove_dir_robust(&addon_path)?;
+
+    // Restore from backup
+    progress.phase("copying files");
+    copy_dir_all(&backup_path, &addon_path, CopyOptions::from_settings(), progress)
+        .map_err(|e| format!("Failed to restore: {}", e))?;
+    backups::strip_meta(&addon_path);
+
+    Ok(())
+}
+
+/// Why a switch didn't happen. `code` is "UNPUSHED_COMMITS" when it was refused to keep
+/// local commits from being lost - retrying with `force` goes ahead anyway - and
+/// "SWITCH_FAILED" for everything else.
+#[derive(Debug, Serialize, Clone)]
+struct SwitchError {
+    code: String,
+    message: String,
+    /// The unpushed commits, "abc1234 subject"
+    commits: Vec<String>,
+    /// Commands that would save them first
+    suggestions: Vec<String>,
+}
+
+impl From<String> for SwitchError {
+    fn from(message: String) -> SwitchError {
+        SwitchError {
+            code: "SWITCH_FAILED".to_string(),
+            message,
+            commits: Vec::new(),
+            suggestions: Vec::new(),
+        }
+    }
+}
+
+impl SwitchError {
+    fn unpushed(unpushed: repo::Unpushed) -> SwitchError {
+        let count = unpushed.commits.len();
+        let against = unpushed.base.as_deref().unwrap_or("any remote");
+        SwitchError {
+            code: "UNPUSHED_COMMITS".to_string(),
+            message: format!(
+                "{} has {} commit{} not on {} - switching would delete {}",
+                unpushed.branch,
+                count,
+                if count == 1 { "" } else { "s" },
+                against,
+                if count == 1 { "it" } else { "them" }
+            ),
+            suggestions: vec![
+                format!("git push origin HEAD:{}", unpushed.branch),
+                format!("git format-patch -{} -o serpens-patches", count),
+            ],
+            commits: unpushed.commits,
+        }
+    }
+}
+
+/// Every command that puts a fresh clone, a zip or a folder in place of a git install
+/// deletes commits only that install has, so each one - the CLI's `--switch` included -
+/// refuses up front with this unless told to go ahead
+fn refuse_unpushed(addon_path: &Path, force: bool) -> Result<(), SwitchError> {
+    if force {
+        return Ok(());
+    }
+    match repo::unpushed_commits(addon_path) {
+        Some(unpushed) => Err(SwitchError::unpushed(unpushed)),
+        None => Ok(()),
+    }
+}
+
+#[tauri::command]
+fn switch_branch(
+    branch_name: String,
+    blender_version: String,
+    remote: Option<String>,
+    force: Option<bool>,
+    addon_id: Option<String>,
+    app: AppHandle,
+    ops: State<'_, OperationState>,
+) -> Result<Timed<SwitchResult>, SwitchError> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    let guard = ops.begin("switch_branch", &blender_version)?;
+    let progress = guard.progress();
+    let result = switch_version(
+        &addon,
+        &app,
+        &branch_name,
+        &blender_version,
+        remote.as_deref(),
+        force.unwrap_or(false),
+        &progress,
+    );
+    guard.finish(&result.as_ref().map_err(|e| e.message.clone()));
+    let summary = result
+        .as_ref()
+        .map(|_| format!("Switched to {}{}", branch_name, notify::commit_suffix(&addon, &blender_version)))
+        .map_err(|e| e.message.clone());
+    notify::operation_finished(&app, "Branch switch", &summary);
+    notices::refresh(&addon, &app, &blender_version);
+    result.map(|result| guard.timed(result))
+}
+
+/// One Blender version's switch, for `switch_branch` and each version of
+/// `switch_branch_all`: a failure is remembered for `retry_last_operation`
+fn switch_version(
+    addon: &Addon,
+    app: &AppHandle,
+    branch_name: &str,
+    blender_version: &str,
+    remote: Option<&str>,
+    force: bool,
+    progress: &Progress,
+) -> Result<SwitchResult, SwitchError> {
+    progress.phase("validating");
+    refuse_unpushed(&addon_dir(addon, blender_version)?, force)?;
+
+    let result = validate::branch_name(branch_name)
+        .and_then(|_| remotes::remote_url(addon, remote))
+        .and_then(|url| install_branch_from(addon, &url, branch_name, blender_version, progress))
+        .map(|_| {
+            let clone_mode = addon_dir(addon, blender_version)
+                .ok()
+                .and_then(|dir| read_marker(&dir))
+                .and_then(|m| m.clone_mode);
+            SwitchResult { degraded: clone_mode.as_deref() == Some(MINIMAL_CLONE), clone_mode }
+        });
+    let attempt = retry::FailedOperation::new("switch_branch", blender_version, Some(branch_name), remote, force);
+    retry::note_outcome(addon, app, attempt, result.as_ref().err());
+    result.map_err(SwitchError::from)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SwitchResult {
+    /// How the branch was cloned, as recorded in the install marker
+    #[serde(rename = "cloneMode")]
+    clone_mode: Option<String>,
+    /// The configured clone failed on the network and a minimal one was installed instead
+    degraded: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct VersionSwitchResult {
+    #[serde(rename = "blenderVersion")]
+    blender_version: String,
+    success: bool,
+    error: Option<String>,
+    /// The `SwitchError` code, so an unpushed-commits refusal can be told apart
+    code: Option<String>,
+}
+
+/// Switch several Blender versions to the same branch, one after another so the clones
+/// don't fight over the disk. Every version gets its own result; one failing doesn't
+/// stop the rest, but cancelling skips whatever hasn't started. Each version is an
+/// ordinary switch, so one with unpushed commits is refused unless `force` is set.
+#[tauri::command]
+async fn switch_branch_all(
+    branch: String,
+    versions: Vec<String>,
+    remote: Option<String>,
+    force: Option<bool>,
+    addon_id: Option<String>,
+    app: AppHandle,
+    ops: State<'_, OperationState>,
+) -> Result<Timed<Vec<VersionSwitchResult>>, String> {
+    validate::branch_name(&branch)?;
+    for version in &versions {
+        validate::blender_version(version)?;
+    }
+    if versions.is_empty() {
+        return Err("No Blender versions selected".to_string());
+    }
+
+    let addon = addons::resolve(addon_id.as_deref())?;
+    let guard = ops.begin("switch_branch_all", &versions.join(", "))?.cancellable();
+    tokio::task::spawn_blocking(move || {
+        let progress = guard.progress();
+        let mut results = Vec::new();
+        for (i, version) in versions.iter().enumerate() {
+            if guard.cancelled() {
+                results.push(VersionSwitchResult {
+                    blender_version: version.clone(),
+                    success: false,
+                    error: Some("Cancelled".to_string()),
+                    code: None,
+                });
+                continue;
+            }
+            progress.percent(i as f32 * 100.0 / versions.len() as f32);
+            let outcome =
+                switch_version(&addon, &app, &branch, version, remote.as_deref(), force.unwrap_or(false), &progress);
+            let (error, code) = outcome.err().map(|e| (e.message, e.code)).unzip();
+            results.push(VersionSwitchResult {
+                blender_version: version.clone(),
+                success: error.is_none(),
+                error,
+                code,
+            });
+        }
+
+        let failed = results.iter().filter(|r| !r.success).count();
+        let outcome = if failed == 0 {
+            Ok(format!("Switched {} versions to {}", results.len(), branch))
+        } else {
+            Err(format!("{} of {} versions failed to switch to {}", failed, results.len(), branch))
+        };
+        notify::operation_finished(&app, "Branch switch", &outcome);
+        for result in &results {
+            notices::refresh(&addon, &app, &result.blender_version);
+        }
+        // Partial failures are in the results; only a total failure fails the command
+        let result = if failed < results.len() { Ok(results) } else { outcome.map(|_| results) };
+        guard.finish_timed(result)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Install `branch` from a local bundle file, bare repo or working copy instead of GitHub
+#[tauri::command]
+fn install_from_source(
+    source: String,
+    branch: String,
+    blender_version: String,
+    force: Option<bool>,
+    addon_id: Option<String>,
+    ops: State<'_, OperationState>,
+) -> Result<bool, SwitchError> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    validate::branch_name(&branch)?;
+    let guard = ops.begin("install_from_source", &blender_version)?;
+    let result = install_source(&addon, &source, &branch, &blender_version, force.unwrap_or(false), &guard.progress());
+    guard.finish(&result.as_ref().map_err(|e| e.message.clone()));
+    result.map(|_| true)
+}
+
+fn install_source(
+    addon: &Addon,
+    source: &str,
+    branch: &str,
+    blender_version: &str,
+    force: bool,
+    progress: &Progress,
+) -> Result<(), SwitchError> {
+    let source_path = PathBuf::from(source.trim());
+    if !source_path.exists() {
+        return Err(format!("Source not found: {}", source_path.to_string_lossy()).into());
+    }
+    let source_path = fs::canonicalize(&source_path).unwrap_or(source_path);
+    let source_str = source_path.to_string_lossy().to_string();
+
+    // ls-remote understands bundles, bare repos and working copies alike, so it doubles
+    // as the "is this a git source" check before we touch the current install
+    let heads = git_output(&std::env::temp_dir(), &["ls-remote", "--heads", "--", &source_str])
+        .map_err(|e| format!("Not a git repository or bundle: {}", e))?;
+    if !heads.lines().any(|l| l.ends_with(&format!("refs/heads/{}", branch))) {
+        return Err(format!("Branch '{}' not found in {}", branch, source_str).into());
+    }
+
+    refuse_unpushed(&addon_dir(addon, blender_version)?, force)?;
+    // Local clones hardlink objects anyway, so history costs next to nothing
+    Ok(clone_install(addon, &source_str, None, branch, None, blender_version, progress)?)
+}
+
+/// Replace the install with a fresh clone of `branch_name`, snapshotting the old one first
+/// when auto-backup is on and recording where we came from in the install marker.
+fn install_branch(addon: &Addon, branch_name: &str, blender_version: &str, progress: &Progress) -> Result<(), String> {
+    install_branch_from(addon, &addon.url(), branch_name, blender_version, progress)
+}
+
+/// `install_branch` from a configured fork instead of the official repo
+fn install_branch_from(
+    addon: &Addon,
+    url: &str,
+    branch_name: &str,
+    blender_version: &str,
+    progress: &Progress,
+) -> Result<(), String> {
+    let depth = load_settings().map(|s| s.clone_depth).unwrap_or_else(|_| default_clone_depth());
+    clone_install(addon, url, depth, branch_name, None, blender_version, progress)?;
+    pycache::after_update(addon, blender_version);
+    Ok(())
+}
+
+/// Snapshot the install about to be replaced, when auto-backup is on, so the change can
+/// be reverted with local edits intact
+fn pre_switch_backup(
+    addon: &Addon,
+    addon_path: &Path,
+    blender_version: &str,
+    progress: &Progress,
+) -> Result<(), String> {
+    if !addon_path.exists() || !auto_backup_enabled() {
+        return Ok(());
+    }
+    snapshot_install(addon, addon_path, blender_version, progress)
+}
+
+fn auto_backup_enabled() -> bool {
+    load_settings().map(|s| s.auto_backup).unwrap_or(true)
+}
+
+/// Replace the pre-switch snapshot with a copy of the install as it is now
+fn snapshot_install(
+    addon: &Addon,
+    addon_path: &Path,
+    blender_version: &str,
+    progress: &Progress,
+) -> Result<(), String> {
+    progress.phase("backing up");
+    let backup = pre_switch_backup_dir(addon, blender_version)?;
+    remove_dir_robust(&backup)?;
+    copy_dir_all(addon_path, &backup, CopyOptions::from_settings(), progress)
+        .map_err(|e| format!("Failed to back up before switching: {}", e))?;
+    backups::write_meta(addon_path, &backup)
+}
+
+/// `depth` of None clones the full history. With a `commit`, the install's branch is
+/// reset to that commit, which must be in the branch's history.
+fn clone_install(
+    addon: &Addon,
+    source: &str,
+    depth: Option<u32>,
+    branch_name: &str,
+    commit: Option<&str>,
+    blender_version: &str,
+    progress: &Progress,
+) -> Result<(), String> {
+    let addon_path = addon_dir(addon, blender_version)?;
+    clone_install_at(addon, &addon_path, source, depth, branch_name, commit, blender_version, progress)
+}
+
+/// `clone_install` into any folder - the addons dir or an extensions repo
+#[allow(clippy::too_many_arguments)]
+fn clone_install_at(
+    addon: &Addon,
+    addon_path: &Path,
+    source: &str,
+    depth: Option<u32>,
+    branch_name: &str,
+    commit: Option<&str>,
+    blender_version: &str,
+    progress: &Progress,
+) -> Result<(), String> {
+    let addons_path = addon_path.parent().ok_or("Invalid install path")?.to_path_buf();
+    let addon_path = addon_path.to_path_buf();
+    let addon_path_str = addon_path.to_string_lossy().to_string();
+
+    // Ensure addons directory exists
+    fs::create_dir_all(&addons_path).map_err(|e| format!("Failed to create addons dir: {}", e))?;
+
+    // Remember what was installed so the switch can be reverted
+    let old_marker = read_marker(&addon_path);
+    let old_branch = old_marker
+        .as_ref()
+        .and_then(|m| m.branch.clone())
+        .or_else(|| git_output(&addon_path, &["rev-parse", "--abbrev-ref", "HEAD"]).ok());
+    let previous_branch = if old_branch.as_deref() == Some(branch_name) {
+        old_marker.and_then(|m| m.previous_branch)
+    } else {
+        old_branch
+    };
+
+    pre_switch_backup(addon, &addon_path, blender_version, progress)?;
+
+    // Clone next to the install and swap it in at the end, so a failed clone leaves
+    // the current install untouched
+    let staging = StagingDir::new(&addons_path, "clone");
+    let staging_path = staging.path().to_path_buf();
+    // From here on a failure keeps the downloaded clone for `retry_last_operation`
+    let keep = |e: String| {
+        retry::keep_partial(addon, &staging_path, &addons_path);
+        e
+    };
+    let resumed = retry::take_partial(addon, &addons_path, source, branch_name, &staging_path);
+
+    let reference = if resumed { None } else { mirror::prepare(addon, source, branch_name, progress) };
+    let mut mode = clone_mode(depth);
+    let mut output = if resumed {
+        progress.phase("resuming");
+        if retry::partial_is_minimal(&staging_path) {
+            mode = MINIMAL_CLONE;
+        }
+        retry::resume_fetch(&staging_path, branch_name, depth, progress).map_err(keep)?
+    } else {
+        progress.phase("cloning");
+        run_clone(source, branch_name, &staging_path, depth, false, reference.as_deref(), progress)?
+    };
+    if resumed && !output.status.success() {
+        return Err(keep(format!("Updating the kept clone failed:\n{}", String::from_utf8_lossy(&output.stderr))));
+    }
+    if !output.status.success()
+        && mode != MINIMAL_CLONE
+        && is_network_error(&String::from_utf8_lossy(&output.stderr))
+        && load_settings().map(|s| s.minimal_clone_fallback).unwrap_or(true)
+    {
+        applog::warn(&format!(
+            "Clone of {} failed on the network, retrying as a minimal clone: {}",
+            branch_name,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+        progress.phase("retrying with a minimal clone");
+        if staging_path.exists() {
+            fs::remove_dir_all(&staging_path).ok();
+        }
+        mode = MINIMAL_CLONE;
+        output = run_clone(source, branch_name, &staging_path, Some(1), true, reference.as_deref(), progress)?;
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    if !output.status.success() {
+        return Err(format!("Git clone failed:\n{}\n{}", stdout, stderr));
+    }
+    applog::info(&format!("{} {} ({} clone)", if resumed { "Resumed" } else { "Cloned" }, branch_name, mode));
+
+    if let Some(commit) = commit {
+        progress.phase("checking out commit");
+        reset_to_commit(&staging_path, branch_name, commit).map_err(keep)?;
+    }
+
+    // Verify files were actually cloned
+    progress.phase("verifying");
+    let init_file = staging_path.join("__init__.py");
+    if !init_file.exists() {
+        return Err(format!(
+            "Clone completed but __init__.py not found. The branch '{}' may not contain the addon.\nPath: {}\nGit output:\n{}{}",
+            branch_name, addon_path_str, stdout, stderr
+        ));
+    }
+
+    if let Ok(settings) = load_settings() {
+        if !settings.git_user_name.is_empty() && !settings.git_user_email.is_empty() {
+            repo::apply_git_identity(&staging_path, &settings.git_user_name, &settings.git_user_email).map_err(keep)?;
+        }
+        remotes::add_configured_remotes(addon, &staging_path, source);
+        if settings.fix_permissions {
+            progress.phase("fixing permissions");
+            permissions::normalize(&staging_path).map_err(keep)?;
+        }
+    }
+
+    progress.phase("finishing");
+    write_marker(
+        addon,
+        &staging_path,
+        &InstallMarker {
+            branch: Some(branch_name.to_string()),
+            commit: git_output(&staging_path, &["rev-parse", "HEAD"]).ok(),
+            installed_at: Some(chrono::Local::now().to_rfc3339()),
+            previous_branch,
+            clone_mode: Some(mode.to_string()),
+            ..Default::default()
+        },
+    )
+    .map_err(keep)?;
+
+    progress.phase("swapping");
+    swap_into_place(&staging, &addon_path).map_err(keep)
+}
+
+/// The clone taken when the configured one fails on the network: one commit, no other
+/// branches, and file contents only for the checkout
+const MINIMAL_CLONE: &str = "minimal";
+
+/// git's wording for failures a flaky or slow connection causes, as opposed to a missing
+/// branch or a bad URL
+const NETWORK_ERRORS: &[&str] = &[
+    "could not resolve host",
+    "failed to connect",
+    "connection timed out",
+    "operation timed out",
+    "connection reset",
+    "connection refused",
+    "early eof",
+    "rpc failed",
+    "the remote end hung up unexpectedly",
+    "unexpected disconnect",
+    "transfer closed",
+    "gnutls_handshake",
+    "ssl_read",
+    "ssl_connect",
+    "http/2 stream",
+    "invalid index-pack output",
+];
+
+fn is_network_error(stderr: &str) -> bool {
+    let stderr = stderr.to_lowercase();
+    NETWORK_ERRORS.iter().any(|pattern| stderr.contains(pattern))
+}
+
+/// How a clone of `depth` is recorded: "full" or "shallow"
+fn clone_mode(depth: Option<u32>) -> &'static str {
+    if depth.is_some() {
+        "shallow"
+    } else {
+        "full"
+    }
+}
+
+/// Clone the specific branch - call git directly with separate args
+fn run_clone(
+    source: &str,
+    branch_name: &str,
+    staging_path: &Path,
+    depth: Option<u32>,
+    blobless: bool,
+    reference: Option<&Path>,
+    progress: &Progress,
+) -> Result<std::process::Output, String> {
+    let mut clone = gitbin::git_command();
+    clone
+        .arg("clone")
+        .arg("--progress")
+        .arg("--branch")
+        .arg(branch_name)
+        .arg("--single-branch");
+    if let Some(reference) = reference {
+        // Objects the mirror has aren't downloaded, and are copied in afterwards so the
+        // install stands alone
+        clone.arg("--reference-if-able").arg(reference).arg("--dissociate");
+    }
+    if let Some(depth) = depth {
+        clone.arg("--depth").arg(depth.to_string());
+    }
+    if blobless {
+        clone.arg("--filter=blob:none");
+    }
+    clone.arg("--").arg(source).arg(staging_path);
+    if let Some(parent) = staging_path.parent() {
+        clone.current_dir(parent);
+    }
+    progress.output(&mut clone).map_err(|e| gitbin::run_error(&e))
+}
+
+/// Point the freshly cloned branch at `commit`, deepening a shallow clone if the commit
+/// is further back than it reaches.
+fn reset_to_commit(repo: &Path, branch_name: &str, commit: &str) -> Result<(), String> {
+    let object = format!("{}^{{commit}}", commit);
+    if git_output(repo, &["cat-file", "-e", &object]).is_err()
+        && git_output(repo, &["rev-parse", "--is-shallow-repository"]).as_deref() == Ok("true")
+    {
+        git_output(repo, &["fetch", "--quiet", "--unshallow", "origin"])
+            .map_err(|e| format!("Failed to fetch history: {}", e))?;
+    }
+    let full = git_output(repo, &["rev-parse", "--verify", "--quiet", &object])
+        .map_err(|_| format!("Commit {} doesn't exist on the remote", commit))?;
+    if git_output(repo, &["merge-base", "--is-ancestor", &full, "HEAD"]).is_err() {
+        return Err(format!("Commit {} isn't on branch '{}'", commit, branch_name));
+    }
+    git_output(repo, &["reset", "--hard", "--quiet", &full])
+        .map_err(|e| format!("Failed to check out {}: {}", commit, e))?;
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RevertResult {
+    branch: String,
+    /// "backup" when the exact pre-switch state was restored, "clone" for a fresh clone
+    method: String,
+}
+
+#[tauri::command]
+fn revert_branch(
+    blender_version: String,
+    force: Option<bool>,
+    addon_id: Option<String>,
+    ops: State<'_, OperationState>,
+) -> Result<RevertResult, SwitchError> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    let guard = ops.begin("revert_branch", &blender_version)?;
+    let result = revert(&addon, &blender_version, force.unwrap_or(false), &guard.progress());
+    guard.finish(&result.as_ref().map_err(|e| e.message.clone()));
+    result
+}
+
+/// Put the previous branch back from the pre-switch snapshot, or failing that a fresh
+/// clone - only the clone can lose commits, so only it is refused over them
+fn revert(addon: &Addon, blender_version: &str, force: bool, progress: &Progress) -> Result<RevertResult, SwitchError> {
+    let addon_path = addon_dir(addon, blender_version)?;
+
+    let marker = read_marker(&addon_path).ok_or("No switch history recorded for this install".to_string())?;
+    let previous = marker.previous_branch.clone().ok_or("No previous branch to revert to".to_string())?;
+
+    // Prefer the pre-switch snapshot - it keeps any local edits the user had
+    let backup = pre_switch_backup_dir(addon, blender_version)?;
+    let backup_branch = read_marker(&backup)
+        .and_then(|m| m.branch)
+        .or_else(|| git_output(&backup, &["rev-parse", "--abbrev-ref", "HEAD"]).ok());
+
+    if backup.exists() && backup_branch.as_deref() == Some(previous.as_str()) {
+        // Swap: the install we're leaving becomes the new pre-switch snapshot
+        let staging = backup.with_file_name("pre_switch_staging");
+        let options = CopyOptions::from_settings();
+        remove_dir_robust(&staging)?;
+        copy_dir_all(&addon_path, &staging, options, progress)
+            .map_err(|e| format!("Failed to snapshot current install: {}", e))?;
+        backups::write_meta(&addon_path, &staging)?;
+        remove_dir_robust(&addon_path)?;
+        copy_dir_all(&backup, &addon_path, options, progress).map_err(|e| format!("Failed to restore: {}", e))?;
+        backups::strip_meta(&addon_path);
+        remove_dir_robust(&backup).ok();
+        fs::rename(&staging, &backup).ok();
+
+        let mut restored = read_marker(&addon_path).unwrap_or_default();
+        restored.branch = Some(previous.clone());
+        restored.previous_branch = marker.branch;
+        write_marker(addon, &addon_path, &restored)?;
+
+        return Ok(RevertResult { branch: previous, method: "backup".to_string() });
+    }
+
+    refuse_unpushed(&addon_path, force)?;
+    install_branch(addon, &previous, blender_version, progress)?;
+    Ok(RevertResult { branch: previous, method: "clone".to_string() })
+}
+
+#[tauri::command]
+fn pull_latest(
+    blender_version: String,
+    addon_id: Option<String>,
+    app: AppHandle,
+    ops: State<'_, OperationState>,
+) -> Result<Timed<bool>, String> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    let guard = ops.begin("pull_latest", &blender_version)?;
+    let result = guard.finish_timed(git_pull(&addon, &blender_version, &guard.progress()).map(|_| true));
+    retry::note_outcome(
+        &addon,
+        &app,
+        retry::FailedOperation::new("pull_latest", &blender_version, None, None, false),
+        result.as_ref().err(),
+    );
+    let summary = result
+        .as_ref()
+        .map(|_| format!("Pulled the latest changes{}", notify::commit_suffix(&addon, &blender_version)));
+    notify::operation_finished(&app, "Pull", &summary.map_err(Clone::clone));
+    notices::refresh(&addon, &app, &blender_version);
+    result
+}
+
+fn git_pull(addon: &Addon, blender_version: &str, progress: &Progress) -> Result<(), String> {
+    progress.phase("validating");
+    let addon_path = addon_dir(addon, blender_version)?;
+
+    match detect_origin(&addon_path) {
+        None => return Err("No installation found".to_string()),
+        Some(InstallOrigin::Archive) => {
+            return Err("This install has no git history (it was unpacked from an archive) - switch to a branch to get one that can pull".to_string())
+        }
+        Some(_) => {}
+    }
+    if let Some(kind) = repo::interrupted_pull(&addon_path) {
+        return Err(format!("A previous update stopped mid-{} - fix the stuck update first", kind));
+    }
+
+    progress.phase("pulling");
+    let output = progress
+        .output(gitbin::git_command().args(["pull", "--progress"]).current_dir(&addon_path))
+        .map_err(|e| gitbin::run_error(&e))?;
+
+    if output.status.success() {
+        pycache::after_update(addon, blender_version);
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).to_string())
+    }
+}
+
+#[tauri::command]
+fn open_folder(blender_version: String) -> Result<bool, String> {
+    let addons_path = addons_dir(&blender_version)?;
+
+    fs::create_dir_all(&addons_path).ok();
+
+    Command::new("explorer")
+        .arg(&addons_path)
+        .spawn()
+        .map_err(|e| format!("Failed to open explorer: {}", e))?;
+
+    Ok(true)
+}
+
+#[tauri::command]
+fn load_settings() -> Result<Settings, String> {
+    let settings_path = data_dir()?.join("settings.json");
+
+    if settings_path.exists() {
+        let content = fs::read_to_string(&settings_path).map_err(|e| format!("Failed to read settings: {}", e))?;
+        serde_json::from_str(&content).map_err(|e| format!("Failed to parse settings: {}", e))
+    } else {
+        Ok(Settings::default())
+    }
+}
+
+/// Suggest the Blender version the user actually runs - whichever config folder was
+/// touched most recently. Saved as the default when there is no settings file yet.
+#[tauri::command]
+fn first_run_detect(state: State<'_, SettingsState>) -> Result<Option<String>, String> {
+    let Ok(entries) = fs::read_dir(blender_config_root()?) else { return Ok(None) };
+
+    // userpref.blend is rewritten whenever that Blender saves its preferences, which
+    // tracks actual use better than the folder timestamp alone
+    let last_used = |dir: &Path| {
+        [dir.to_path_buf(), dir.join("config"), dir.join("config").join("userpref.blend")]
+            .iter()
+            .filter_map(|p| fs::metadata(p).and_then(|m| m.modified()).ok())
+            .max()
+    };
+    let detected = entries
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .filter(|e| blender::is_version_dir_name(&e.file_name().to_string_lossy()))
+        .filter_map(|e| last_used(&e.path()).map(|t| (t, e.file_name().to_string_lossy().to_string())))
+        .max()
+        .map(|(_, version)| version);
+
+    if let Some(version) = &detected {
+        if !data_dir()?.join("settings.json").exists() {
+            let mut settings = load_settings()?;
+            settings.blender_version = version.clone();
+            state.save_now(&settings)?;
+        }
+    }
+    Ok(detected)
+}
+
+/// How long a save waits for a newer one before writing, so a burst of saves from
+/// the UI (a field saved on every keystroke) ends up as a single write
+const SETTINGS_DEBOUNCE: Duration = Duration::from_millis(150);
+/// A superseded save gives up waiting for the newer one after this
+const SETTINGS_WAIT: Duration = Duration::from_secs(5);
+
+/// Serializes settings writes. Every save takes a sequence number; once it has the lock,
+/// a save that a newer one overtook skips writing and waits for the newer one instead.
+#[derive(Default)]
+struct SettingsState {
+    latest: AtomicU64,
+    /// Sequence number of the last save that finished, written or not
+    finished: Mutex<u64>,
+    done: Condvar,
+}
+
+impl SettingsState {
+    /// Coalesce with whatever else is being saved right now; the newest wins
+    fn save_latest(&self, settings: Settings) -> Result<Settings, String> {
+        let seq = self.latest.fetch_add(1, Ordering::SeqCst) + 1;
+        std::thread::sleep(SETTINGS_DEBOUNCE);
+        let mut finished = self.finished.lock().unwrap_or_else(|e| e.into_inner());
+        if self.latest.load(Ordering::SeqCst) != seq {
+            let (finished, _) = self
+                .done
+                .wait_timeout_while(finished, SETTINGS_WAIT, |finished| *finished <= seq)
+                .unwrap_or_else(|e| e.into_inner());
+            drop(finished);
+            return load_settings();
+        }
+        let result = write_settings(&settings);
+        *finished = (*finished).max(seq);
+        self.done.notify_all();
+        result.map(|_| settings)
+    }
+
+    /// Write right away, still one writer at a time; pending older saves are dropped
+    fn save_now(&self, settings: &Settings) -> Result<(), String> {
+        let seq = self.latest.fetch_add(1, Ordering::SeqCst) + 1;
+        let mut finished = self.finished.lock().unwrap_or_else(|e| e.into_inner());
+        let result = write_settings(settings);
+        *finished = (*finished).max(seq);
+        self.done.notify_all();
+        result
+    }
+}
+
+/// Save and return what ended up on disk - a newer save arriving at the same time wins
+#[tauri::command]
+async fn save_settings(settings: Settings, app: AppHandle) -> Result<Settings, String> {
+    tokio::task::spawn_blocking(move || {
+        let saved = app.state::<SettingsState>().save_latest(settings);
+        app.state::<updates::CheckerWake>().notify();
+        saved
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+fn write_settings(settings: &Settings) -> Result<(), String> {
+    if settings.clone_depth == Some(0) {
+        return Err("Clone depth must be at least 1 (or empty for full history)".to_string());
+    }
+    remotes::validate_remotes(&settings.remotes)?;
+    addons::validate_addons(&settings.addons)?;
+    profiles::validate_profiles(&settings.profiles)?;
+    if settings.cloud_placeholders != "download" && settings.cloud_placeholders != "skip" {
+        return Err(format!("Unknown cloud placeholder handling '{}'", settings.cloud_placeholders));
+    }
+    if settings.preferred_protocol != giturl::HTTPS && settings.preferred_protocol != giturl::SSH {
+        return Err(format!("Unknown protocol '{}': use https or ssh", settings.preferred_protocol));
+    }
+    if !settings.git_path.trim().is_empty() {
+        gitbin::check_path(&settings.git_path)?;
+    }
+    if !settings.custom_path.trim().is_empty() && !Path::new(settings.custom_path.trim()).is_dir() {
+        return Err(format!("Custom Addons Path {} doesn't exist or isn't a folder", settings.custom_path.trim()));
+    }
+
+    let settings_dir = data_dir()?;
+    let settings_path = settings_dir.join("settings.json");
+
+    fs::create_dir_all(&settings_dir).map_err(|e| format!("Failed to create settings dir: {}", e))?;
+
+    let content = serde_json::to_string_pretty(settings).map_err(|e| format!("Failed to serialize settings: {}", e))?;
+
+    // Write beside and rename over, so a crash mid-write can't leave half a file
+    let temp_path = settings_dir.join("settings.json.tmp");
+    fs::write(&temp_path, content).map_err(|e| format!("Failed to write settings: {}", e))?;
+    fs::rename(&temp_path, &settings_path).map_err(|e| format!("Failed to write settings: {}", e))?;
+    gitbin::forget();
+
+    // Policy can lock the startup locations - surface that rather than pretending it worked
+    autostart::apply(settings.launch_on_startup).map_err(|e| format!("Settings saved. {}", e))?;
+
+    Ok(())
+}
+
+/// A setting that differs from its default, by its name in settings.json
+#[derive(Debug, Serialize, Deserialize)]
+struct SettingDiff {
+    field: String,
+    value: serde_json::Value,
+    default: serde_json::Value,
+}
+
+fn settings_json(settings: &Settings) -> Result<serde_json::Map<String, serde_json::Value>, String> {
+    match serde_json::to_value(settings).map_err(|e| format!("Failed to serialize settings: {}", e))? {
+        serde_json::Value::Object(map) => Ok(map),
+        _ => Err("Settings didn't serialize to an object".to_string()),
+    }
+}
+
+/// Only the settings the user has changed, for support and bug reports
+#[tauri::command]
+fn settings_diff() -> Result<Vec<SettingDiff>, String> {
+    let current = settings_json(&load_settings()?)?;
+    let defaults = settings_json(&Settings::default())?;
+    Ok(current
+        .into_iter()
+        .filter_map(|(field, value)| {
+            let default = defaults.get(&field).cloned().unwrap_or(serde_json::Value::Null);
+            (value != default).then_some(SettingDiff { field, value, default })
+        })
+        .collect())
+}
+
+/// Put one setting back to its default and keep everything else
+#[tauri::command]
+fn reset_settings_field(field_name: String, state: State<'_, SettingsState>) -> Result<bool, String> {
+    let mut current = settings_json(&load_settings()?)?;
+    let defaults = settings_json(&Settings::default())?;
+    let default = defaults.get(&field_name).cloned().ok_or_else(|| {
+        let known: Vec<&str> = defaults.keys().map(String::as_str).collect();
+        format!("Unknown setting \"{}\" (expected one of: {})", field_name, known.join(", "))
+    })?;
+    current.insert(field_name, default);
+    let settings: Settings = serde_json::from_value(serde_json::Value::Object(current))
+        .map_err(|e| format!("Failed to rebuild settings: {}", e))?;
+    state.save_now(&settings)?;
+    Ok(true)
+}
+
+fn main() {
+    if let Some(code) = cli::run() {
+        std::process::exit(code);
+    }
+
+    tauri::Builder::default()
+        // Must come first: a second launch (e.g. from a clicked link) hands its
+        // arguments to this instance and exits
+        .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| tray::show_window(app)))
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_shell::init())
+        .plugin(tauri_plugin_notification::init())
+        .manage(OperationState::default())
+        .manage(updates::UpdateState::default())
+        .manage(updates::CheckerWake::default())
+        .manage(updates::BackgroundGate::default())
+        .manage(remote::BranchFileCache::default())
+        .manage(autostart::AutostartState::default())
+        .manage(deeplink::DeepLinkState::default())
+        .manage(notices::NoticeState::default())
+        .manage(divergence::DivergenceCache::default())
+        .manage(SettingsState::default())
+        .manage(selfupdate::SelfUpdateState::default())
+        .setup(|app| {
+            // The window starts hidden (see tauri.conf.json) so it can be put back where
+            // it was before anyone sees it
+            if let Some(window) = app.get_webview_window("main") {
+                let was_in_tray = window_state::restore(&window);
+                let tray_mode = load_settings().map(|s| s.minimize_to_tray).unwrap_or(false);
+                let start_in_tray =
+                    std::env::args().any(|a| a == autostart::MINIMIZED_ARG) || (tray_mode && was_in_tray);
+                if !start_in_tray {
+                    window.show().ok();
+                }
+            }
+            app.manage(window_state::spawn_saver(app.handle().clone()));
+            autostart::repair_on_launch(&app.state::<autostart::AutostartState>());
+            tray::create(app.handle())?;
+            deeplink::setup(app.handle());
+            updates::spawn_update_checker(app.handle().clone());
+            backups::spawn_backup_scheduler(app.handle().clone());
+            appupdate::spawn_app_update_checker(app.handle().clone());
+            std::thread::spawn(tempcleanup::clean_all);
+            // Fixed up front so the first save doesn't fail; the frontend asks again to show why
+            settingsdir::check_settings_dir().ok();
+            // Git and the network wait for the user unless launch checks are on
+            if load_settings().map(|s| s.check_on_launch).unwrap_or(true) {
+                updates::allow_background(app.handle());
+            }
+            Ok(())
+        })
+        .on_window_event(|window, event| match event {
+            tauri::WindowEvent::CloseRequested { api, .. }
+                if load_settings().map(|s| s.minimize_to_tray).unwrap_or(false) =>
+            {
+                api.prevent_close();
+                window.hide().ok();
+                window_state::changed(window.app_handle());
+            }
+            tauri::WindowEvent::DragDrop(tauri::DragDropEvent::Drop { paths, .. }) => {
+                zipinstall::handle_drop(window.app_handle(), paths);
+            }
+            tauri::WindowEvent::Moved(_) | tauri::WindowEvent::Resized(_) => {
+                window_state::changed(window.app_handle());
+            }
+            _ => {}
+        })
+        .invoke_handler(tauri::generate_handler![
+            check_installation,
+            check_all_installations,
+            fetch_branches,
+            remotes::list_remotes,
+            remotes::fetch_forks,
+            remotes::add_fork_remote,
+            extensions::install_as_extension,
+            extensions::detect_extension_repos,
+            backup_installation,
+            restore_backup,
+            switch_branch,
+            revert_branch,
+            install_from_source,
+            zipinstall::install_from_zip,
+            folderinstall::install_from_folder,
+            uninstall::uninstall_addon,
+            pull_latest,
+            open_folder,
+            load_settings,
+            save_settings,
+            settings_diff,
+            reset_settings_field,
+            first_run_detect,
+            switch_branch_all,
+            spec::get_install_spec,
+            spec::apply_install_spec,
+            install_origin,
+            blender::detect_blender_executables,
+            archive::build_addon_zip,
+            archive::build_extension,
+            repo::optimize_repo,
+            repo::set_git_identity,
+            repo::get_git_identity,
+            repo::get_file_mode,
+            repo::set_file_mode,
+            repo::sync_with_upstream,
+            repo::abort_merge,
+            repo::export_bundle,
+            repo::attach_to_branch,
+            stash::list_stashes,
+            stash::apply_stash,
+            stash::drop_stash,
+            validate::validate_ref_name,
+            notices::get_notices,
+            issuereport::prepare_issue_report,
+            remote::get_branch_readme,
+            remote::compare_branch_trees,
+            remote::estimate_clone_size,
+            netcheck::check_remote,
+            credentials::check_git_credentials,
+            syncfolders::check_sync_folder,
+            tempcleanup::cleanup_temp_dirs,
+            branchexport::export_branches,
+            safeinstall::install_and_verify,
+            settingsdir::check_settings_dir,
+            appinfo::app_info,
+            mirror::mirror_cache_info,
+            mirror::clear_mirror_cache,
+            updates::last_update_status,
+            datadir::relocate_data_dir,
+            checks::run_addon_tests,
+            checks::verify_loads,
+            checks::lint_addon,
+            fingerprint::install_fingerprint,
+            verify::verify_installation,
+            verify::repair_installation,
+            verify::is_pristine,
+            pycache::list_pycache,
+            pycache::clear_pycache,
+            nesting::fix_nested_install,
+            gitbin::git_info,
+            knowngood::check_known_good,
+            remote::get_branch_file,
+            remote::get_remote_log,
+            remote::get_remote_tree,
+            branchsync::branch_sync_overview,
+            addons::list_addons,
+            profiles::save_profile,
+            profiles::apply_profile,
+            profiles::list_profiles,
+            profiles::delete_profile,
+            userdirs::blender_user_dirs,
+            retry::last_failed_operation,
+            retry::retry_last_operation,
+            switchpreview::preview_switch,
+            restorepoints::tag_state,
+            restorepoints::list_states,
+            restorepoints::restore_state,
+            restorepoints::delete_state,
+            updates::check_for_updates,
+            updates::user_active,
+            appupdate::check_app_update,
+            appupdate::check_manager_update,
+            selfupdate::download_app_update,
+            selfupdate::install_app_update_and_restart,
+            backups::list_backups,
+            backups::last_backup_time,
+            autostart::get_autostart_status,
+            deeplink::take_pending_deep_link,
+            ops::cancel_operation,
+            ops::pause_operation,
+            ops::resume_operation,
+            ops::current_operation,
+            ops::get_active_operation
+        ])
+        .run(tauri::generate_context!())
+        .expect("error while running tauri application");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::scratch;
+    use filetime::FileTime;
+
+    fn mtime(path: &Path) -> FileTime {
+        FileTime::from_last_modification_time(&fs::metadata(path).unwrap())
+    }
+
+    #[test]
+    fn copy_keeps_mtimes_and_modes() {
+        let dir = scratch("main", "copy-metadata");
+        let (src, dst) = (dir.join("src"), dir.join("dst"));
+        fs::create_dir_all(src.join("tools")).unwrap();
+        fs::write(src.join("__init__.py"), "bl_info = {}\n").unwrap();
+        let script = src.join("tools").join("build.sh");
+        fs::write(&script, "#!/bin/sh\n").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        let old = FileTime::from_unix_time(1_600_000_000, 0);
+        filetime::set_file_mtime(&script, old).unwrap();
+        filetime::set_file_mtime(src.join("tools"), old).unwrap();
+
+        copy_dir_all(&src, &dst, CopyOptions::default(), &Progress::none()).unwrap();
+        let copied = dst.join("tools").join("build.sh");
+        assert_eq!(fs::read_to_string(&copied).unwrap(), "#!/bin/sh\n");
+        assert_eq!(mtime(&copied), old);
+        assert_eq!(mtime(&dst.join("tools")), old);
+        assert_eq!(mtime(&dst.join("__init__.py")), mtime(&src.join("__init__.py")));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(fs::metadata(&copied).unwrap().permissions().mode() & 0o777, 0o755);
+        }
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn read_only_file_in_a_copy_can_be_deleted() {
+        let dir = scratch("main", "copy-read-only");
+        let (src, dst) = (dir.join("src"), dir.join("dst"));
+        fs::create_dir_all(src.join(".git").join("objects")).unwrap();
+        let pack = src.join(".git").join("objects").join("pack-1.pack");
+        fs::write(&pack, "pack").unwrap();
+        let mut perms = fs::metadata(&pack).unwrap().permissions();
+        perms.set_readonly(true);
+        fs::set_permissions(&pack, perms).unwrap();
+
+        copy_dir_all(&src, &dst, CopyOptions::default(), &Progress::none()).unwrap();
+        let copied = dst.join(".git").join("objects").join("pack-1.pack");
+        assert!(fs::metadata(&copied).unwrap().permissions().readonly());
+        remove_dir_checked(&dst).unwrap();
+        assert!(!dst.exists());
+
+        make_writable(&pack);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn links_are_recreated_only_when_asked() {
+        let dir = scratch("main", "copy-links");
+        let src = dir.join("src");
+        fs::create_dir_all(&src).unwrap();
+        fs::write(src.join("__init__.py"), "").unwrap();
+        std::os::unix::fs::symlink("__init__.py", src.join("alias.py")).unwrap();
+
+        copy_dir_all(&src, &dir.join("skipped"), CopyOptions::default(), &Progress::none()).unwrap();
+        assert!(fs::symlink_metadata(dir.join("skipped").join("alias.py")).is_err());
+        let recreate = CopyOptions { recreate_links: true, ..Default::default() };
+        copy_dir_all(&src, &dir.join("linked"), recreate, &Progress::none()).unwrap();
+        assert_eq!(fs::read_link(dir.join("linked").join("alias.py")).unwrap(), Path::new("__init__.py"));
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    fn ago(seconds: i64) -> String {
+        let now = chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        relative_time(now - chrono::Duration::seconds(seconds), now)
+    }
+
+    #[test]
+    fn relative_time_bucket_boundaries() {
+        const MINUTE: i64 = 60;
+        const HOUR: i64 = 60 * MINUTE;
+        const DAY: i64 = 24 * HOUR;
+        let cases = [
+            (59, "just now"),
+            (MINUTE, "1 minute ago"),
+            (59 * MINUTE, "59 minutes ago"),
+            (60 * MINUTE, "1 hour ago"),
+            (23 * HOUR, "23 hours ago"),
+            (24 * HOUR, "1 day ago"),
+            (13 * DAY, "13 days ago"),
+            (14 * DAY, "2 weeks ago"),
+            (69 * DAY, "9 weeks ago"),
+            (70 * DAY, "2 months ago"),
+            (364 * DAY, "12 months ago"),
+            (365 * DAY, "1 year ago"),
+            (730 * DAY, "2 years ago"),
+        ];
+        for (seconds, expected) in cases {
+            assert_eq!(ago(seconds), expected, "{} seconds", seconds);
+        }
+    }
+
+    #[test]
+    fn relative_time_singular_and_plural() {
+        assert_eq!(ago(2 * 60), "2 minutes ago");
+        assert_eq!(ago(2 * 60 * 60 - 1), "1 hour ago");
+        assert_eq!(ago(2 * 60 * 60), "2 hours ago");
+        assert_eq!(ago(2 * 24 * 60 * 60), "2 days ago");
+    }
+
+    #[test]
+    fn relative_time_in_the_future_is_just_now() {
+        assert_eq!(ago(-1), "just now");
+        assert_eq!(ago(-3 * 24 * 60 * 60), "just now");
+    }
+
+    #[test]
+    fn relative_time_compares_across_time_zones() {
+        let now = chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let ahead = chrono::FixedOffset::east_opt(5 * 60 * 60).unwrap();
+        let then = (now - chrono::Duration::minutes(5)).with_timezone(&ahead);
+        assert_eq!(relative_time(then, now), "5 minutes ago");
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn file_held_open_is_reported_in_use() {
+        use std::os::windows::fs::OpenOptionsExt;
+        const FILE_SHARE_READ: u32 = 0x1;
+        const FILE_SHARE_WRITE: u32 = 0x2;
+
+        let dir = scratch("main", "delete-in-use");
+        let target = dir.join("scripting_nodes");
+        fs::create_dir_all(&target).unwrap();
+        fs::write(target.join("free.py"), "").unwrap();
+        let held = target.join("held.py");
+        fs::write(&held, "").unwrap();
+        // Without FILE_SHARE_DELETE nobody else can delete it while this is open
+        let handle = fs::OpenOptions::new()
+            .read(true)
+            .share_mode(FILE_SHARE_READ | FILE_SHARE_WRITE)
+            .open(&held)
+            .unwrap();
+
+        let error = remove_dir_checked(&target).unwrap_err();
+        assert_eq!(error.code, "FILES_IN_USE");
+        assert_eq!(error.paths, vec![held.to_string_lossy().to_string()]);
+        assert!(error.partial, "free.py should already be gone");
+        assert!(!target.join("free.py").exists());
+        assert!(held.exists());
+
+        drop(handle);
+        remove_dir_checked(&target).unwrap();
+        assert!(!target.exists());
+        fs::remove_dir_all(&dir).ok();
+    }
 }
@@ -0,0 +1,101 @@
+// Install marker - a small JSON file the manager leaves inside installs it created
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::addons::Addon;
+
+pub const MARKER_FILE: &str = ".serpens_manager.json";
+
+/// Who put the install there, which decides what the manager can safely do with it
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum InstallOrigin {
+    /// Created by the manager - the marker file is present
+    Manager,
+    /// A git checkout the user cloned themselves
+    Git,
+    /// Plain files with no git history, e.g. unpacked from a release zip
+    Archive,
+    /// A link to a folder elsewhere, typically a developer's working copy
+    Symlink,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct InstallMarker {
+    pub branch: Option<String>,
+    pub commit: Option<String>,
+    #[serde(rename = "installedAt")]
+    pub installed_at: Option<String>,
+    /// The branch that was installed before this one, for one-click revert
+    #[serde(rename = "previousBranch")]
+    pub previous_branch: Option<String>,
+    /// File name and SHA-256 of the zip, for installs made from one
+    #[serde(rename = "sourceZip", default)]
+    pub source_zip: Option<String>,
+    #[serde(rename = "sourceSha256", default)]
+    pub source_sha256: Option<String>,
+    /// The folder copied in, for installs made from a local directory
+    #[serde(rename = "sourcePath", default)]
+    pub source_path: Option<String>,
+    /// "full", "shallow", or "minimal" when the network forced a one-commit blobless
+    /// clone that can be deepened later
+    #[serde(rename = "cloneMode", default)]
+    pub clone_mode: Option<String>,
+    /// Id of the addon it holds, for installs of anything but Serpens
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub addon: Option<String>,
+}
+
+/// None when nothing is installed at `addon_path`
+pub fn detect_origin(addon_path: &Path) -> Option<InstallOrigin> {
+    let meta = fs::symlink_metadata(addon_path).ok()?;
+    Some(if meta.file_type().is_symlink() {
+        InstallOrigin::Symlink
+    } else if addon_path.join(MARKER_FILE).exists() {
+        InstallOrigin::Manager
+    } else if addon_path.join(".git").exists() {
+        InstallOrigin::Git
+    } else {
+        InstallOrigin::Archive
+    })
+}
+
+pub fn read_marker(addon_path: &Path) -> Option<InstallMarker> {
+    let content = fs::read_to_string(addon_path.join(MARKER_FILE)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+pub fn write_marker(addon: &Addon, addon_path: &Path, marker: &InstallMarker) -> Result<(), String> {
+    let marker = InstallMarker {
+        addon: (!addon.is_builtin()).then(|| addon.id.clone()),
+        ..marker.clone()
+    };
+    let content =
+        serde_json::to_string_pretty(&marker).map_err(|e| format!("Failed to serialize install marker: {}", e))?;
+    fs::write(addon_path.join(MARKER_FILE), content).map_err(|e| format!("Failed to write install marker: {}", e))?;
+    exclude_from_git(addon_path);
+    Ok(())
+}
+
+/// Keep the marker out of `git status` so it never reads as a local modification.
+fn exclude_from_git(addon_path: &Path) {
+    let info = addon_path.join(".git").join("info");
+    if !addon_path.join(".git").is_dir() {
+        return;
+    }
+    let exclude = info.join("exclude");
+    let existing = fs::read_to_string(&exclude).unwrap_or_default();
+    if existing.lines().any(|l| l.trim() == MARKER_FILE) {
+        return;
+    }
+    fs::create_dir_all(&info).ok();
+    let mut content = existing;
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    content.push_str(MARKER_FILE);
+    content.push('\n');
+    fs::write(exclude, content).ok();
+}
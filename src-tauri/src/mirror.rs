@@ -0,0 +1,102 @@
+// A bare copy of the repo kept in the data folder, so switching back to a branch seen
+// before only downloads what changed since. Clones borrow its objects and then copy
+// them (--dissociate), so an install never depends on the cache still being there.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::addons::Addon;
+use crate::ops::Progress;
+use crate::{addons, applog, dir_size, git_output, gitbin, giturl, load_settings, remove_dir_robust};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MirrorCache {
+    pub path: String,
+    #[serde(rename = "sizeBytes")]
+    pub size_bytes: u64,
+    /// Branches held, as "remote/branch"
+    pub branches: Vec<String>,
+}
+
+/// Each addon has its own, as its repos share no history with another addon's
+fn mirror_dir(addon: &Addon) -> Result<PathBuf, String> {
+    Ok(addon.data_root()?.join("cache").join(format!("{}.git", addon.folder_name)))
+}
+
+/// One bare repo serves every remote, since forks share most of their history; each
+/// remote's branches live under refs/mirrors/<key>/
+fn remote_key(addon: &Addon, url: &str) -> String {
+    if giturl::is_official(addon, url) {
+        return "origin".to_string();
+    }
+    // The https and SSH forms of a fork share its refs
+    let id = giturl::github_path(url)
+        .map(|path| path.to_lowercase())
+        .unwrap_or_else(|| url.to_string());
+    let digest = Sha256::digest(id.as_bytes());
+    digest.iter().take(6).map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Bring the mirror's copy of `branch` up to date and return the mirror to clone with,
+/// or None when the cache is off or couldn't be updated - the clone then goes straight
+/// to the remote as before
+pub fn prepare(addon: &Addon, url: &str, branch: &str, progress: &Progress) -> Option<PathBuf> {
+    if !load_settings().map(|s| s.mirror_cache).unwrap_or(true) {
+        return None;
+    }
+    let mirror = mirror_dir(addon).ok()?;
+    if !mirror.join("HEAD").exists() {
+        fs::create_dir_all(&mirror).ok()?;
+        if let Err(e) = git_output(&mirror, &["init", "--bare", "--quiet"]) {
+            applog::warn(&format!("Couldn't create the mirror cache: {}", e));
+            return None;
+        }
+    }
+
+    progress.phase("updating mirror");
+    let refspec = format!("+refs/heads/{}:refs/mirrors/{}/{}", branch, remote_key(addon, url), branch);
+    let mut fetch = gitbin::git_command();
+    fetch.args(["fetch", "--progress", "--no-tags", "--", url, &refspec]).current_dir(&mirror);
+    match progress.output(&mut fetch) {
+        Ok(output) if output.status.success() => Some(mirror),
+        Ok(output) => {
+            applog::warn(&format!(
+                "Couldn't update the mirror cache for {}, cloning without it: {}",
+                branch,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+            None
+        }
+        Err(e) => {
+            applog::warn(&format!("Couldn't run git to update the mirror cache: {}", e));
+            None
+        }
+    }
+}
+
+#[tauri::command]
+pub fn mirror_cache_info(addon_id: Option<String>) -> Result<MirrorCache, String> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    let mirror = mirror_dir(&addon)?;
+    let branches = git_output(&mirror, &["for-each-ref", "--format=%(refname)", "refs/mirrors/"])
+        .map(|refs| refs.lines().filter_map(|r| r.strip_prefix("refs/mirrors/")).map(str::to_string).collect())
+        .unwrap_or_default();
+    Ok(MirrorCache {
+        path: mirror.to_string_lossy().to_string(),
+        size_bytes: dir_size(&mirror),
+        branches,
+    })
+}
+
+/// Delete the mirror; the next switch starts it again from scratch
+#[tauri::command]
+pub fn clear_mirror_cache(addon_id: Option<String>) -> Result<u64, String> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    let mirror = mirror_dir(&addon)?;
+    let freed = dir_size(&mirror);
+    remove_dir_robust(&mirror)?;
+    applog::info(&format!("Cleared the mirror cache ({} bytes)", freed));
+    Ok(freed)
+}
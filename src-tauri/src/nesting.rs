@@ -0,0 +1,115 @@
+// Installs nested one folder too deep - addons/scripting_nodes/scripting_nodes/__init__.py -
+// which an archive with its own top folder or a manual copy produces. Blender only looks
+// for __init__.py directly inside the addon folder, so it doesn't see the addon at all.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+use crate::addons::Addon;
+use crate::fingerprint::is_manager_file;
+use crate::ops::OperationState;
+use crate::{addon_dir, addons, addons_dir, applog, remove_dir_checked, validate};
+
+/// Where the inner folder waits while the outer one is cleared; deliberately not a staging
+/// name, so temp cleanup never deletes the only copy if the app dies halfway
+const HOLDING_NAME: &str = ".serpens_unnest";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FlattenResult {
+    /// The folder that held the addon, relative to the addons folder
+    #[serde(rename = "movedFrom")]
+    pub moved_from: String,
+    /// Leftovers from the outer folder that were dropped, such as its `__pycache__`
+    pub discarded: Vec<String>,
+}
+
+/// The real addon folder when `addon_path` has no `__init__.py` of its own and holds
+/// exactly one subfolder that does, with only bytecode or our bookkeeping beside it
+pub fn nested_root(addon_path: &Path) -> Option<PathBuf> {
+    if addon_path.join("__init__.py").is_file() || addon_path.join(".git").exists() {
+        return None;
+    }
+    let mut inner = None;
+    for entry in fs::read_dir(addon_path).ok()?.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if is_leftover(&name) {
+            continue;
+        }
+        let path = entry.path();
+        if inner.is_some() || !path.is_dir() || !path.join("__init__.py").is_file() {
+            return None;
+        }
+        inner = Some(path);
+    }
+    inner
+}
+
+/// Left behind in the outer folder and safe to drop: bytecode, Finder and Explorer
+/// droppings, and our own files, which are carried over instead
+fn is_leftover(name: &str) -> bool {
+    name == "__pycache__" || name == ".DS_Store" || name.eq_ignore_ascii_case("desktop.ini") || is_manager_file(name)
+}
+
+/// Move the nested addon up into the addon folder itself
+#[tauri::command]
+pub async fn fix_nested_install(
+    blender_version: String,
+    addon_id: Option<String>,
+    ops: State<'_, OperationState>,
+) -> Result<FlattenResult, String> {
+    validate::blender_version(&blender_version)?;
+    let addon = addons::resolve(addon_id.as_deref())?;
+    let guard = ops.begin("fix_nested_install", &blender_version)?;
+    tokio::task::spawn_blocking(move || {
+        let result = flatten(&addon, &blender_version);
+        guard.finish(&result);
+        result
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+fn flatten(addon: &Addon, blender_version: &str) -> Result<FlattenResult, String> {
+    let addons_path = addons_dir(blender_version)?;
+    let addon_path = addon_dir(addon, blender_version)?;
+    if fs::symlink_metadata(&addon_path).is_ok_and(|m| m.file_type().is_symlink()) {
+        return Err("The addon folder is a link - fix the nesting in the folder it points to".to_string());
+    }
+    let inner = nested_root(&addon_path).ok_or("The install isn't nested - nothing to fix")?;
+    let moved_from = inner.strip_prefix(&addons_path).unwrap_or(&inner).to_string_lossy().replace('\\', "/");
+
+    let holding = addons_path.join(HOLDING_NAME);
+    if holding.exists() {
+        return Err(format!(
+            "{} is left from an earlier attempt - move its contents to {} by hand or delete it",
+            holding.display(),
+            addon_path.display()
+        ));
+    }
+    fs::rename(&inner, &holding).map_err(|e| format!("Failed to move {}: {}", inner.display(), e))?;
+
+    // The marker and manifest describe the install, so they move with it
+    let mut discarded = Vec::new();
+    for entry in fs::read_dir(&addon_path)
+        .map_err(|e| format!("Failed to read {}: {}", addon_path.display(), e))?
+        .flatten()
+    {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let carried = is_manager_file(&name)
+            && !holding.join(&name).exists()
+            && fs::rename(entry.path(), holding.join(&name)).is_ok();
+        if !carried {
+            discarded.push(format!("{}/{}", addon.folder_name, name));
+        }
+    }
+    let stranded = |e: String| {
+        format!("{} The addon is safe in {}; move it to {} by hand.", e, holding.display(), addon_path.display())
+    };
+    remove_dir_checked(&addon_path).map_err(|e| stranded(e.message))?;
+    fs::rename(&holding, &addon_path).map_err(|e| stranded(format!("Failed to move the addon back: {}.", e)))?;
+
+    applog::info(&format!("Moved the nested addon in {} up to {}", moved_from, addon_path.display()));
+    Ok(FlattenResult { moved_from, discarded })
+}
@@ -0,0 +1,298 @@
+// Connection diagnostics for the repo - which layer of the network is failing, rather
+// than git's "unable to access"
+
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::process::Stdio;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::{addons, gitbin, spec, REPO_URL};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const GIT_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Probe {
+    /// "dns", "tcp", "tls", "http" or "git"
+    pub layer: String,
+    /// "pass", "fail", or "skipped" when an earlier layer failed or it doesn't apply
+    pub status: String,
+    #[serde(rename = "latencyMs")]
+    pub latency_ms: Option<u64>,
+    pub detail: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RemoteCheck {
+    pub url: String,
+    pub host: String,
+    /// Proxy the probes went through, from git config or the environment
+    pub proxy: Option<String>,
+    /// Extra CA bundle trusted, from git config or the environment
+    #[serde(rename = "caFile")]
+    pub ca_file: Option<String>,
+    pub probes: Vec<Probe>,
+    pub ok: bool,
+}
+
+/// Probe the path to `repo_url` (the addon's repo by default) layer by layer: name
+/// lookup, TCP connect, TLS handshake, an HTTP request, and finally git itself. Uses the
+/// same proxy and CA bundle git would.
+#[tauri::command]
+pub async fn check_remote(repo_url: Option<String>, addon_id: Option<String>) -> Result<RemoteCheck, String> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    // Serpens is probed over https whatever the preferred protocol
+    let default = if addon.is_builtin() { REPO_URL.to_string() } else { spec::parse_repo(&addon.repo_url)? };
+    tokio::task::spawn_blocking(move || check(repo_url.as_deref().unwrap_or(&default)))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+}
+
+pub fn check(url: &str) -> Result<RemoteCheck, String> {
+    let (host, port) = host_port(url).ok_or_else(|| format!("Can't check '{}': only https URLs are supported", url))?;
+    let proxy = proxy_setting();
+    let ca_file = ca_setting();
+    // Behind a proxy, the proxy is what this machine actually has to reach
+    let (connect_host, connect_port) = match proxy.as_deref().and_then(host_port) {
+        Some(via) => via,
+        None => (host.clone(), port),
+    };
+
+    let mut probes = Vec::new();
+
+    let mut addr: Option<SocketAddr> = None;
+    run(&mut probes, "dns", || {
+        let found: Vec<SocketAddr> = (connect_host.as_str(), connect_port)
+            .to_socket_addrs()
+            .map_err(|e| format!("Couldn't resolve {}: {}", connect_host, e))?
+            .collect();
+        addr = found.first().copied();
+        found
+            .first()
+            .map(|a| format!("{} resolves to {}", connect_host, a.ip()))
+            .ok_or_else(|| format!("{} has no addresses", connect_host))
+    });
+
+    let mut stream: Option<TcpStream> = None;
+    run(&mut probes, "tcp", || {
+        let target = addr.ok_or("No address to connect to")?;
+        let tcp = TcpStream::connect_timeout(&target, CONNECT_TIMEOUT)
+            .map_err(|e| format!("Couldn't connect to {}: {}", target, e))?;
+        stream = Some(tcp);
+        Ok(format!("Connected to {}", target))
+    });
+
+    let tls = tls_config(ca_file.as_deref());
+    if proxy.is_some() {
+        // The handshake with the repo happens inside the proxy tunnel, covered by the HTTP probe
+        probes.push(skipped("tls", "checked through the proxy by the HTTP probe"));
+    } else {
+        run(&mut probes, "tls", || {
+            let config = tls.clone()?;
+            let tcp = stream.take().ok_or("No connection to secure")?;
+            handshake(config, &host, tcp)
+        });
+    }
+
+    run(&mut probes, "http", || http_probe(url, proxy.as_deref(), tls.clone()?));
+    run(&mut probes, "git", || git_probe(url));
+
+    Ok(RemoteCheck {
+        url: url.to_string(),
+        host,
+        proxy,
+        ca_file,
+        ok: probes.iter().all(|p| p.status != "fail"),
+        probes,
+    })
+}
+
+/// Time one layer, or skip it when an earlier one already failed
+fn run(probes: &mut Vec<Probe>, layer: &str, probe: impl FnOnce() -> Result<String, String>) {
+    if probes.iter().any(|p| p.status == "fail") {
+        probes.push(skipped(layer, "an earlier layer failed"));
+        return;
+    }
+    let started = Instant::now();
+    let outcome = probe();
+    probes.push(Probe {
+        layer: layer.to_string(),
+        status: if outcome.is_ok() { "pass" } else { "fail" }.to_string(),
+        latency_ms: Some(started.elapsed().as_millis() as u64),
+        detail: outcome.unwrap_or_else(|e| e),
+    });
+}
+
+fn skipped(layer: &str, why: &str) -> Probe {
+    Probe {
+        layer: layer.to_string(),
+        status: "skipped".to_string(),
+        latency_ms: None,
+        detail: format!("Skipped: {}", why),
+    }
+}
+
+/// Host and port of an http(s) URL; proxies are often given as plain "host:port"
+fn host_port(url: &str) -> Option<(String, u16)> {
+    let (default_port, rest) = if let Some(rest) = url.strip_prefix("https://") {
+        (443, rest)
+    } else if let Some(rest) = url.strip_prefix("http://") {
+        (80, rest)
+    } else {
+        (8080, url)
+    };
+    let authority = rest.split('/').next()?;
+    let authority = authority.rsplit('@').next()?;
+    let (host, port) = if let Some(v6) = authority.strip_prefix('[') {
+        // "[::1]:3128"
+        let (host, rest) = v6.split_once(']')?;
+        (host, rest.strip_prefix(':').map(str::parse).transpose().ok()?.unwrap_or(default_port))
+    } else {
+        match authority.rsplit_once(':') {
+            Some((host, port)) => (host, port.parse().ok()?),
+            None => (authority, default_port),
+        }
+    };
+    (!host.is_empty()).then(|| (host.to_string(), port))
+}
+
+/// A global/system git config value
+pub fn git_config(key: &str) -> Option<String> {
+    let output = gitbin::git_command().args(["config", "--get", key]).output().ok()?;
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !value.is_empty()).then_some(value)
+}
+
+/// git's own precedence: http.proxy, then the usual environment variables
+fn proxy_setting() -> Option<String> {
+    git_config("http.proxy").or_else(|| {
+        ["HTTPS_PROXY", "https_proxy", "ALL_PROXY", "all_proxy"]
+            .iter()
+            .find_map(|name| std::env::var(name).ok().filter(|v| !v.is_empty()))
+    })
+}
+
+fn ca_setting() -> Option<String> {
+    std::env::var("GIT_SSL_CAINFO")
+        .ok()
+        .filter(|v| !v.is_empty())
+        .or_else(|| git_config("http.sslCAInfo"))
+}
+
+/// The public roots plus the configured CA bundle, if any
+fn tls_config(ca_file: Option<&str>) -> Result<Arc<rustls::ClientConfig>, String> {
+    use rustls::pki_types::pem::PemObject;
+    use rustls::pki_types::CertificateDer;
+
+    let mut roots = rustls::RootCertStore::empty();
+    roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    if let Some(path) = ca_file {
+        let certs =
+            CertificateDer::pem_file_iter(path).map_err(|e| format!("Couldn't read CA bundle {}: {}", path, e))?;
+        for cert in certs {
+            let cert = cert.map_err(|e| format!("Bad certificate in {}: {}", path, e))?;
+            roots.add(cert).map_err(|e| format!("Unusable certificate in {}: {}", path, e))?;
+        }
+    }
+    let config = rustls::ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .map_err(|e| format!("TLS setup failed: {}", e))?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    Ok(Arc::new(config))
+}
+
+fn handshake(config: Arc<rustls::ClientConfig>, host: &str, mut tcp: TcpStream) -> Result<String, String> {
+    let name = rustls::pki_types::ServerName::try_from(host.to_string())
+        .map_err(|e| format!("Invalid host name {}: {}", host, e))?;
+    let mut conn = rustls::ClientConnection::new(config, name).map_err(|e| format!("TLS setup failed: {}", e))?;
+    tcp.set_read_timeout(Some(CONNECT_TIMEOUT)).ok();
+    tcp.set_write_timeout(Some(CONNECT_TIMEOUT)).ok();
+    while conn.is_handshaking() {
+        conn.complete_io(&mut tcp).map_err(|e| format!("TLS handshake failed: {}", e))?;
+    }
+    conn.send_close_notify();
+    conn.complete_io(&mut tcp).ok();
+    tcp.flush().ok();
+    let version = conn.protocol_version().map(|v| format!("{:?}", v)).unwrap_or_else(|| "TLS".to_string());
+    Ok(format!("Certificate for {} verified ({})", host, version.replace('_', ".")))
+}
+
+fn http_probe(url: &str, proxy: Option<&str>, tls: Arc<rustls::ClientConfig>) -> Result<String, String> {
+    let mut agent = ureq::AgentBuilder::new().timeout(CONNECT_TIMEOUT * 2).tls_config(tls);
+    if let Some(proxy) = proxy {
+        agent = agent.proxy(ureq::Proxy::new(proxy).map_err(|e| format!("Unusable proxy {}: {}", proxy, e))?);
+    }
+    match agent.build().head(url).set("User-Agent", "serpens-dev-manager").call() {
+        Ok(response) => Ok(format!("HTTP {} from {}", response.status(), response.get_url())),
+        Err(ureq::Error::Status(404, _)) => Err("HTTP 404 - the repository doesn't exist or is private".to_string()),
+        Err(ureq::Error::Status(407, _)) => Err("HTTP 407 - the proxy wants credentials".to_string()),
+        Err(ureq::Error::Status(code, _)) if code >= 500 => {
+            Err(format!("HTTP {} - the server is having trouble", code))
+        }
+        // Anything else still proves the server answered
+        Err(ureq::Error::Status(code, _)) => Ok(format!("HTTP {}", code)),
+        Err(ureq::Error::Transport(t)) => Err(format!("Request failed: {}", t)),
+    }
+}
+
+/// What the real operations do, with a timeout and without ever prompting for credentials
+fn git_probe(url: &str) -> Result<String, String> {
+    let stdout = ls_remote(url, &[], &[])?;
+    let heads = stdout.lines().count();
+    Ok(format!("git sees {} branch{}", heads, if heads == 1 { "" } else { "es" }))
+}
+
+/// `git ls-remote --heads` with `config` overrides ("key=value") and extra environment,
+/// giving up after GIT_TIMEOUT. Never prompts; the branch list on success.
+pub fn ls_remote(url: &str, config: &[&str], envs: &[(&str, &str)]) -> Result<String, String> {
+    let mut command = gitbin::git_command();
+    for entry in config {
+        command.args(["-c", entry]);
+    }
+    let mut child = command
+        .args(["ls-remote", "--heads", "--", url])
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .envs(envs.iter().copied())
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| gitbin::run_error(&e))?;
+    // Drain the pipes while waiting so a long branch list can't stall git
+    let drain = |pipe: Option<Box<dyn Read + Send>>| {
+        thread::spawn(move || {
+            let mut text = String::new();
+            if let Some(mut pipe) = pipe {
+                pipe.read_to_string(&mut text).ok();
+            }
+            text
+        })
+    };
+    let stdout = drain(child.stdout.take().map(|p| Box::new(p) as Box<dyn Read + Send>));
+    let stderr = drain(child.stderr.take().map(|p| Box::new(p) as Box<dyn Read + Send>));
+
+    let started = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if started.elapsed() > GIT_TIMEOUT => {
+                child.kill().ok();
+                child.wait().ok();
+                return Err(format!("git ls-remote didn't finish within {}s", GIT_TIMEOUT.as_secs()));
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(100)),
+            Err(e) => return Err(format!("Failed to wait for git: {}", e)),
+        }
+    };
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+    if status.success() {
+        Ok(stdout)
+    } else {
+        Err(format!("git failed: {}", stderr.trim()))
+    }
+}
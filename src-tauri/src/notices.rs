@@ -0,0 +1,177 @@
+// Non-blocking heads-ups for the frontend: things worth knowing that aren't errors
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::addons::Addon;
+use crate::{addon_dir, addons_dir, backups, branchwatch, git_output, giturl, syncfolders};
+
+/// The same code isn't repeated sooner than this, however often the checks run
+const REPEAT_AFTER: Duration = Duration::from_secs(6 * 60 * 60);
+const STALE_BACKUP_AGE: Duration = Duration::from_secs(182 * 24 * 60 * 60);
+const LOW_DISK_BYTES: u64 = 1024 * 1024 * 1024;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Notice {
+    /// "info" or "warning"
+    pub level: String,
+    /// Stable identifier ("stale_backup", ...), also the rate-limit key
+    pub code: String,
+    pub message: String,
+    /// What the frontend can offer to do about it ("backup", "branches", "settings")
+    pub action: Option<String>,
+}
+
+/// Each code's last notice and when it went out
+#[derive(Default)]
+pub struct NoticeState(Mutex<HashMap<String, (Instant, Notice)>>);
+
+impl Notice {
+    pub fn warning(code: &str, message: String, action: Option<&str>) -> Notice {
+        Notice {
+            level: "warning".to_string(),
+            code: code.to_string(),
+            message,
+            action: action.map(str::to_string),
+        }
+    }
+}
+
+/// Send a notice unless the same code went out recently
+pub fn emit(app: &AppHandle, notice: Notice) {
+    {
+        let state = app.state::<NoticeState>();
+        let mut sent = state.0.lock().unwrap_or_else(|e| e.into_inner());
+        if sent.get(&notice.code).is_some_and(|(at, _)| at.elapsed() < REPEAT_AFTER) {
+            return;
+        }
+        sent.insert(notice.code.clone(), (Instant::now(), notice.clone()));
+    }
+    app.emit("serpens://notice", &notice).ok();
+}
+
+/// Notices sent within the rate-limit window, for a frontend that wasn't listening yet
+#[tauri::command]
+pub fn get_notices(state: State<'_, NoticeState>) -> Vec<Notice> {
+    let sent = state.0.lock().unwrap_or_else(|e| e.into_inner());
+    sent.values()
+        .filter(|(at, _)| at.elapsed() < REPEAT_AFTER)
+        .map(|(_, notice)| notice.clone())
+        .collect()
+}
+
+/// Run every check in the background and emit whatever turns up. Called at startup,
+/// on status refresh and after operations; the rate limit keeps repeats quiet.
+pub fn refresh(addon: &Addon, app: &AppHandle, blender_version: &str) {
+    let (addon, app) = (addon.clone(), app.clone());
+    let blender_version = blender_version.to_string();
+    thread::spawn(move || {
+        for notice in collect(&addon, &blender_version) {
+            emit(&app, notice);
+        }
+    });
+}
+
+fn collect(addon: &Addon, blender_version: &str) -> Vec<Notice> {
+    let Ok(addon_path) = addon_dir(addon, blender_version) else { return Vec::new() };
+    let installed = addon_path.exists();
+    let git = installed && addon_path.join(".git").exists();
+
+    let checks = [
+        if installed { stale_backup(addon, blender_version) } else { None },
+        if git { deleted_branch(addon, &addon_path) } else { None },
+        low_disk_space(blender_version),
+        synced_folder(blender_version),
+        if git { tls_verify_disabled(&addon_path) } else { tls_env_disabled() },
+    ];
+    checks.into_iter().flatten().collect()
+}
+
+fn stale_backup(addon: &Addon, blender_version: &str) -> Option<Notice> {
+    let newest = backups::newest_backup_time(addon, blender_version)?;
+    let age = SystemTime::now().duration_since(newest).ok()?;
+    if age < STALE_BACKUP_AGE {
+        return None;
+    }
+    Some(Notice::warning(
+        "stale_backup",
+        format!("Your newest backup is {} days old", age.as_secs() / 86_400),
+        Some("backup"),
+    ))
+}
+
+/// The installed branch came from the official repo but isn't there any more
+fn deleted_branch(addon: &Addon, addon_path: &Path) -> Option<Notice> {
+    let origin = git_output(addon_path, &["remote", "get-url", "origin"]).ok()?;
+    if !giturl::is_official(addon, &origin) {
+        return None;
+    }
+    let branch = git_output(addon_path, &["rev-parse", "--abbrev-ref", "HEAD"]).ok()?;
+    if branch == "HEAD" {
+        return None;
+    }
+    let known = branchwatch::known_branches()?;
+    if known.contains(&branch) {
+        return None;
+    }
+    Some(Notice::warning(
+        "deleted_branch",
+        format!("The installed branch '{}' has been deleted from the remote", branch),
+        Some("branches"),
+    ))
+}
+
+fn low_disk_space(blender_version: &str) -> Option<Notice> {
+    let addons_path = addons_dir(blender_version).ok()?;
+    // The addons folder may not exist yet; its nearest existing parent is on the same disk
+    let existing = addons_path.ancestors().find(|p| p.exists())?;
+    let free = fs2::available_space(existing).ok()?;
+    if free >= LOW_DISK_BYTES {
+        return None;
+    }
+    Some(Notice::warning(
+        "low_disk_space",
+        format!("Only {} MB free on the disk Blender's addons live on", free / (1024 * 1024)),
+        None,
+    ))
+}
+
+/// The addons folder is inside OneDrive or similar, where the sync client can hold files
+/// open mid-switch
+fn synced_folder(blender_version: &str) -> Option<Notice> {
+    let synced = syncfolders::sync_root(&addons_dir(blender_version).ok()?)?;
+    Some(Notice::warning(
+        "synced_folder",
+        format!(
+            "Blender's addons folder is inside {} ({}) - switches may be slow or fail while it syncs; pause syncing or exclude the folder if they do",
+            synced.provider, synced.root
+        ),
+        None,
+    ))
+}
+
+/// http.sslVerify off, in the install's config or inherited from global config
+fn tls_verify_disabled(addon_path: &Path) -> Option<Notice> {
+    let verify = git_output(addon_path, &["config", "--bool", "--get", "http.sslVerify"]).ok();
+    if verify.as_deref() == Some("false") {
+        return Some(tls_notice());
+    }
+    tls_env_disabled()
+}
+
+fn tls_env_disabled() -> Option<Notice> {
+    std::env::var_os("GIT_SSL_NO_VERIFY").is_some().then(tls_notice)
+}
+
+fn tls_notice() -> Notice {
+    Notice::warning(
+        "tls_verify_disabled",
+        "Git's TLS certificate verification is turned off - downloads can't be trusted".to_string(),
+        None,
+    )
+}
@@ -0,0 +1,55 @@
+// Desktop notifications for things that happen while the window is in the background
+
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::addons::Addon;
+use crate::{addon_dir, git_output, load_settings};
+
+/// Notify about the outcome of `operation` ("Branch switch", "Backup", ...) unless the
+/// user is already looking at the window or has notifications turned off.
+///
+/// The desktop backends don't expose an urgency level, so failures stand out with their
+/// own title and an audible alert while successes arrive silently.
+pub fn operation_finished(app: &AppHandle, operation: &str, outcome: &Result<String, String>) {
+    if !should_notify(app) {
+        return;
+    }
+
+    let builder = app.notification().builder();
+    let builder = match outcome {
+        Ok(summary) => builder.title(format!("{} finished", operation)).body(summary),
+        Err(e) => builder
+            .title(format!("{} failed", operation))
+            .body(e.lines().next().unwrap_or("Unknown error"))
+            .sound("Default"),
+    };
+    builder.show().ok();
+}
+
+/// A plain informational notification, under the same rules as operation results
+pub fn show(app: &AppHandle, title: &str, body: &str) {
+    if should_notify(app) {
+        app.notification().builder().title(title).body(body).show().ok();
+    }
+}
+
+fn should_notify(app: &AppHandle) -> bool {
+    load_settings().map(|s| s.notifications).unwrap_or(true) && !window_in_view(app)
+}
+
+fn window_in_view(app: &AppHandle) -> bool {
+    let Some(window) = app.get_webview_window("main") else { return false };
+    window.is_visible().unwrap_or(false)
+        && !window.is_minimized().unwrap_or(false)
+        && window.is_focused().unwrap_or(false)
+}
+
+/// " (commit ab12cd3)" for the current install, or nothing if it isn't a git checkout
+pub fn commit_suffix(addon: &Addon, blender_version: &str) -> String {
+    addon_dir(addon, blender_version)
+        .ok()
+        .and_then(|path| git_output(&path, &["rev-parse", "--short", "HEAD"]).ok())
+        .map(|sha| format!(" (commit {})", sha))
+        .unwrap_or_default()
+}
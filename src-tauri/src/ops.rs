@@ -0,0 +1,442 @@
+// Operation lock - keeps commands that touch the install from running over each other,
+// and tracks what the running one is doing so a reloaded frontend can pick it up again
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::{self, Read};
+use std::process::{Command, Output, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::State;
+
+use crate::gitprogress::{self, ProgressUpdate, RateMeter};
+use crate::{applog, suspend};
+
+/// How long a finished operation's outcome stays visible to `get_active_operation`
+const FINISHED_GRACE: Duration = Duration::from_secs(30);
+/// git output lines kept on the operation for the frontend to show
+const LOG_LINES: usize = 100;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OperationInfo {
+    /// Tells operations apart for `pause_operation` / `resume_operation`
+    pub id: u64,
+    pub kind: String,
+    #[serde(rename = "blenderVersion")]
+    pub blender_version: String,
+    #[serde(rename = "startedAt")]
+    pub started_at: String,
+    /// What it's doing right now ("cloning", "copying files", ...)
+    pub phase: Option<String>,
+    /// 0-100 when the operation can tell
+    pub progress: Option<f32>,
+    /// git's own progress for the current phase, while a clone or pull reports it
+    pub transfer: Option<ProgressUpdate>,
+    /// git's other stderr lines, most recent last - anything the progress parser didn't
+    /// recognise, so nothing a newer git prints goes missing
+    pub log: Vec<String>,
+    pub cancellable: bool,
+    /// A git step is frozen by `pause_operation`
+    pub paused: bool,
+    /// "running", "succeeded", "failed", "cancelled", or "abandoned" when it ended without
+    /// reporting an outcome - an early return or a panic
+    pub status: String,
+    #[serde(rename = "finishedAt")]
+    pub finished_at: Option<String>,
+    /// How long it has been going, or how long it took once finished
+    #[serde(rename = "elapsedMs")]
+    pub elapsed_ms: u64,
+    pub error: Option<String>,
+    /// Milliseconds spent in each phase so far
+    pub timings: BTreeMap<String, u64>,
+}
+
+/// A command's result together with where its time went
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Timed<T> {
+    pub result: T,
+    pub timings: BTreeMap<String, u64>,
+    #[serde(rename = "totalMs")]
+    pub total_ms: u64,
+}
+
+struct Running {
+    info: OperationInfo,
+    cancel: Arc<AtomicBool>,
+    started: Instant,
+    /// When the current phase began
+    phase_started: Instant,
+    /// The git process the operation is waiting on, if any
+    child: Option<u32>,
+    /// Fills in the download rate when git doesn't print one
+    rate: RateMeter,
+}
+
+impl Running {
+    /// Charge the time since the last phase change to the current phase
+    fn close_phase(&mut self) {
+        let phase = self.info.phase.clone().unwrap_or_else(|| "starting".to_string());
+        let elapsed = self.phase_started.elapsed().as_millis() as u64;
+        *self.info.timings.entry(phase).or_default() += elapsed;
+        self.phase_started = Instant::now();
+    }
+}
+
+type Slot = Arc<Mutex<Option<Running>>>;
+type Finished = Arc<Mutex<Option<(Instant, OperationInfo)>>>;
+
+#[derive(Default)]
+pub struct OperationState {
+    current: Slot,
+    last: Finished,
+    next_id: AtomicU64,
+}
+
+/// Held for the duration of an operation; releases the lock when dropped.
+/// Owns its handle to the state so it can be moved into blocking tasks.
+pub struct OperationGuard {
+    slot: Slot,
+    last: Finished,
+    cancel: Arc<AtomicBool>,
+}
+
+/// Lets code deep inside an operation report its phase and progress without holding
+/// the guard. `Progress::none()` for callers outside the app, such as the CLI.
+#[derive(Clone)]
+pub struct Progress(Option<Slot>);
+
+fn lock<T>(slot: &Arc<Mutex<T>>) -> MutexGuard<'_, T> {
+    slot.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+impl OperationState {
+    pub fn begin(&self, kind: &str, blender_version: &str) -> Result<OperationGuard, String> {
+        let mut current = lock(&self.current);
+        if let Some(running) = current.as_ref() {
+            return Err(format!("Another operation is already running ({})", running.info.kind));
+        }
+        let cancel = Arc::new(AtomicBool::new(false));
+        *current = Some(Running {
+            info: OperationInfo {
+                id: self.next_id.fetch_add(1, Ordering::SeqCst) + 1,
+                kind: kind.to_string(),
+                blender_version: blender_version.to_string(),
+                started_at: chrono::Local::now().to_rfc3339(),
+                phase: None,
+                progress: None,
+                paused: false,
+                transfer: None,
+                log: Vec::new(),
+                cancellable: false,
+                status: "running".to_string(),
+                finished_at: None,
+                elapsed_ms: 0,
+                error: None,
+                timings: BTreeMap::new(),
+            },
+            cancel: cancel.clone(),
+            started: Instant::now(),
+            phase_started: Instant::now(),
+            child: None,
+            rate: RateMeter::default(),
+        });
+        Ok(OperationGuard { slot: self.current.clone(), last: self.last.clone(), cancel })
+    }
+
+    /// Ask the running operation to stop. Returns false when nothing is running.
+    pub fn cancel(&self) -> bool {
+        match lock(&self.current).as_ref() {
+            Some(running) => {
+                running.cancel.store(true, Ordering::SeqCst);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Freeze the git command operation `id` is waiting on. Only git steps (cloning,
+    /// pulling) can be paused - file copies and swaps are short and not worth the risk
+    /// of leaving the install half-written.
+    pub fn pause(&self, id: u64) -> Result<(), String> {
+        let mut current = lock(&self.current);
+        let running = current
+            .as_mut()
+            .filter(|r| r.info.id == id)
+            .ok_or_else(|| "That operation is no longer running".to_string())?;
+        if running.info.paused {
+            return Ok(());
+        }
+        let pid = running
+            .child
+            .ok_or_else(|| "This step can't be paused - only git transfers can".to_string())?;
+        suspend::suspend(pid)?;
+        running.info.paused = true;
+        applog::info(&format!("{} paused", running.info.kind));
+        Ok(())
+    }
+
+    pub fn resume(&self, id: u64) -> Result<(), String> {
+        let mut current = lock(&self.current);
+        let running = current
+            .as_mut()
+            .filter(|r| r.info.id == id)
+            .ok_or_else(|| "That operation is no longer running".to_string())?;
+        if !running.info.paused {
+            return Ok(());
+        }
+        if let Some(pid) = running.child {
+            suspend::resume(pid)?;
+        }
+        running.info.paused = false;
+        applog::info(&format!("{} resumed", running.info.kind));
+        Ok(())
+    }
+
+    /// The running operation, or the one that just finished
+    pub fn active(&self) -> Option<OperationInfo> {
+        if let Some(info) = self.running() {
+            return Some(info);
+        }
+        lock(&self.last)
+            .as_ref()
+            .filter(|(finished, _)| finished.elapsed() < FINISHED_GRACE)
+            .map(|(_, info)| info.clone())
+    }
+
+    /// The operation holding the lock, if any - what makes the app busy
+    pub fn running(&self) -> Option<OperationInfo> {
+        lock(&self.current).as_ref().map(|running| {
+            let mut info = running.info.clone();
+            info.elapsed_ms = running.started.elapsed().as_millis() as u64;
+            info
+        })
+    }
+}
+
+impl OperationGuard {
+    /// Long-running operations poll this and bail out when it turns true.
+    pub fn cancelled(&self) -> bool {
+        self.cancel.load(Ordering::SeqCst)
+    }
+
+    /// Mark the operation as one that honours `cancel_operation`.
+    pub fn cancellable(self) -> Self {
+        self.update(|info| info.cancellable = true);
+        self
+    }
+
+    pub fn progress(&self) -> Progress {
+        Progress(Some(self.slot.clone()))
+    }
+
+    /// Record how the operation ended, for `get_active_operation` after it's gone, and
+    /// log where the time went.
+    pub fn finish<T>(&self, result: &Result<T, String>) {
+        let cancelled = self.cancelled();
+        let mut slot = lock(&self.slot);
+        let Some(running) = slot.as_mut() else { return };
+        running.close_phase();
+        let info = &mut running.info;
+        match result {
+            Ok(_) => info.status = "succeeded".to_string(),
+            Err(_) if cancelled => info.status = "cancelled".to_string(),
+            Err(e) => {
+                info.status = "failed".to_string();
+                info.error = Some(e.clone());
+            }
+        }
+        let phases: Vec<String> = info.timings.iter().map(|(phase, ms)| format!("{} {}ms", phase, ms)).collect();
+        applog::info(&format!(
+            "{} {} in {}ms ({})",
+            info.kind,
+            info.status,
+            running.started.elapsed().as_millis(),
+            phases.join(", ")
+        ));
+    }
+
+    /// Finish and pair a successful result with its phase timings
+    pub fn finish_timed<T>(&self, result: Result<T, String>) -> Result<Timed<T>, String> {
+        self.finish(&result);
+        result.map(|result| self.timed(result))
+    }
+
+    /// Pair `result` with the phase timings so far, for commands that report their own
+    /// outcome through `finish` and return a report either way
+    pub fn timed<T>(&self, result: T) -> Timed<T> {
+        let slot = lock(&self.slot);
+        let (timings, total_ms) = slot
+            .as_ref()
+            .map(|r| (r.info.timings.clone(), r.started.elapsed().as_millis() as u64))
+            .unwrap_or_default();
+        Timed { result, timings, total_ms }
+    }
+
+    fn update(&self, f: impl FnOnce(&mut OperationInfo)) {
+        if let Some(running) = lock(&self.slot).as_mut() {
+            f(&mut running.info);
+        }
+    }
+}
+
+impl Drop for OperationGuard {
+    fn drop(&mut self) {
+        if let Some(running) = lock(&self.slot).take() {
+            let mut info = running.info;
+            if info.status == "running" {
+                info.status = "abandoned".to_string();
+                info.error.get_or_insert_with(|| "Ended without reporting an outcome".to_string());
+            }
+            info.finished_at = Some(chrono::Local::now().to_rfc3339());
+            info.elapsed_ms = running.started.elapsed().as_millis() as u64;
+            *lock(&self.last) = Some((Instant::now(), info));
+        }
+    }
+}
+
+impl Progress {
+    pub fn none() -> Progress {
+        Progress(None)
+    }
+
+    /// Move on to a new phase; the time spent in the previous one is recorded
+    pub fn phase(&self, phase: &str) {
+        self.update(|running| {
+            running.close_phase();
+            running.info.phase = Some(phase.to_string());
+            running.info.progress = None;
+            running.info.transfer = None;
+        });
+    }
+
+    /// Run a git command to completion, registering it so `pause_operation` can freeze it.
+    /// Progress lines on stderr (from `--progress`) go into the operation's `transfer`
+    /// instead of the returned output, which keeps only git's real messages.
+    pub fn output(&self, command: &mut Command) -> io::Result<Output> {
+        suspend::prepare(command);
+        let mut child = command.stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped()).spawn()?;
+        let pid = child.id();
+        self.update(|running| running.child = Some(pid));
+
+        let stdout = child.stdout.take().map(|mut pipe| {
+            thread::spawn(move || {
+                let mut bytes = Vec::new();
+                pipe.read_to_end(&mut bytes).ok();
+                bytes
+            })
+        });
+        let stderr = match child.stderr.take() {
+            Some(pipe) => self.read_progress(pipe),
+            None => Vec::new(),
+        };
+        let status = child.wait();
+        self.update(|running| {
+            running.child = None;
+            running.info.paused = false;
+        });
+        Ok(Output {
+            status: status?,
+            stdout: stdout.and_then(|reader| reader.join().ok()).unwrap_or_default(),
+            stderr,
+        })
+    }
+
+    /// Feed git's stderr through the progress parser as it arrives; returns the rest
+    fn read_progress(&self, mut pipe: impl Read) -> Vec<u8> {
+        let mut kept = Vec::new();
+        let mut line = Vec::new();
+        let mut buf = [0u8; 4096];
+        loop {
+            let n = match pipe.read(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => n,
+            };
+            for &byte in &buf[..n] {
+                // git redraws a progress line with \r and ends it with \n
+                if byte != b'\r' && byte != b'\n' {
+                    line.push(byte);
+                    continue;
+                }
+                self.progress_line(&line, &mut kept);
+                line.clear();
+            }
+        }
+        self.progress_line(&line, &mut kept);
+        kept
+    }
+
+    fn progress_line(&self, line: &[u8], kept: &mut Vec<u8>) {
+        let text = String::from_utf8_lossy(line);
+        if let Some(mut update) = gitprogress::parse(&text) {
+            self.update(|running| {
+                if let Some(bytes) = update.bytes_received {
+                    let measured = running.rate.sample(bytes);
+                    update.bytes_per_second = update.bytes_per_second.or(measured);
+                }
+                if let Some(percent) = update.overall_percent() {
+                    running.info.progress = Some(percent);
+                }
+                running.info.transfer = Some(update);
+            });
+        } else if !text.trim().is_empty() {
+            kept.extend_from_slice(line);
+            kept.push(b'\n');
+            self.update(|running| {
+                let log = &mut running.info.log;
+                log.push(text.trim_end().to_string());
+                if log.len() > LOG_LINES {
+                    log.remove(0);
+                }
+            });
+        }
+    }
+
+    /// The operation was asked to stop - for loops that don't have the guard
+    pub fn cancelled(&self) -> bool {
+        self.0
+            .as_ref()
+            .and_then(|slot| lock(slot).as_ref().map(|running| running.cancel.load(Ordering::SeqCst)))
+            .unwrap_or(false)
+    }
+
+    pub fn percent(&self, percent: f32) {
+        self.update(|running| running.info.progress = Some(percent.clamp(0.0, 100.0)));
+    }
+
+    fn update(&self, f: impl FnOnce(&mut Running)) {
+        if let Some(slot) = &self.0 {
+            if let Some(running) = lock(slot).as_mut() {
+                f(running);
+            }
+        }
+    }
+}
+
+#[tauri::command]
+pub fn cancel_operation(ops: State<'_, OperationState>) -> bool {
+    ops.cancel()
+}
+
+#[tauri::command]
+pub fn pause_operation(id: u64, ops: State<'_, OperationState>) -> Result<bool, String> {
+    ops.pause(id).map(|_| true)
+}
+
+#[tauri::command]
+pub fn resume_operation(id: u64, ops: State<'_, OperationState>) -> Result<bool, String> {
+    ops.resume(id).map(|_| true)
+}
+
+/// What's running right now, None when the app is idle. Unlike `get_active_operation`
+/// this never returns an operation that has already finished.
+#[tauri::command]
+pub fn current_operation(ops: State<'_, OperationState>) -> Option<OperationInfo> {
+    ops.running()
+}
+
+#[tauri::command]
+pub fn get_active_operation(ops: State<'_, OperationState>) -> Option<OperationInfo> {
+    ops.active()
+}
@@ -0,0 +1,74 @@
+// Making a fresh install readable by Blender whatever the clone left behind
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Walk the install (skipping .git, which only git reads) and make every file and folder
+/// readable: owner-writable and world-readable on Unix, not hidden or system on Windows.
+pub fn normalize(root: &Path) -> Result<(), String> {
+    normalize_tree(root).map_err(|e| format!("Failed to fix permissions: {}", e))
+}
+
+fn normalize_tree(dir: &Path) -> io::Result<()> {
+    normalize_entry(dir, true)?;
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+        if file_type.is_symlink() {
+            continue;
+        }
+        if file_type.is_dir() {
+            if entry.file_name() != ".git" {
+                normalize_tree(&path)?;
+            }
+        } else {
+            normalize_entry(&path, false)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn normalize_entry(path: &Path, is_dir: bool) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut permissions = fs::metadata(path)?.permissions();
+    // Folders need the execute bit to be entered
+    let wanted = if is_dir { 0o755 } else { 0o644 };
+    let mode = permissions.mode();
+    if mode & wanted != wanted {
+        permissions.set_mode(mode | wanted);
+        fs::set_permissions(path, permissions)?;
+    }
+    Ok(())
+}
+
+#[cfg(windows)]
+fn normalize_entry(path: &Path, _is_dir: bool) -> io::Result<()> {
+    use std::os::windows::ffi::OsStrExt;
+
+    const FILE_ATTRIBUTE_HIDDEN: u32 = 0x2;
+    const FILE_ATTRIBUTE_SYSTEM: u32 = 0x4;
+    const INVALID_FILE_ATTRIBUTES: u32 = u32::MAX;
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetFileAttributesW(file_name: *const u16) -> u32;
+        fn SetFileAttributesW(file_name: *const u16, attributes: u32) -> i32;
+    }
+
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+    // Safety: `wide` is a NUL-terminated UTF-16 path that outlives both calls
+    unsafe {
+        let attributes = GetFileAttributesW(wide.as_ptr());
+        if attributes == INVALID_FILE_ATTRIBUTES {
+            return Err(io::Error::last_os_error());
+        }
+        let cleared = attributes & !(FILE_ATTRIBUTE_HIDDEN | FILE_ATTRIBUTE_SYSTEM);
+        if cleared != attributes && SetFileAttributesW(wide.as_ptr(), cleared) == 0 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
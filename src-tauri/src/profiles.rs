@@ -0,0 +1,216 @@
+// Named setups to flip between - which repo and branch is installed for which Blender
+// version, with the settings that go with it. Applying one saves the settings, then
+// switches the install only when it isn't already there.
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+
+use crate::ops::OperationState;
+use crate::spec::parse_repo;
+use crate::{
+    addon_dir, addons, applog, git_addon_dir, git_output, giturl, load_settings, remotes, updates, validate,
+    SettingsState,
+};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Profile {
+    pub name: String,
+    /// The addon it's for; None is Serpens
+    #[serde(rename = "addonId", default, skip_serializing_if = "Option::is_none")]
+    pub addon_id: Option<String>,
+    /// GitHub "owner/name" where possible, so it follows the preferred protocol
+    #[serde(rename = "repoUrl")]
+    pub repo_url: String,
+    pub branch: String,
+    #[serde(rename = "blenderVersion")]
+    pub blender_version: String,
+    #[serde(rename = "autoBackup")]
+    pub auto_backup: bool,
+    #[serde(rename = "cloneDepth")]
+    pub clone_depth: Option<u32>,
+    #[serde(rename = "verifyAfterUpdate")]
+    pub verify_after_update: bool,
+    #[serde(rename = "clearPycache")]
+    pub clear_pycache: bool,
+    /// RFC 3339
+    #[serde(rename = "savedAt")]
+    pub saved_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProfileApplied {
+    pub name: String,
+    #[serde(rename = "blenderVersion")]
+    pub blender_version: String,
+    #[serde(rename = "addonId")]
+    pub addon_id: Option<String>,
+    /// The remote the branch came from; None is the official repo
+    pub remote: Option<String>,
+    /// The install was switched, rather than already matching
+    pub switched: bool,
+    /// What was done, in order
+    pub steps: Vec<String>,
+}
+
+fn check_name(name: &str) -> Result<(), String> {
+    let valid = !name.trim().is_empty()
+        && name.trim() == name
+        && name.chars().count() <= 64
+        && !name.chars().any(char::is_control);
+    if !valid {
+        return Err(format!("Invalid profile name '{}': up to 64 characters, no leading or trailing spaces", name));
+    }
+    Ok(())
+}
+
+/// Names must be unique, and each profile has to be something a switch could install
+pub fn validate_profiles(profiles: &[Profile]) -> Result<(), String> {
+    for (i, profile) in profiles.iter().enumerate() {
+        check_name(&profile.name)?;
+        if profiles[..i].iter().any(|p| p.name == profile.name) {
+            return Err(format!("Profile '{}' is listed twice", profile.name));
+        }
+        let invalid = |e: String| format!("Profile '{}': {}", profile.name, e);
+        validate::blender_version(&profile.blender_version).map_err(invalid)?;
+        validate::branch_name(&profile.branch).map_err(invalid)?;
+        parse_repo(&profile.repo_url).map_err(invalid)?;
+        if profile.clone_depth == Some(0) {
+            return Err(invalid("Clone depth must be at least 1 (or empty for full history)".to_string()));
+        }
+    }
+    Ok(())
+}
+
+/// Remember what's installed for the current Blender version, and the settings that go
+/// with it, as `name` - replacing a profile of the same name
+#[tauri::command]
+pub fn save_profile(
+    name: String,
+    addon_id: Option<String>,
+    state: State<'_, SettingsState>,
+) -> Result<Profile, String> {
+    check_name(&name)?;
+    let addon = addons::resolve(addon_id.as_deref())?;
+    let mut settings = load_settings()?;
+    let addon_path = git_addon_dir(&addon, &settings.blender_version)?;
+    let branch = git_output(&addon_path, &["symbolic-ref", "--short", "--quiet", "HEAD"])
+        .map_err(|_| "The install is on a commit rather than a branch - switch to a branch first".to_string())?;
+    let origin = git_output(&addon_path, &["remote", "get-url", "origin"])
+        .map_err(|e| format!("Couldn't read where the install was cloned from: {}", e))?;
+    let profile = Profile {
+        name: name.clone(),
+        addon_id: (!addon.is_builtin()).then(|| addon.id.clone()),
+        repo_url: giturl::github_path(&origin).unwrap_or(origin),
+        branch,
+        blender_version: settings.blender_version.clone(),
+        auto_backup: settings.auto_backup,
+        clone_depth: settings.clone_depth,
+        verify_after_update: settings.verify_after_update,
+        clear_pycache: settings.clear_pycache,
+        saved_at: chrono::Local::now().to_rfc3339(),
+    };
+    settings.profiles.retain(|p| p.name != name);
+    settings.profiles.push(profile.clone());
+    state.save_now(&settings)?;
+    Ok(profile)
+}
+
+#[tauri::command]
+pub fn list_profiles() -> Result<Vec<Profile>, String> {
+    Ok(load_settings()?.profiles)
+}
+
+#[tauri::command]
+pub fn delete_profile(name: String, state: State<'_, SettingsState>) -> Result<bool, String> {
+    let mut settings = load_settings()?;
+    let before = settings.profiles.len();
+    settings.profiles.retain(|p| p.name != name);
+    if settings.profiles.len() == before {
+        return Err(format!("No profile called '{}'", name));
+    }
+    state.save_now(&settings)?;
+    Ok(true)
+}
+
+/// Put the profile's settings in place, then switch its Blender version's install to its
+/// branch unless that's already what's there. The switch is an ordinary one - the same
+/// checks, backup and operation lock - so unpushed commits stop it the same way.
+#[tauri::command]
+pub fn apply_profile(
+    name: String,
+    app: AppHandle,
+    ops: State<'_, OperationState>,
+    state: State<'_, SettingsState>,
+) -> Result<ProfileApplied, String> {
+    let mut settings = load_settings()?;
+    let profile = settings
+        .profiles
+        .iter()
+        .find(|p| p.name == name)
+        .cloned()
+        .ok_or_else(|| format!("No profile called '{}'", name))?;
+    // Nothing changes while something else is running, rather than the settings
+    // changing and the switch then refusing to start
+    if let Some(running) = ops.running() {
+        return Err(format!("Another operation is already running ({})", running.kind));
+    }
+    let addon = addons::resolve(profile.addon_id.as_deref())?;
+    let mut steps = Vec::new();
+
+    settings.blender_version = profile.blender_version.clone();
+    settings.auto_backup = profile.auto_backup;
+    settings.clone_depth = profile.clone_depth;
+    settings.verify_after_update = profile.verify_after_update;
+    settings.clear_pycache = profile.clear_pycache;
+    state.save_now(&settings)?;
+    app.state::<updates::CheckerWake>().notify();
+    steps.push(format!("Settings switched to Blender {}", profile.blender_version));
+
+    let url = parse_repo(&profile.repo_url)?;
+    let remote = if giturl::is_official(&addon, &url) {
+        None
+    } else {
+        let owner = giturl::github_path(&url)
+            .and_then(|path| path.split('/').next().map(str::to_string))
+            .unwrap_or_else(|| profile.name.clone());
+        let remote = remotes::add_fork_remote(owner, profile.repo_url.clone(), state.clone())?;
+        steps.push(format!("Using remote {} for {}", remote, profile.repo_url));
+        Some(remote)
+    };
+
+    let installed = addon_dir(&addon, &profile.blender_version)
+        .ok()
+        .filter(|dir| dir.join(".git").exists())
+        .and_then(|dir| {
+            let branch = git_output(&dir, &["symbolic-ref", "--short", "--quiet", "HEAD"]).ok()?;
+            let origin = git_output(&dir, &["remote", "get-url", "origin"]).ok()?;
+            Some((branch, origin))
+        });
+    let matches = installed
+        .as_ref()
+        .is_some_and(|(branch, origin)| *branch == profile.branch && giturl::same_repo(origin, &url));
+    if matches {
+        steps.push(format!("Already on {} from {}", profile.branch, profile.repo_url));
+    } else {
+        crate::switch_branch(
+            profile.branch.clone(),
+            profile.blender_version.clone(),
+            remote.clone(),
+            Some(false),
+            profile.addon_id.clone(),
+            app,
+            ops,
+        )
+        .map_err(|e| format!("Settings are applied, but the switch to {} failed: {}", profile.branch, e.message))?;
+        steps.push(format!("Switched to {} from {}", profile.branch, profile.repo_url));
+    }
+    applog::info(&format!("Applied profile '{}': {}", profile.name, steps.join("; ")));
+    Ok(ProfileApplied {
+        name: profile.name,
+        blender_version: profile.blender_version,
+        addon_id: profile.addon_id,
+        remote,
+        switched: !matches,
+        steps,
+    })
+}
@@ -0,0 +1,122 @@
+// Compiled Python left over from another branch. Python trusts a .pyc whose recorded
+// source mtime and size still match, which a switch or pull doesn't always change, so
+// Blender can go on running the old code.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::addons::Addon;
+use crate::{addon_dir, addons, addons_dir, applog, dir_size, load_settings, remove_dir_robust, validate};
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct PycacheReport {
+    /// `__pycache__` folders in the install, relative to the addons folder
+    pub directories: Vec<String>,
+    /// Loose .pyc files: written beside the source by old layouts, or the addon's own
+    /// bytecode in the addons folder's `__pycache__`
+    pub files: Vec<String>,
+    pub bytes: u64,
+    /// How many of the folders and files were deleted; 0 when only listing
+    pub removed: usize,
+}
+
+/// The compiled bytecode of the install, without touching it
+#[tauri::command]
+pub async fn list_pycache(blender_version: String, addon_id: Option<String>) -> Result<PycacheReport, String> {
+    validate::blender_version(&blender_version)?;
+    let addon = addons::resolve(addon_id.as_deref())?;
+    tokio::task::spawn_blocking(move || Ok(scan(&addon, &addons_dir(&blender_version)?, false)))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Delete every `__pycache__` and .pyc of the install so Blender recompiles on next load
+#[tauri::command]
+pub async fn clear_pycache(blender_version: String, addon_id: Option<String>) -> Result<PycacheReport, String> {
+    validate::blender_version(&blender_version)?;
+    let addon = addons::resolve(addon_id.as_deref())?;
+    tokio::task::spawn_blocking(move || {
+        let addon_path = addon_dir(&addon, &blender_version)?;
+        if !addon_path.exists() {
+            return Err("No installation found".to_string());
+        }
+        let report = scan(&addon, &addons_dir(&blender_version)?, true);
+        applog::info(&format!("Cleared {} compiled Python cache entries ({} bytes)", report.removed, report.bytes));
+        Ok(report)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Clear the bytecode after a switch or pull when `clearPycache` is on. Never fails the
+/// operation it follows - stale bytecode is worth a warning, not a failed switch.
+pub fn after_update(addon: &Addon, blender_version: &str) {
+    if !load_settings().map(|s| s.clear_pycache).unwrap_or(true) {
+        return;
+    }
+    let Ok(addons_path) = addons_dir(blender_version) else { return };
+    let report = scan(addon, &addons_path, true);
+    let found = report.directories.len() + report.files.len();
+    if report.removed < found {
+        applog::warn(&format!(
+            "Couldn't remove {} of {} compiled Python cache entries; Blender may still load old code",
+            found - report.removed,
+            found
+        ));
+    }
+}
+
+/// Bytecode of the install under `addons_path`, deleted as it's found when `delete` is set
+pub fn scan(addon: &Addon, addons_path: &Path, delete: bool) -> PycacheReport {
+    let mut report = PycacheReport::default();
+    walk(addons_path, &addons_path.join(&addon.folder_name), delete, &mut report);
+    stale_bytecode(addon, addons_path, delete, &mut report);
+    report
+}
+
+fn walk(addons_path: &Path, dir: &Path, delete: bool, report: &mut PycacheReport) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name();
+        let rel = path.strip_prefix(addons_path).unwrap_or(&path).to_string_lossy().replace('\\', "/");
+        match entry.file_type() {
+            Ok(t) if t.is_dir() && name == "__pycache__" => {
+                report.bytes += dir_size(&path);
+                report.directories.push(rel);
+                if delete && remove_dir_robust(&path).is_ok() {
+                    report.removed += 1;
+                }
+            }
+            Ok(t) if t.is_dir() && name != ".git" => walk(addons_path, &path, delete, report),
+            Ok(t) if t.is_file() && path.extension().is_some_and(|e| e == "pyc") => {
+                remove_file(&path, rel, delete, report);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The addon's bytecode in the addons folder's own `__pycache__`, which older single-file
+/// layouts and some tools leave behind; the folder is dropped if the addon was all it held
+fn stale_bytecode(addon: &Addon, addons_path: &Path, delete: bool, report: &mut PycacheReport) {
+    let cache = addons_path.join("__pycache__");
+    let Ok(entries) = fs::read_dir(&cache) else { return };
+    let prefix = format!("{}.", addon.folder_name);
+    for entry in entries.flatten().filter(|e| e.file_name().to_string_lossy().starts_with(&prefix)) {
+        let rel = format!("__pycache__/{}", entry.file_name().to_string_lossy());
+        remove_file(&entry.path(), rel, delete, report);
+    }
+    if delete && fs::read_dir(&cache).is_ok_and(|mut rest| rest.next().is_none()) {
+        fs::remove_dir(&cache).ok();
+    }
+}
+
+fn remove_file(path: &Path, rel: String, delete: bool, report: &mut PycacheReport) {
+    report.bytes += fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    report.files.push(rel);
+    if delete && fs::remove_file(path).is_ok() {
+        report.removed += 1;
+    }
+}
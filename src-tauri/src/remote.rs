@@ -0,0 +1,601 @@
+// Looking at remote branches without installing them
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::io::Read;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::appupdate::github_api;
+use crate::{
+    addons, applog, default_clone_depth, divergence, git_output, gitbin, giturl, load_settings, remotes, validate,
+    TempDir,
+};
+
+/// Previews longer than this are cut off; changelogs that size are mostly history anyway
+const MAX_FILE_BYTES: usize = 256 * 1024;
+/// How far into a file to look for a NUL byte before calling it binary, as git does
+const BINARY_SNIFF: usize = 8000;
+const RAW_TIMEOUT: Duration = Duration::from_secs(15);
+const DEFAULT_LOG_LIMIT: usize = 20;
+const MAX_LOG_LIMIT: usize = 100;
+const DEFAULT_TREE_PAGE: usize = 1000;
+const MAX_TREE_PAGE: usize = 5000;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BranchTreeComparison {
+    pub base: String,
+    pub other: String,
+    #[serde(rename = "onlyInBase")]
+    pub only_in_base: Vec<String>,
+    #[serde(rename = "onlyInOther")]
+    pub only_in_other: Vec<String>,
+    pub common: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CloneEstimate {
+    /// Roughly what a clone with the current depth setting downloads
+    pub bytes: u64,
+    /// Files in the branch, when the tree was counted
+    pub files: Option<u64>,
+    /// "tree" (sum of the branch's files, for shallow clones) or "repo" (GitHub's size of
+    /// the whole repository, for full-history clones)
+    pub method: String,
+}
+
+/// How much a clone of `branch` will download, asked of GitHub so nothing is fetched.
+/// Shallow clones get the branch's file sizes added up, which overestimates a little since
+/// git compresses the transfer; full clones get GitHub's figure for the whole history.
+#[tauri::command]
+pub async fn estimate_clone_size(
+    branch: String,
+    remote: Option<String>,
+    addon_id: Option<String>,
+) -> Result<CloneEstimate, String> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    tokio::task::spawn_blocking(move || {
+        validate::branch_name(&branch)?;
+        let url = remotes::remote_url(&addon, remote.as_deref())?;
+        let repo = giturl::github_path(&url).ok_or("Size estimates are only available for GitHub remotes")?;
+        let depth = load_settings().map(|s| s.clone_depth).unwrap_or_else(|_| default_clone_depth());
+
+        if depth.is_none() {
+            let body = github_api(&format!("https://api.github.com/repos/{}", repo), "Repository not found")?;
+            let info: serde_json::Value =
+                serde_json::from_str(&body).map_err(|e| format!("Unexpected repository data: {}", e))?;
+            let kb = info["size"].as_u64().ok_or("GitHub didn't report a repository size")?;
+            return Ok(CloneEstimate { bytes: kb * 1024, files: None, method: "repo".to_string() });
+        }
+
+        let body = github_api(
+            &format!("https://api.github.com/repos/{}/git/trees/{}?recursive=1", repo, encode_segment(&branch)),
+            &format!("Branch '{}' not found", branch),
+        )?;
+        let tree: serde_json::Value =
+            serde_json::from_str(&body).map_err(|e| format!("Unexpected tree data: {}", e))?;
+        let blobs: Vec<u64> = tree["tree"]
+            .as_array()
+            .map(|entries| {
+                entries
+                    .iter()
+                    .filter(|e| e["type"] == "blob")
+                    .filter_map(|e| e["size"].as_u64())
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(CloneEstimate {
+            bytes: blobs.iter().sum(),
+            files: Some(blobs.len() as u64),
+            method: "tree".to_string(),
+        })
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BranchFile {
+    pub branch: String,
+    pub path: String,
+    /// The branch tip the file was read at
+    pub commit: String,
+    /// False when the branch has no such file - not an error
+    pub present: bool,
+    pub content: Option<String>,
+    /// The file is longer than the preview limit and `content` stops there
+    pub truncated: bool,
+}
+
+/// Files already read, by (commit, path) - what a commit holds never changes, so entries
+/// stay valid for as long as the app runs
+#[derive(Default)]
+pub struct BranchFileCache(pub Mutex<HashMap<(String, String), BranchFile>>);
+
+/// One text file from a branch without cloning it, such as its CHANGELOG.md or
+/// README.md for the branch picker. GitHub remotes are read raw over HTTPS; anything else,
+/// or GitHub failing, falls back to a single-commit blobless clone.
+#[tauri::command]
+pub async fn get_branch_file(
+    branch: String,
+    path: String,
+    remote: Option<String>,
+    addon_id: Option<String>,
+    cache: tauri::State<'_, BranchFileCache>,
+) -> Result<BranchFile, String> {
+    validate::branch_name(&branch)?;
+    let path = repo_path(&path)?;
+    let addon = addons::resolve(addon_id.as_deref())?;
+    let url = remotes::remote_url(&addon, remote.as_deref())?;
+    let tip = tokio::task::spawn_blocking({
+        let (url, branch) = (url.clone(), branch.clone());
+        move || branch_tip(&url, &branch)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))??;
+
+    let key = (tip.clone(), path.clone());
+    if let Some(file) = cache.0.lock().unwrap_or_else(|e| e.into_inner()).get(&key) {
+        return Ok(BranchFile { branch, ..file.clone() });
+    }
+    let file = tokio::task::spawn_blocking(move || {
+        let raw = giturl::github_path(&url).and_then(|repo| read_raw(&repo, &tip, &path));
+        let (commit, bytes) = match raw {
+            Some(bytes) => (tip, bytes),
+            None => read_via_clone(&url, &branch, &path)?,
+        };
+        finish_file(branch, path, commit, bytes)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))??;
+    cache
+        .0
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert((file.commit.clone(), file.path.clone()), file.clone());
+    Ok(file)
+}
+
+/// A path inside the repo, with forward slashes and nothing that climbs out of it
+fn repo_path(path: &str) -> Result<String, String> {
+    let normal = path.trim().replace('\\', "/");
+    let normal = normal.trim_start_matches("./");
+    let valid = !normal.is_empty()
+        && !normal.starts_with('/')
+        && !normal.contains(':')
+        && normal.split('/').all(|part| !part.is_empty() && part != "." && part != "..");
+    if !valid {
+        return Err(format!("Invalid file path '{}': expected a path inside the repo, like CHANGELOG.md", path));
+    }
+    Ok(normal.to_string())
+}
+
+fn branch_tip(url: &str, branch: &str) -> Result<String, String> {
+    let refname = format!("refs/heads/{}", branch);
+    let listing = git_output(std::path::Path::new("."), &["ls-remote", "--", url, &refname])
+        .map_err(|e| format!("Couldn't look up {}: {}", branch, e))?;
+    listing
+        .lines()
+        .find_map(|line| line.split_once('\t').filter(|(_, name)| *name == refname).map(|(sha, _)| sha.to_string()))
+        .ok_or_else(|| format!("No branch '{}' on the remote", branch))
+}
+
+/// The file's bytes at `commit` from raw.githubusercontent.com: Some(None) when GitHub
+/// says there's no such file, None when it couldn't be asked
+fn read_raw(repo: &str, commit: &str, path: &str) -> Option<Option<Vec<u8>>> {
+    let encoded: Vec<String> = path.split('/').map(encode_segment).collect();
+    let url = format!("https://raw.githubusercontent.com/{}/{}/{}", repo, commit, encoded.join("/"));
+    let mut request = ureq::get(&url).set("User-Agent", "serpens-dev-manager").timeout(RAW_TIMEOUT);
+    let token = load_settings().map(|s| s.github_token).unwrap_or_default();
+    if !token.trim().is_empty() {
+        request = request.set("Authorization", &format!("Bearer {}", token.trim()));
+    }
+    match request.call() {
+        Ok(response) => {
+            let mut bytes = Vec::new();
+            // One byte past the limit tells a file cut off from one that just fits
+            response.into_reader().take(MAX_FILE_BYTES as u64 + 1).read_to_end(&mut bytes).ok()?;
+            Some(Some(bytes))
+        }
+        // Private repos answer 404 too without a token; the clone can still sign in
+        Err(ureq::Error::Status(404, _)) if !token.trim().is_empty() => Some(None),
+        Err(_) => None,
+    }
+}
+
+/// (commit, bytes) of the file on the branch's tip, through a one-commit blobless clone
+/// that downloads just this file's contents
+fn read_via_clone(url: &str, branch: &str, path: &str) -> Result<(String, Option<Vec<u8>>), String> {
+    let checkout = shallow_blobless_clone(url, branch, "file")?;
+    let commit = git_output(checkout.path(), &["rev-parse", "HEAD"])?;
+    let object = format!("HEAD:{}", path);
+    let is_blob = git_output(checkout.path(), &["cat-file", "-t", &object]).is_ok_and(|t| t == "blob");
+    if !is_blob {
+        return Ok((commit, None));
+    }
+    let output = gitbin::git_command()
+        .args(["cat-file", "blob", &object])
+        .current_dir(checkout.path())
+        .output()
+        .map_err(|e| gitbin::run_error(&e))?;
+    if !output.status.success() {
+        return Err(format!("Couldn't read {}: {}", path, String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok((commit, Some(output.stdout)))
+}
+
+fn finish_file(branch: String, path: String, commit: String, bytes: Option<Vec<u8>>) -> Result<BranchFile, String> {
+    let Some(mut bytes) = bytes else {
+        return Ok(BranchFile { branch, path, commit, present: false, content: None, truncated: false });
+    };
+    if bytes[..bytes.len().min(BINARY_SNIFF)].contains(&0) {
+        return Err(format!("{} is a binary file and can't be previewed", path));
+    }
+    let truncated = bytes.len() > MAX_FILE_BYTES;
+    bytes.truncate(MAX_FILE_BYTES);
+    let content = String::from_utf8_lossy(&bytes).to_string();
+    Ok(BranchFile { branch, path, commit, present: true, content: Some(content), truncated })
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RemoteCommit {
+    pub sha: String,
+    pub subject: String,
+    pub author: String,
+    /// RFC 3339
+    pub date: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RemoteLog {
+    pub branch: String,
+    /// The branch tip, which is also the first commit listed
+    pub tip: String,
+    /// Newest first
+    pub commits: Vec<RemoteCommit>,
+    /// The branch has older commits than the ones listed
+    pub more: bool,
+    /// The oldest commit anything is known about: past it nothing was fetched, so how much
+    /// older history exists is unknown. None when the list reaches the first commit or
+    /// the history was fetched in full.
+    pub boundary: Option<String>,
+    /// "github" for the commits API, "git" for the local branch cache
+    pub source: String,
+}
+
+/// The latest commits on a branch, for skimming before switching to it. Works with nothing
+/// installed: GitHub remotes ask the commits API, anything else (or GitHub failing) fetches
+/// the branch's commits - no files - into the branch cache and reads them there.
+#[tauri::command]
+pub async fn get_remote_log(
+    branch: String,
+    limit: Option<usize>,
+    remote: Option<String>,
+    addon_id: Option<String>,
+) -> Result<RemoteLog, String> {
+    validate::branch_name(&branch)?;
+    let limit = limit.unwrap_or(DEFAULT_LOG_LIMIT).clamp(1, MAX_LOG_LIMIT);
+    let addon = addons::resolve(addon_id.as_deref())?;
+    let url = remotes::remote_url(&addon, remote.as_deref())?;
+    tokio::task::spawn_blocking(move || {
+        let tip = branch_tip(&url, &branch)?;
+        let github = giturl::github_path(&url).map(|repo| log_via_api(&repo, &tip, limit));
+        let log = match github {
+            Some(Ok(log)) => log,
+            Some(Err(e)) => {
+                applog::warn(&format!("GitHub couldn't list {}'s commits ({}), reading them with git", branch, e));
+                log_via_cache_repo(&url, &tip, limit)?
+            }
+            None => log_via_cache_repo(&url, &tip, limit)?,
+        };
+        Ok(RemoteLog { branch, tip, ..log })
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+fn log_via_api(repo: &str, tip: &str, limit: usize) -> Result<RemoteLog, String> {
+    // One past the limit says whether there's more without a second page
+    let body = github_api(
+        &format!("https://api.github.com/repos/{}/commits?sha={}&per_page={}", repo, tip, limit + 1),
+        "Commit not found",
+    )?;
+    let listed: Vec<serde_json::Value> =
+        serde_json::from_str(&body).map_err(|e| format!("Unexpected commit data: {}", e))?;
+    let more = listed.len() > limit;
+    let commits: Vec<RemoteCommit> = listed
+        .iter()
+        .take(limit)
+        .filter_map(|c| {
+            Some(RemoteCommit {
+                sha: c["sha"].as_str()?.to_string(),
+                subject: c["commit"]["message"].as_str()?.lines().next().unwrap_or_default().to_string(),
+                author: c["commit"]["author"]["name"].as_str().unwrap_or_default().to_string(),
+                date: c["commit"]["committer"]["date"].as_str().unwrap_or_default().to_string(),
+            })
+        })
+        .collect();
+    Ok(RemoteLog {
+        branch: String::new(),
+        tip: String::new(),
+        boundary: more.then(|| commits.last().map(|c| c.sha.clone())).flatten(),
+        more,
+        commits,
+        source: "github".to_string(),
+    })
+}
+
+fn log_via_cache_repo(url: &str, tip: &str, limit: usize) -> Result<RemoteLog, String> {
+    let repo = divergence::cache_repo()?;
+    if git_output(&repo, &["cat-file", "-e", &format!("{}^{{commit}}", tip)]).is_err() {
+        git_output(&repo, &["fetch", "--quiet", "--filter=tree:0", "--", url, tip])
+            .map_err(|e| format!("Couldn't fetch the branch's history: {}", e))?;
+    }
+    let format = "--format=%H%x1f%an%x1f%cI%x1f%s";
+    let count = (limit + 1).to_string();
+    let listing = git_output(&repo, &["log", "-n", &count, format, tip])?;
+    // Shallow grafts are where the cache's history stops; a partial clone fetches any
+    // other missing parent on demand, so nothing else cuts it short
+    let shallow = fs::read_to_string(repo.join("shallow")).unwrap_or_default();
+    let mut commits = Vec::new();
+    let mut boundary = None;
+    for line in listing.lines().take(limit) {
+        let mut fields = line.split('\x1f');
+        let (Some(sha), Some(author), Some(date), Some(subject)) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        if boundary.is_none() && shallow.lines().any(|l| l == sha) {
+            boundary = Some(sha.to_string());
+        }
+        commits.push(RemoteCommit {
+            sha: sha.to_string(),
+            subject: subject.to_string(),
+            author: author.to_string(),
+            date: date.to_string(),
+        });
+    }
+    let more = listing.lines().count() > limit || boundary.is_some();
+    Ok(RemoteLog {
+        branch: String::new(),
+        tip: String::new(),
+        commits,
+        more,
+        boundary,
+        source: "git".to_string(),
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TreeEntry {
+    /// From the repo root, with forward slashes
+    pub path: String,
+    /// "file", "dir", "symlink" or "submodule"
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Bytes, for files; None when the listing came from git, which would have to
+    /// download every file to say
+    pub size: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RemoteTree {
+    pub branch: String,
+    pub commit: String,
+    pub subpath: Option<String>,
+    /// This page, sorted by path
+    pub entries: Vec<TreeEntry>,
+    /// Entries in the whole listing
+    pub total: usize,
+    pub offset: usize,
+    /// More entries follow this page; ask again from `offset + entries.len()`
+    pub truncated: bool,
+    /// "github" for the trees API, "git" for a tree-only clone
+    pub source: String,
+}
+
+/// Every file and folder on a branch's tip, or under `subpath`, without cloning its
+/// contents. Big trees come back a page at a time.
+#[tauri::command]
+pub async fn get_remote_tree(
+    branch: String,
+    subpath: Option<String>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    remote: Option<String>,
+    addon_id: Option<String>,
+) -> Result<RemoteTree, String> {
+    validate::branch_name(&branch)?;
+    let subpath = match subpath.as_deref().map(str::trim) {
+        None | Some("") | Some("/") => None,
+        Some(path) => Some(repo_path(path)?),
+    };
+    let offset = offset.unwrap_or(0);
+    let limit = limit.unwrap_or(DEFAULT_TREE_PAGE).clamp(1, MAX_TREE_PAGE);
+    let addon = addons::resolve(addon_id.as_deref())?;
+    let url = remotes::remote_url(&addon, remote.as_deref())?;
+    tokio::task::spawn_blocking(move || {
+        let tip = branch_tip(&url, &branch)?;
+        // GitHub stops a recursive tree at 100,000 entries; git has no such limit
+        let github = giturl::github_path(&url).map(|repo| tree_via_api(&repo, &tip));
+        let (mut entries, source) = match github {
+            Some(Ok(Some(entries))) => (entries, "github"),
+            Some(Err(e)) => {
+                applog::warn(&format!("GitHub couldn't list {}'s files ({}), reading them with git", branch, e));
+                (tree_via_clone(&url, &branch)?, "git")
+            }
+            _ => (tree_via_clone(&url, &branch)?, "git"),
+        };
+        if let Some(sub) = &subpath {
+            let prefix = format!("{}/", sub);
+            entries.retain(|e| e.path.starts_with(&prefix));
+            if entries.is_empty() {
+                return Err(format!("No folder '{}' on {}", sub, branch));
+            }
+        }
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        let total = entries.len();
+        let page: Vec<TreeEntry> = entries.into_iter().skip(offset).take(limit).collect();
+        Ok(RemoteTree {
+            truncated: offset + page.len() < total,
+            branch,
+            commit: tip,
+            subpath,
+            entries: page,
+            total,
+            offset,
+            source: source.to_string(),
+        })
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// None when GitHub cut the listing short
+fn tree_via_api(repo: &str, commit: &str) -> Result<Option<Vec<TreeEntry>>, String> {
+    let body = github_api(
+        &format!("https://api.github.com/repos/{}/git/trees/{}?recursive=1", repo, commit),
+        "Commit not found",
+    )?;
+    let tree: serde_json::Value = serde_json::from_str(&body).map_err(|e| format!("Unexpected tree data: {}", e))?;
+    if tree["truncated"].as_bool().unwrap_or(false) {
+        return Ok(None);
+    }
+    let entries = tree["tree"].as_array().ok_or("Unexpected tree data: no entries")?;
+    Ok(Some(
+        entries
+            .iter()
+            .filter_map(|e| {
+                let path = e["path"].as_str()?.to_string();
+                let kind = entry_kind(e["mode"].as_str().unwrap_or_default(), e["type"].as_str()?);
+                Some(TreeEntry { path, kind: kind.to_string(), size: e["size"].as_u64() })
+            })
+            .collect(),
+    ))
+}
+
+fn tree_via_clone(url: &str, branch: &str) -> Result<Vec<TreeEntry>, String> {
+    let checkout = shallow_blobless_clone(url, branch, "tree")?;
+    let listing = git_output(checkout.path(), &["ls-tree", "-r", "-t", "-z", "HEAD"])?;
+    Ok(listing
+        .split('\0')
+        .filter_map(|line| {
+            // "<mode> <type> <object>\t<path>"
+            let (meta, path) = line.split_once('\t')?;
+            let mut meta = meta.split(' ');
+            let (mode, object_type) = (meta.next()?, meta.next()?);
+            Some(TreeEntry {
+                path: path.to_string(),
+                kind: entry_kind(mode, object_type).to_string(),
+                size: None,
+            })
+        })
+        .collect())
+}
+
+fn entry_kind(mode: &str, object_type: &str) -> &'static str {
+    match (mode, object_type) {
+        (_, "tree") => "dir",
+        (_, "commit") => "submodule",
+        ("120000", _) => "symlink",
+        _ => "file",
+    }
+}
+
+/// Percent-encode a branch name for use as one URL path segment ("feature/x" included)
+fn encode_segment(text: &str) -> String {
+    text.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Root names a README goes by, most likely first - each is read like any other file, so
+/// misses are cached by commit along with the hit
+const README_NAMES: [&str; 5] = ["README.md", "readme.md", "Readme.md", "README", "README.txt"];
+
+/// A branch's README, read through [`get_branch_file`] so the two can't disagree about
+/// where a branch's files come from
+#[tauri::command]
+pub async fn get_branch_readme(
+    branch: String,
+    remote: Option<String>,
+    addon_id: Option<String>,
+    cache: tauri::State<'_, BranchFileCache>,
+) -> Result<Option<String>, String> {
+    for name in README_NAMES {
+        let file =
+            get_branch_file(branch.clone(), name.to_string(), remote.clone(), addon_id.clone(), cache.clone()).await?;
+        if file.present {
+            return Ok(file.content);
+        }
+    }
+    Ok(None)
+}
+
+/// Clone just the tip commit and trees of `branch` into a temp dir - file contents are
+/// fetched lazily, so this stays tiny. The directory is removed when the guard drops.
+fn shallow_blobless_clone(url: &str, branch: &str, tag: &str) -> Result<TempDir, String> {
+    validate::branch_name(branch)?;
+    let temp = TempDir::new(tag)?;
+    let output = gitbin::git_command()
+        .args(["clone", "--depth", "1", "--filter=blob:none", "--no-checkout", "--single-branch"])
+        .arg("--branch")
+        .arg(branch)
+        .arg("--")
+        .arg(url)
+        .arg(temp.path())
+        .output()
+        .map_err(|e| gitbin::run_error(&e))?;
+    if !output.status.success() {
+        return Err(format!("Git clone failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(temp)
+}
+
+/// Which files each of two branches has that the other doesn't. Only the tip commits and
+/// their trees are fetched, into a throwaway bare repo.
+#[tauri::command]
+pub async fn compare_branch_trees(
+    base: String,
+    other: String,
+    remote: Option<String>,
+    addon_id: Option<String>,
+) -> Result<BranchTreeComparison, String> {
+    validate::branch_name(&base)?;
+    validate::branch_name(&other)?;
+    let addon = addons::resolve(addon_id.as_deref())?;
+    let url = remotes::remote_url(&addon, remote.as_deref())?;
+    tokio::task::spawn_blocking(move || {
+        let repo = TempDir::new("compare")?;
+        git_output(repo.path(), &["init", "--bare", "--quiet"])?;
+        let mut refspecs: Vec<String> =
+            [&base, &other].iter().map(|b| format!("+refs/heads/{0}:refs/heads/{0}", b)).collect();
+        refspecs.dedup();
+        let mut fetch = vec!["fetch", "--quiet", "--depth", "1", "--filter=blob:none", "--", &url];
+        fetch.extend(refspecs.iter().map(String::as_str));
+        git_output(repo.path(), &fetch).map_err(|e| format!("Failed to fetch branches: {}", e))?;
+
+        let tree = |branch: &str| -> Result<BTreeSet<String>, String> {
+            let refname = format!("refs/heads/{}", branch);
+            let files = git_output(repo.path(), &["ls-tree", "-r", "--name-only", &refname])?;
+            Ok(files.lines().map(str::to_string).collect())
+        };
+        let base_files = tree(&base)?;
+        let other_files = tree(&other)?;
+
+        Ok(BranchTreeComparison {
+            only_in_base: base_files.difference(&other_files).cloned().collect(),
+            only_in_other: other_files.difference(&base_files).cloned().collect(),
+            common: base_files.intersection(&other_files).cloned().collect(),
+            base,
+            other,
+        })
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
@@ -0,0 +1,214 @@
+// Extra remotes - forks to list and install branches from alongside the official repo
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::State;
+
+use crate::addons::{self, Addon};
+use crate::appupdate::github_api;
+use crate::giturl::{self, same_repo};
+use crate::spec::parse_repo;
+use crate::{git_output, load_settings, SettingsState};
+
+/// GitHub's page size limit; popular repos need several pages
+const FORKS_PER_PAGE: usize = 100;
+/// Enough for any realistic list without burning the rate limit
+const MAX_FORK_PAGES: usize = 10;
+
+/// The official repo, always available under this name
+pub const DEFAULT_REMOTE: &str = "origin";
+
+/// A configured remote, as stored in settings
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Remote {
+    pub name: String,
+    /// https or SSH URL, or GitHub "owner/name" to follow the preferred protocol
+    pub url: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RemoteInfo {
+    pub name: String,
+    pub url: String,
+    /// The official repo rather than one from settings
+    pub builtin: bool,
+}
+
+#[tauri::command]
+pub fn list_remotes(addon_id: Option<String>) -> Result<Vec<RemoteInfo>, String> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    let mut remotes = vec![RemoteInfo { name: DEFAULT_REMOTE.to_string(), url: addon.url(), builtin: true }];
+    for remote in load_settings()?.remotes {
+        let url = parse_repo(&remote.url)?;
+        remotes.push(RemoteInfo { name: remote.name, url, builtin: false });
+    }
+    Ok(remotes)
+}
+
+/// Clone URL for a remote name; None is `addon`'s own repo
+pub fn remote_url(addon: &Addon, name: Option<&str>) -> Result<String, String> {
+    match name {
+        None | Some(DEFAULT_REMOTE) | Some("") => Ok(addon.url()),
+        Some(name) => {
+            let remote = load_settings()?
+                .remotes
+                .into_iter()
+                .find(|r| r.name == name)
+                .ok_or_else(|| format!("No remote named '{}' - add it in settings first", name))?;
+            parse_repo(&remote.url)
+        }
+    }
+}
+
+/// Names must work as git remote names and not shadow the official one
+pub fn validate_remotes(remotes: &[Remote]) -> Result<(), String> {
+    for (i, remote) in remotes.iter().enumerate() {
+        let name = remote.name.as_str();
+        let name_ok = !name.is_empty()
+            && !name.starts_with(['-', '.'])
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c));
+        if !name_ok {
+            return Err(format!("Invalid remote name '{}': use letters, digits, '-', '_' or '.'", name));
+        }
+        if [DEFAULT_REMOTE, "upstream", "serpens"].contains(&name) {
+            return Err(format!("'{}' is reserved - pick another remote name", name));
+        }
+        if remotes[..i].iter().any(|r| r.name == name) {
+            return Err(format!("Remote '{}' is listed twice", name));
+        }
+        parse_repo(&remote.url).map_err(|e| format!("Remote '{}': {}", name, e))?;
+    }
+    Ok(())
+}
+
+/// Make the other configured remotes available in a fresh clone, so branches from any
+/// fork can be fetched and compared without re-cloning. `origin` stays whatever the
+/// install was cloned from.
+pub fn add_configured_remotes(addon: &Addon, addon_path: &Path, cloned_from: &str) {
+    let Ok(settings) = load_settings() else { return };
+    let existing = git_output(addon_path, &["remote"]).unwrap_or_default();
+    if !giturl::is_official(addon, cloned_from) && !existing.lines().any(|r| r == "serpens") {
+        git_output(addon_path, &["remote", "add", "serpens", &addon.url()]).ok();
+    }
+    for remote in settings.remotes {
+        let Ok(url) = parse_repo(&remote.url) else { continue };
+        if same_repo(&url, cloned_from) || existing.lines().any(|r| r == remote.name) {
+            continue;
+        }
+        git_output(addon_path, &["remote", "add", &remote.name, &url]).ok();
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Fork {
+    pub owner: String,
+    pub url: String,
+    pub stars: u64,
+    #[serde(rename = "pushedAt")]
+    pub pushed_at: Option<String>,
+    #[serde(rename = "defaultBranch")]
+    pub default_branch: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ForkList {
+    /// Most recently pushed first
+    pub forks: Vec<Fork>,
+    /// Every page was read; false when GitHub stopped answering partway (usually the
+    /// rate limit), with the reason in `error`
+    pub complete: bool,
+    pub error: Option<String>,
+}
+
+/// Forks of the addon's repo, from the GitHub API
+#[tauri::command]
+pub async fn fetch_forks(addon_id: Option<String>) -> Result<ForkList, String> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    tokio::task::spawn_blocking(move || {
+        let repo = giturl::github_path(&addon.url()).ok_or("The addon's repo isn't on GitHub")?;
+        let mut forks = Vec::new();
+        let mut error = None;
+        for page in 1..=MAX_FORK_PAGES {
+            let url = format!(
+                "https://api.github.com/repos/{}/forks?sort=newest&per_page={}&page={}",
+                repo, FORKS_PER_PAGE, page
+            );
+            let batch = match github_api(&url, "Repository not found").and_then(|body| parse_forks(&body)) {
+                Ok(batch) => batch,
+                // Nothing to show at all is an error; a later page failing just cuts the list short
+                Err(e) if forks.is_empty() => return Err(e),
+                Err(e) => {
+                    error = Some(e);
+                    break;
+                }
+            };
+            let last_page = batch.len() < FORKS_PER_PAGE;
+            forks.extend(batch);
+            if last_page {
+                break;
+            }
+        }
+        // RFC 3339 timestamps sort chronologically as text
+        forks.sort_by(|a, b| b.pushed_at.cmp(&a.pushed_at));
+        Ok(ForkList { forks, complete: error.is_none(), error })
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+fn parse_forks(body: &str) -> Result<Vec<Fork>, String> {
+    let entries: Vec<serde_json::Value> =
+        serde_json::from_str(body).map_err(|e| format!("Unexpected forks data: {}", e))?;
+    Ok(entries
+        .iter()
+        .filter_map(|fork| {
+            Some(Fork {
+                owner: fork["owner"]["login"].as_str()?.to_string(),
+                url: fork["html_url"].as_str()?.to_string(),
+                stars: fork["stargazers_count"].as_u64().unwrap_or(0),
+                pushed_at: fork["pushed_at"].as_str().map(str::to_string),
+                default_branch: fork["default_branch"].as_str().unwrap_or("main").to_string(),
+            })
+        })
+        .collect())
+}
+
+/// Add a fork to the configured remotes so its branches can be listed and installed;
+/// returns the remote name, which is the owner's name made safe for git
+#[tauri::command]
+pub fn add_fork_remote(owner: String, url: String, state: State<'_, SettingsState>) -> Result<String, String> {
+    // Kept as "owner/name", so the remote follows the preferred protocol if it changes
+    let stored = giturl::github_path(&url).unwrap_or_else(|| url.clone());
+    let url = parse_repo(&stored)?;
+    let mut settings = load_settings()?;
+    if let Some(existing) = settings.remotes.iter().find(|r| parse_repo(&r.url).is_ok_and(|u| same_repo(&u, &url))) {
+        return Ok(existing.name.clone());
+    }
+    let base: String = owner
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || "-_".contains(c) { c } else { '-' })
+        .collect();
+    let base = base.trim_start_matches(['-', '.']).to_string();
+    let base = if base.is_empty() { "fork".to_string() } else { base };
+    // A reserved or already-taken name gets a number
+    let taken = |name: &str| {
+        [DEFAULT_REMOTE, "upstream", "serpens"].contains(&name) || settings.remotes.iter().any(|r| r.name == name)
+    };
+    let name = (1..)
+        .map(|n| if n == 1 { base.clone() } else { format!("{}-{}", base, n) })
+        .find(|name| !taken(name))
+        .unwrap_or(base);
+    settings.remotes.push(Remote { name: name.clone(), url: stored });
+    validate_remotes(&settings.remotes)?;
+    state.save_now(&settings)?;
+    Ok(name)
+}
+
+/// "owner/name" of the repo an install was cloned from, when that isn't the official one
+pub fn fork_of_install(addon: &Addon, addon_path: &Path) -> Option<String> {
+    let origin = git_output(addon_path, &["remote", "get-url", "origin"]).ok()?;
+    if giturl::is_official(addon, &origin) {
+        return None;
+    }
+    Some(giturl::github_path(&origin).unwrap_or(origin))
+}
@@ -0,0 +1,576 @@
+// Git repository maintenance for the installed addon
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+use crate::addons::Addon;
+use crate::marker::{read_marker, write_marker};
+use crate::ops::OperationState;
+use crate::{addons, dir_size, git_addon_dir, git_output, gitbin, load_settings, validate};
+
+/// A pull that stopped halfway - "merge" or "rebase" - or None when the repo is clean
+pub fn interrupted_pull(addon_path: &Path) -> Option<&'static str> {
+    if git_output(addon_path, &["rev-parse", "-q", "--verify", "MERGE_HEAD"]).is_ok() {
+        return Some("merge");
+    }
+    let git_dir = addon_path.join(".git");
+    if git_dir.join("rebase-merge").is_dir() || git_dir.join("rebase-apply").is_dir() {
+        return Some("rebase");
+    }
+    None
+}
+
+/// Local commits a re-clone would throw away
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Unpushed {
+    pub branch: String,
+    /// What they were compared against - the upstream, or the remote branch of the same
+    /// name; None when neither exists and nothing on any remote counted as pushed
+    pub base: Option<String>,
+    /// "abc1234 subject", newest first
+    pub commits: Vec<String>,
+}
+
+/// Commits on the install's branch that no remote has, or None when there are none or
+/// the install isn't a git checkout
+pub fn unpushed_commits(addon_path: &Path) -> Option<Unpushed> {
+    if !addon_path.join(".git").exists() {
+        return None;
+    }
+    let branch = git_output(addon_path, &["rev-parse", "--abbrev-ref", "HEAD"]).ok()?;
+    let base = git_output(addon_path, &["rev-parse", "--abbrev-ref", "--symbolic-full-name", "@{upstream}"])
+        .ok()
+        .or_else(|| {
+            let same_name = format!("origin/{}", branch);
+            git_output(addon_path, &["rev-parse", "-q", "--verify", &format!("refs/remotes/{}", same_name)])
+                .ok()
+                .map(|_| same_name)
+        });
+    let log = match &base {
+        Some(base) => git_output(addon_path, &["log", "--format=%h %s", &format!("{}..HEAD", base)]),
+        None => git_output(addon_path, &["log", "--format=%h %s", "HEAD", "--not", "--remotes"]),
+    }
+    .ok()?;
+    let commits: Vec<String> = log.lines().map(str::to_string).collect();
+    (!commits.is_empty()).then_some(Unpushed { branch, base, commits })
+}
+
+/// Back out of a pull stuck on conflicts, putting the install back where it was before
+#[tauri::command]
+pub fn abort_merge(
+    blender_version: String,
+    addon_id: Option<String>,
+    ops: State<'_, OperationState>,
+) -> Result<bool, String> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    let guard = ops.begin("abort_merge", &blender_version)?;
+    let result = abort_interrupted_pull(&addon, &blender_version);
+    guard.finish(&result);
+    result.map(|_| true)
+}
+
+fn abort_interrupted_pull(addon: &Addon, blender_version: &str) -> Result<(), String> {
+    let addon_path = git_addon_dir(addon, blender_version)?;
+    match interrupted_pull(&addon_path) {
+        Some("merge") => git_output(&addon_path, &["merge", "--abort"])
+            // Merges without ORIG_HEAD refuse --abort; reset --merge does the same job
+            .or_else(|_| git_output(&addon_path, &["reset", "--merge"]))
+            .map_err(|e| format!("Failed to abort the merge: {}", e))?,
+        Some(_) => {
+            git_output(&addon_path, &["rebase", "--abort"]).map_err(|e| format!("Failed to abort the rebase: {}", e))?
+        }
+        None => return Err("No merge in progress - nothing to fix".to_string()),
+    };
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OptimizeResult {
+    #[serde(rename = "beforeBytes")]
+    pub before_bytes: u64,
+    #[serde(rename = "afterBytes")]
+    pub after_bytes: u64,
+}
+
+#[tauri::command]
+pub async fn optimize_repo(
+    blender_version: String,
+    addon_id: Option<String>,
+    ops: State<'_, OperationState>,
+) -> Result<OptimizeResult, String> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    let guard = ops.begin("optimize_repo", &blender_version)?;
+    tokio::task::spawn_blocking(move || {
+        let result = optimize(&addon, &blender_version);
+        guard.finish(&result);
+        result
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+fn optimize(addon: &Addon, blender_version: &str) -> Result<OptimizeResult, String> {
+    let addon_path = git_addon_dir(addon, blender_version)?;
+    let git_dir = addon_path.join(".git");
+
+    let before_bytes = dir_size(&git_dir);
+    git_output(&addon_path, &["repack", "-a", "-d", "--quiet"]).map_err(|e| format!("git repack failed: {}", e))?;
+    git_output(&addon_path, &["gc", "--prune=now", "--quiet"]).map_err(|e| format!("git gc failed: {}", e))?;
+    let after_bytes = dir_size(&git_dir);
+
+    Ok(OptimizeResult { before_bytes, after_bytes })
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitIdentity {
+    pub name: Option<String>,
+    pub email: Option<String>,
+}
+
+#[tauri::command]
+pub fn set_git_identity(
+    name: String,
+    email: String,
+    blender_version: String,
+    addon_id: Option<String>,
+) -> Result<bool, String> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    let (name, email) = (name.trim(), email.trim());
+    if name.is_empty() {
+        return Err("Name cannot be empty".to_string());
+    }
+    if !email.contains('@') {
+        return Err(format!("'{}' doesn't look like an email address", email));
+    }
+
+    let addon_path = git_addon_dir(&addon, &blender_version)?;
+    apply_git_identity(&addon_path, name, email)?;
+    Ok(true)
+}
+
+#[tauri::command]
+pub fn get_git_identity(blender_version: String, addon_id: Option<String>) -> Result<GitIdentity, String> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    let addon_path = git_addon_dir(&addon, &blender_version)?;
+    // Effective values - repo config falling back to the user's global config
+    Ok(GitIdentity {
+        name: git_output(&addon_path, &["config", "--get", "user.name"]).ok(),
+        email: git_output(&addon_path, &["config", "--get", "user.email"]).ok(),
+    })
+}
+
+/// Write user.name / user.email into the repo's own config (not the user's global one)
+pub fn apply_git_identity(addon_path: &Path, name: &str, email: &str) -> Result<(), String> {
+    git_output(addon_path, &["config", "--local", "user.name", name])
+        .map_err(|e| format!("Failed to set user.name: {}", e))?;
+    git_output(addon_path, &["config", "--local", "user.email", email])
+        .map_err(|e| format!("Failed to set user.email: {}", e))?;
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FileModeInfo {
+    /// Effective core.fileMode - whether git treats the executable bit as a change
+    pub enabled: bool,
+    /// Set in the repo's own config rather than inherited
+    #[serde(rename = "setLocally")]
+    pub set_locally: bool,
+    /// Files that only show as modified because their executable bit differs
+    #[serde(rename = "modeOnlyChanges")]
+    pub mode_only_changes: usize,
+}
+
+#[tauri::command]
+pub fn get_file_mode(blender_version: String, addon_id: Option<String>) -> Result<FileModeInfo, String> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    let addon_path = git_addon_dir(&addon, &blender_version)?;
+    let local = git_output(&addon_path, &["config", "--local", "--bool", "--get", "core.fileMode"]).ok();
+    Ok(FileModeInfo {
+        enabled: file_mode_enabled(&addon_path),
+        set_locally: local.is_some(),
+        mode_only_changes: worktree_changes(&addon_path).mode_only,
+    })
+}
+
+/// Turn off core.fileMode for an install shared between Windows and Linux/WSL, where
+/// the executable bit flips on every file and they all look modified
+#[tauri::command]
+pub fn set_file_mode(blender_version: String, enabled: bool, addon_id: Option<String>) -> Result<bool, String> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    let addon_path = git_addon_dir(&addon, &blender_version)?;
+    git_output(&addon_path, &["config", "--local", "core.fileMode", if enabled { "true" } else { "false" }])
+        .map_err(|e| format!("Failed to set core.fileMode: {}", e))?;
+    Ok(true)
+}
+
+pub struct WorktreeChanges {
+    /// Tracked files with real edits
+    pub dirty: bool,
+    /// Tracked files whose only change is the executable bit, while git is counting that;
+    /// 0 once core.fileMode is off
+    pub mode_only: usize,
+}
+
+/// Whether the install has local edits, not counting executable-bit flips - those come
+/// from moving the checkout between filesystems, never from editing the addon
+pub fn worktree_changes(addon_path: &Path) -> WorktreeChanges {
+    let real = changed_paths(addon_path, false).unwrap_or_default();
+    let mode_only = if file_mode_enabled(addon_path) {
+        changed_paths(addon_path, true).unwrap_or_default().difference(&real).count()
+    } else {
+        0
+    };
+    WorktreeChanges { dirty: !real.is_empty(), mode_only }
+}
+
+fn file_mode_enabled(addon_path: &Path) -> bool {
+    git_output(addon_path, &["config", "--bool", "--get", "core.fileMode"]).map_or(true, |v| v == "true")
+}
+
+/// Tracked paths `git status` reports, with core.fileMode forced to `file_mode`. Untracked
+/// files are left out - user data the addon writes lives in the checkout too.
+fn changed_paths(addon_path: &Path, file_mode: bool) -> Option<BTreeSet<String>> {
+    let file_mode = format!("core.fileMode={}", file_mode);
+    let entries = porcelain_status(addon_path, &["-c", &file_mode, "status", "--untracked-files=no"]).ok()?;
+    Some(entries.into_iter().map(|entry| entry.path).collect())
+}
+
+/// Run `git <args> --porcelain=v1 -z` - a `status` command line - and parse what it reports
+pub fn porcelain_status(addon_path: &Path, args: &[&str]) -> Result<Vec<StatusEntry>, String> {
+    // Not git_output: trimming would eat the leading space of the first status code
+    let output = gitbin::git_command()
+        .args(args)
+        .args(["--porcelain=v1", "-z"])
+        .current_dir(addon_path)
+        .output()
+        .map_err(|e| gitbin::run_error(&e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(parse_porcelain_z(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// One entry of `git status --porcelain=v1 -z`
+#[derive(Debug, PartialEq)]
+pub struct StatusEntry {
+    /// The two-letter status, e.g. " M", "D " or "??"
+    pub code: String,
+    pub path: String,
+    /// Where a rename or copy came from
+    pub source: Option<String>,
+}
+
+/// Split `git status --porcelain=v1 -z` output into entries. A rename or copy is followed
+/// by its source as a bare path with no status code, however short, so that's taken
+/// before anything too short to be an entry is passed over.
+pub fn parse_porcelain_z(status: &str) -> Vec<StatusEntry> {
+    let mut entries = Vec::new();
+    let mut fields = status.split('\0');
+    while let Some(field) = fields.next() {
+        // Status paths start after the two-letter code and a space
+        let (Some(code), Some(path)) = (field.get(..2), field.get(3..)) else { continue };
+        let source = if code.contains(['R', 'C']) { fields.next().map(str::to_string) } else { None };
+        entries.push(StatusEntry { code: code.to_string(), path: path.to_string(), source });
+    }
+    entries
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SyncResult {
+    /// "up_to_date", "fast_forwarded" or "diverged"
+    pub status: String,
+    #[serde(rename = "upstreamBranch")]
+    pub upstream_branch: String,
+    #[serde(rename = "commitsBroughtIn")]
+    pub commits_brought_in: u32,
+    pub ahead: u32,
+    pub behind: u32,
+}
+
+/// The official repository, unless the user pointed the manager somewhere else. The
+/// setting is Serpens' alone; other addons sync with their own repo.
+pub fn upstream_url(addon: &Addon) -> String {
+    load_settings()
+        .map(|s| s.upstream_url)
+        .ok()
+        .filter(|url| !url.trim().is_empty() && addon.is_builtin())
+        .unwrap_or_else(|| addon.url())
+}
+
+#[tauri::command]
+pub async fn sync_with_upstream(
+    blender_version: String,
+    addon_id: Option<String>,
+    ops: State<'_, OperationState>,
+) -> Result<SyncResult, String> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    let guard = ops.begin("sync_with_upstream", &blender_version)?;
+    tokio::task::spawn_blocking(move || {
+        let result = sync_upstream(&addon, &blender_version);
+        guard.finish(&result);
+        result
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+fn sync_upstream(addon: &Addon, blender_version: &str) -> Result<SyncResult, String> {
+    let addon_path = git_addon_dir(addon, blender_version)?;
+    let url = upstream_url(addon);
+
+    match git_output(&addon_path, &["remote", "get-url", "upstream"]) {
+        Ok(existing) if existing == url => {}
+        Ok(_) => {
+            git_output(&addon_path, &["remote", "set-url", "upstream", &url])?;
+        }
+        Err(_) => {
+            git_output(&addon_path, &["remote", "add", "upstream", &url])?;
+        }
+    }
+
+    let branch = git_output(&addon_path, &["rev-parse", "--abbrev-ref", "HEAD"])?;
+    if branch == "HEAD" {
+        return Err("HEAD is detached - check out a branch before syncing".to_string());
+    }
+
+    // Output: "ref: refs/heads/main\tHEAD"
+    let symref = git_output(&addon_path, &["ls-remote", "--symref", "upstream", "HEAD"])
+        .map_err(|e| format!("Failed to query upstream: {}", e))?;
+    let default_branch = symref
+        .lines()
+        .find_map(|l| l.strip_prefix("ref: refs/heads/"))
+        .and_then(|l| l.split('\t').next())
+        .ok_or("Could not determine the upstream default branch")?
+        .to_string();
+
+    // Ahead/behind counts need a common ancestor, which a depth-1 clone doesn't have
+    if git_output(&addon_path, &["rev-parse", "--is-shallow-repository"]).as_deref() == Ok("true") {
+        git_output(&addon_path, &["fetch", "--unshallow", "origin"])
+            .map_err(|e| format!("Failed to fetch full history: {}", e))?;
+    }
+    git_output(&addon_path, &["fetch", "--", "upstream", &default_branch])
+        .map_err(|e| format!("Failed to fetch upstream: {}", e))?;
+
+    let upstream_ref = format!("upstream/{}", default_branch);
+    let (ahead, behind) = ahead_behind(&addon_path, "HEAD", &upstream_ref)?;
+
+    let (status, brought_in) = if behind == 0 {
+        ("up_to_date", 0)
+    } else if ahead == 0 {
+        git_output(&addon_path, &["merge", "--ff-only", &upstream_ref])
+            .map_err(|e| format!("Fast-forward failed: {}", e))?;
+        ("fast_forwarded", behind)
+    } else {
+        // Both sides have commits - leave the branch alone and let the user decide
+        ("diverged", 0)
+    };
+
+    Ok(SyncResult {
+        status: status.to_string(),
+        upstream_branch: upstream_ref,
+        commits_brought_in: brought_in,
+        ahead,
+        behind,
+    })
+}
+
+/// How many commits `local` has that `other` doesn't, and vice versa
+pub fn ahead_behind(addon_path: &Path, local: &str, other: &str) -> Result<(u32, u32), String> {
+    let counts = git_output(addon_path, &["rev-list", "--left-right", "--count", &format!("{}...{}", local, other)])?;
+    let mut parts = counts.split_whitespace().map(|n| n.parse::<u32>().unwrap_or(0));
+    Ok((parts.next().unwrap_or(0), parts.next().unwrap_or(0)))
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BundleResult {
+    pub path: String,
+    #[serde(rename = "sizeBytes")]
+    pub size_bytes: u64,
+}
+
+/// Pack every ref and its full history into a single file that `git clone` accepts.
+/// `dest_path` may be a file name or an existing folder to put the bundle in.
+#[tauri::command]
+pub async fn export_bundle(
+    dest_path: String,
+    blender_version: String,
+    addon_id: Option<String>,
+    ops: State<'_, OperationState>,
+) -> Result<BundleResult, String> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    let guard = ops.begin("export_bundle", &blender_version)?;
+    tokio::task::spawn_blocking(move || {
+        let result = write_bundle(&addon, &blender_version, &dest_path);
+        guard.finish(&result);
+        result
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+fn write_bundle(addon: &Addon, blender_version: &str, dest_path: &str) -> Result<BundleResult, String> {
+    let addon_path = git_addon_dir(addon, blender_version)?;
+
+    let mut dest = PathBuf::from(dest_path);
+    if dest.is_dir() {
+        let branch = git_output(&addon_path, &["rev-parse", "--abbrev-ref", "HEAD"])?;
+        dest = dest.join(format!("{}-{}.bundle", addon.folder_name, branch.replace('/', "-")));
+    }
+    if let Some(parent) = dest.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create destination: {}", e))?;
+    }
+
+    // A bundle of a shallow clone lists the missing history as prerequisites and
+    // can't be cloned on a machine that doesn't already have it
+    if git_output(&addon_path, &["rev-parse", "--is-shallow-repository"]).as_deref() == Ok("true") {
+        git_output(&addon_path, &["fetch", "--unshallow", "origin"])
+            .map_err(|e| format!("Failed to fetch full history: {}", e))?;
+    }
+
+    let dest_str = dest.to_string_lossy().to_string();
+    git_output(&addon_path, &["bundle", "create", &dest_str, "--all"])
+        .map_err(|e| format!("git bundle failed: {}", e))?;
+
+    Ok(BundleResult {
+        size_bytes: fs::metadata(&dest).map(|m| m.len()).unwrap_or(0),
+        path: dest_str,
+    })
+}
+
+/// Put a detached install back on a branch at the commit it's on, tracking
+/// `origin/<branch>` when the remote has one, so pulls work again.
+#[tauri::command]
+pub fn attach_to_branch(
+    branch: String,
+    blender_version: String,
+    addon_id: Option<String>,
+    ops: State<'_, OperationState>,
+) -> Result<bool, String> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    validate::branch_name(&branch)?;
+    let guard = ops.begin("attach_to_branch", &blender_version)?;
+    let result = attach(&addon, &branch, &blender_version);
+    guard.finish(&result);
+    result
+}
+
+fn attach(addon: &Addon, branch: &str, blender_version: &str) -> Result<bool, String> {
+    let addon_path = git_addon_dir(addon, blender_version)?;
+
+    if git_output(&addon_path, &["rev-parse", "--abbrev-ref", "HEAD"])? != "HEAD" {
+        return Err("HEAD is already on a branch".to_string());
+    }
+
+    let local_ref = format!("refs/heads/{}", branch);
+    if git_output(&addon_path, &["rev-parse", "--verify", "--quiet", &local_ref]).is_ok() {
+        // Moving an existing branch is only safe if it loses nothing
+        if git_output(&addon_path, &["merge-base", "--is-ancestor", &local_ref, "HEAD"]).is_err() {
+            return Err(format!(
+                "Local branch '{}' has commits that aren't in the current checkout - pick another name",
+                branch
+            ));
+        }
+        git_output(&addon_path, &["checkout", "-B", branch])
+    } else {
+        git_output(&addon_path, &["checkout", "-b", branch])
+    }
+    .map_err(|e| format!("Failed to create branch: {}", e))?;
+
+    // Single-branch clones only fetch what they were cloned with, so ask for this one by name
+    let remote_ref = format!("refs/remotes/origin/{}", branch);
+    let fetched =
+        git_output(&addon_path, &["fetch", "--", "origin", &format!("+{}:{}", local_ref, remote_ref)]).is_ok();
+    if fetched {
+        git_output(&addon_path, &["branch", "--set-upstream-to", &format!("origin/{}", branch)])
+            .map_err(|e| format!("Failed to set upstream: {}", e))?;
+    }
+
+    let mut marker = read_marker(&addon_path).unwrap_or_default();
+    marker.branch = Some(branch.to_string());
+    write_marker(addon, &addon_path, &marker)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::{bare_remote, commit, git, scratch};
+
+    /// A working copy on `main` with one commit pushed to a bare `origin` next to it
+    fn pushed_clone(name: &str) -> (PathBuf, PathBuf) {
+        let dir = scratch("repo", name);
+        let remote = bare_remote(&dir);
+        let clone = dir.join("clone");
+        git(&dir, &["init", "-q", "-b", "main", &clone.to_string_lossy()]);
+        git(&clone, &["remote", "add", "origin", &remote.to_string_lossy()]);
+        commit(&clone, "a.txt", "Pushed");
+        git(&clone, &["push", "-q", "-u", "origin", "main"]);
+        (dir, clone)
+    }
+
+    #[test]
+    fn local_only_commit_is_unpushed() {
+        let (dir, clone) = pushed_clone("local-only");
+        assert!(unpushed_commits(&clone).is_none());
+
+        commit(&clone, "b.txt", "Local only");
+        let unpushed = unpushed_commits(&clone).expect("the local commit isn't on origin");
+        assert_eq!(unpushed.branch, "main");
+        assert_eq!(unpushed.base.as_deref(), Some("origin/main"));
+        assert_eq!(unpushed.commits.len(), 1);
+        assert!(unpushed.commits[0].ends_with(" Local only"), "{:?}", unpushed.commits);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn branch_without_upstream_counts_commits_no_remote_has() {
+        let (dir, clone) = pushed_clone("no-upstream");
+        git(&clone, &["checkout", "-q", "-b", "feature"]);
+        commit(&clone, "c.txt", "Feature work");
+        let unpushed = unpushed_commits(&clone).expect("the feature commit isn't on any remote");
+        assert_eq!(unpushed.branch, "feature");
+        assert_eq!(unpushed.base, None);
+        assert_eq!(unpushed.commits.len(), 1);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn short_rename_sources_are_not_taken_for_entries() {
+        let entries = parse_porcelain_z("R  b.c\0a.c\0 M zz.txt\0?? new.py\0");
+        assert_eq!(
+            entries,
+            vec![
+                StatusEntry {
+                    code: "R ".to_string(),
+                    path: "b.c".to_string(),
+                    source: Some("a.c".to_string())
+                },
+                StatusEntry { code: " M".to_string(), path: "zz.txt".to_string(), source: None },
+                StatusEntry { code: "??".to_string(), path: "new.py".to_string(), source: None },
+            ]
+        );
+        assert!(parse_porcelain_z("").is_empty());
+    }
+
+    #[test]
+    fn changed_paths_survive_a_short_named_rename() {
+        let (dir, clone) = pushed_clone("short-rename");
+        commit(&clone, "a.c", "Short name");
+        commit(&clone, "zz.txt", "Longer name");
+        git(&clone, &["mv", "a.c", "b.c"]);
+        fs::write(clone.join("zz.txt"), "Edited").unwrap();
+        let paths = changed_paths(&clone, true).expect("git status runs");
+        assert_eq!(paths.into_iter().collect::<Vec<_>>(), ["b.c", "zz.txt"]);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn folder_without_git_has_nothing_unpushed() {
+        let dir = scratch("repo", "not-git");
+        assert!(unpushed_commits(&dir).is_none());
+        fs::remove_dir_all(&dir).ok();
+    }
+}
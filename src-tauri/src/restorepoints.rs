@@ -0,0 +1,263 @@
+// Named restore points for git installs: a local tag at the installed commit plus a stash
+// commit of any edits, so getting back is a checkout instead of copying a backup folder.
+// They live in the install's repository, so a branch switch (a fresh clone) ends them.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::State;
+
+use crate::addons::Addon;
+use crate::ops::OperationState;
+use crate::{addons, git_addon_dir, git_output, gitbin, pycache, validate};
+
+const TAG_PREFIX: &str = "serpens/";
+/// Where each restore point's stash commit is kept: outside refs/stash, so dropping
+/// stashes never takes one with it
+const STASH_REFS: &str = "refs/serpens/states/";
+/// Used for the tag and stash commits when the install has no git identity of its own
+const FALLBACK_NAME: &str = "Serpens Dev Manager";
+const FALLBACK_EMAIL: &str = "serpens-dev-manager@localhost";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RestorePoint {
+    pub label: String,
+    /// "serpens/<label>"
+    pub tag: String,
+    pub commit: String,
+    /// The branch checked out when it was made
+    pub branch: Option<String>,
+    /// Local edits were saved with it
+    pub stashed: bool,
+    pub date: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RestoreResult {
+    pub label: String,
+    pub commit: String,
+    /// The branch has moved on since, so the install is left on the commit itself
+    pub detached: bool,
+    /// The restore point's edits were applied again
+    #[serde(rename = "restoredChanges")]
+    pub restored_changes: bool,
+    /// Edits in the install beforehand, stashed under this message rather than lost
+    #[serde(rename = "savedCurrent")]
+    pub saved_current: Option<String>,
+}
+
+/// One path segment of a tag name: no slashes, and nothing git refuses in a refname
+fn check_label(label: &str) -> Result<(), String> {
+    let valid = !label.is_empty()
+        && label.len() <= 64
+        && !label.starts_with(['.', '-'])
+        && !label.ends_with(".lock")
+        && !label.contains("..")
+        && label.chars().all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c));
+    if !valid {
+        return Err(format!("Invalid label '{}': use up to 64 letters, digits, '-', '_' or '.'", label));
+    }
+    Ok(())
+}
+
+/// `-c user.name=... -c user.email=...` when the install has no identity to sign with
+fn identity_args(addon_path: &Path) -> Vec<String> {
+    let mut args = Vec::new();
+    if git_output(addon_path, &["config", "user.name"]).is_err() {
+        args.extend(["-c".to_string(), format!("user.name={}", FALLBACK_NAME)]);
+    }
+    if git_output(addon_path, &["config", "user.email"]).is_err() {
+        args.extend(["-c".to_string(), format!("user.email={}", FALLBACK_EMAIL)]);
+    }
+    args
+}
+
+fn git_as_manager(addon_path: &Path, args: &[&str]) -> Result<String, String> {
+    let identity = identity_args(addon_path);
+    let mut full: Vec<&str> = identity.iter().map(String::as_str).collect();
+    full.extend(args);
+    git_output(addon_path, &full)
+}
+
+/// Tag the installed commit as `label` and keep a stash commit of any local edits with it.
+/// The working tree is left exactly as it was.
+#[tauri::command]
+pub fn tag_state(
+    label: String,
+    blender_version: String,
+    addon_id: Option<String>,
+    ops: State<'_, OperationState>,
+) -> Result<RestorePoint, String> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    validate::blender_version(&blender_version)?;
+    check_label(&label)?;
+    let guard = ops.begin("tag_state", &blender_version)?;
+    let result = tag(&addon, &label, &blender_version);
+    guard.finish(&result);
+    result
+}
+
+fn tag(addon: &Addon, label: &str, blender_version: &str) -> Result<RestorePoint, String> {
+    let addon_path = git_addon_dir(addon, blender_version)?;
+    let tag = format!("{}{}", TAG_PREFIX, label);
+    if git_output(&addon_path, &["rev-parse", "--verify", "--quiet", &format!("refs/tags/{}", tag)]).is_ok() {
+        return Err(format!("There is already a restore point called '{}'", label));
+    }
+    let commit = git_output(&addon_path, &["rev-parse", "HEAD"])?;
+    let branch = git_output(&addon_path, &["symbolic-ref", "--short", "--quiet", "HEAD"]).ok();
+
+    // `stash create` makes the commit without touching the working tree or the stash
+    // list, and prints nothing when there's nothing to save
+    let stash = git_as_manager(&addon_path, &["stash", "create", &format!("Restore point {}", label)])
+        .map_err(|e| format!("Failed to save local edits: {}", e))?;
+    if !stash.is_empty() {
+        git_output(&addon_path, &["update-ref", &format!("{}{}", STASH_REFS, label), &stash])
+            .map_err(|e| format!("Failed to keep local edits: {}", e))?;
+    }
+    let message = format!("branch: {}", branch.as_deref().unwrap_or("(detached)"));
+    git_as_manager(&addon_path, &["tag", "--annotate", "--message", &message, &tag, &commit])
+        .map_err(|e| format!("Failed to create tag {}: {}", tag, e))?;
+
+    Ok(RestorePoint {
+        label: label.to_string(),
+        tag,
+        commit,
+        branch,
+        stashed: !stash.is_empty(),
+        date: chrono::Local::now().to_rfc3339(),
+    })
+}
+
+#[tauri::command]
+pub fn list_states(blender_version: String, addon_id: Option<String>) -> Result<Vec<RestorePoint>, String> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    validate::blender_version(&blender_version)?;
+    states(&addon, &blender_version)
+}
+
+fn states(addon: &Addon, blender_version: &str) -> Result<Vec<RestorePoint>, String> {
+    let addon_path = git_addon_dir(addon, blender_version)?;
+    let listing = git_output(
+        &addon_path,
+        &[
+            "for-each-ref",
+            "--sort=-creatordate",
+            "--format=%(refname:strip=3)%1f%(*objectname)%1f%(contents:subject)%1f%(creatordate:iso-strict)",
+            &format!("refs/tags/{}", TAG_PREFIX),
+        ],
+    )?;
+    Ok(listing
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\x1f');
+            let label = fields.next()?.to_string();
+            let commit = fields.next()?.to_string();
+            let branch = fields.next()?.strip_prefix("branch: ").filter(|b| *b != "(detached)").map(str::to_string);
+            let stash_ref = format!("{}{}", STASH_REFS, label);
+            Some(RestorePoint {
+                tag: format!("{}{}", TAG_PREFIX, label),
+                stashed: git_output(&addon_path, &["rev-parse", "--verify", "--quiet", &stash_ref]).is_ok(),
+                date: fields.next().unwrap_or_default().to_string(),
+                label,
+                commit,
+                branch,
+            })
+        })
+        .collect())
+}
+
+/// Put the install back on the restore point's commit with its edits. Edits made since
+/// are stashed first, so nothing in the working tree is lost.
+#[tauri::command]
+pub fn restore_state(
+    label: String,
+    blender_version: String,
+    addon_id: Option<String>,
+    ops: State<'_, OperationState>,
+) -> Result<RestoreResult, String> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    validate::blender_version(&blender_version)?;
+    check_label(&label)?;
+    let guard = ops.begin("restore_state", &blender_version)?;
+    let result = restore(&addon, &label, &blender_version);
+    guard.finish(&result);
+    if result.is_ok() {
+        pycache::after_update(&addon, &blender_version);
+    }
+    result
+}
+
+fn restore(addon: &Addon, label: &str, blender_version: &str) -> Result<RestoreResult, String> {
+    let addon_path = git_addon_dir(addon, blender_version)?;
+    let point = states(addon, blender_version)?
+        .into_iter()
+        .find(|p| p.label == label)
+        .ok_or_else(|| format!("No restore point called '{}'", label))?;
+
+    let saved_message = format!("Before restoring {}", label);
+    let before = git_output(&addon_path, &["stash", "list", "--format=%H"]).unwrap_or_default();
+    git_as_manager(&addon_path, &["stash", "push", "--message", &saved_message])
+        .map_err(|e| format!("Failed to stash the current edits: {}", e))?;
+    let saved_current = (git_output(&addon_path, &["stash", "list", "--format=%H"]).unwrap_or_default() != before)
+        .then_some(saved_message);
+
+    // Back on the branch when it still points here; a branch that moved on isn't reset,
+    // so the install is left on the commit instead
+    let on_branch = point.branch.as_deref().filter(|branch| {
+        git_output(&addon_path, &["rev-parse", "--verify", "--quiet", &format!("refs/heads/{}", branch)])
+            .is_ok_and(|tip| tip == point.commit)
+    });
+    let checkout = match on_branch {
+        Some(branch) => git_output(&addon_path, &["checkout", "--quiet", branch]),
+        None => git_output(&addon_path, &["checkout", "--quiet", "--detach", &point.tag]),
+    };
+    checkout.map_err(|e| format!("Failed to check out {}: {}", point.tag, e))?;
+
+    let restored_changes = point.stashed;
+    if restored_changes {
+        let stash = format!("{}{}", STASH_REFS, label);
+        let output = gitbin::git_command()
+            .args(["stash", "apply", "--index", &stash])
+            .current_dir(&addon_path)
+            .output()
+            .map_err(|e| gitbin::run_error(&e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "Checked out {} but its edits didn't apply: {}",
+                point.tag,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+    }
+
+    Ok(RestoreResult {
+        label: label.to_string(),
+        commit: point.commit,
+        detached: on_branch.is_none(),
+        restored_changes,
+        saved_current,
+    })
+}
+
+#[tauri::command]
+pub fn delete_state(
+    label: String,
+    blender_version: String,
+    addon_id: Option<String>,
+    ops: State<'_, OperationState>,
+) -> Result<bool, String> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    validate::blender_version(&blender_version)?;
+    check_label(&label)?;
+    let guard = ops.begin("delete_state", &blender_version)?;
+    let result = delete(&addon, &label, &blender_version);
+    guard.finish(&result);
+    result
+}
+
+fn delete(addon: &Addon, label: &str, blender_version: &str) -> Result<bool, String> {
+    let addon_path = git_addon_dir(addon, blender_version)?;
+    git_output(&addon_path, &["tag", "--delete", &format!("{}{}", TAG_PREFIX, label)])
+        .map_err(|_| format!("No restore point called '{}'", label))?;
+    git_output(&addon_path, &["update-ref", "-d", &format!("{}{}", STASH_REFS, label)]).ok();
+    Ok(true)
+}
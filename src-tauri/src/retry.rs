@@ -0,0 +1,213 @@
+// The last switch or pull that failed, so it can be tried again in one click. A clone
+// that finished downloading but failed a later step (a locked file at the swap, a
+// permissions fix) is kept, and the retry only fetches what changed since.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::process::Output;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::addons::Addon;
+use crate::notices::Notice;
+use crate::ops::{OperationState, Progress};
+use crate::{addons, addons_dir, applog, git_output, gitbin, giturl, remotes, remove_dir_robust};
+
+/// Where the kept clone waits beside the install; deliberately not a staging name, so
+/// starting the retry doesn't sweep it away first
+const PARTIAL_NAME: &str = ".serpens_resume";
+/// One per addon, in the addon's own data folder
+const RECORD_FILE: &str = "last-failed-operation.json";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FailedOperation {
+    /// "switch_branch" or "pull_latest"
+    pub kind: String,
+    #[serde(rename = "blenderVersion")]
+    pub blender_version: String,
+    pub branch: Option<String>,
+    pub remote: Option<String>,
+    /// The switch was told to discard unpushed commits
+    pub force: bool,
+    pub error: String,
+    #[serde(rename = "failedAt")]
+    pub failed_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RetryResult {
+    pub kind: String,
+    #[serde(rename = "blenderVersion")]
+    pub blender_version: String,
+    /// "resumed" when a kept clone was brought up to date, "restarted" when it began again
+    pub method: String,
+}
+
+impl FailedOperation {
+    pub fn new(
+        kind: &str,
+        blender_version: &str,
+        branch: Option<&str>,
+        remote: Option<&str>,
+        force: bool,
+    ) -> FailedOperation {
+        FailedOperation {
+            kind: kind.to_string(),
+            blender_version: blender_version.to_string(),
+            branch: branch.map(str::to_string),
+            remote: remote.map(str::to_string),
+            force,
+            error: String::new(),
+            failed_at: String::new(),
+        }
+    }
+}
+
+/// Remember `operation` when it failed, or forget an earlier failure for its Blender
+/// version once it went through
+pub fn note_outcome(addon: &Addon, app: &AppHandle, operation: FailedOperation, error: Option<&String>) {
+    let Ok(path) = addon.data_root().map(|dir| dir.join(RECORD_FILE)) else { return };
+    let Some(error) = error else {
+        if read(addon).is_some_and(|last| last.blender_version == operation.blender_version) {
+            fs::remove_file(&path).ok();
+        }
+        return;
+    };
+    let failed = FailedOperation {
+        error: error.clone(),
+        failed_at: chrono::Local::now().to_rfc3339(),
+        ..operation
+    };
+    let written = serde_json::to_string_pretty(&failed)
+        .map_err(|e| e.to_string())
+        .and_then(|json| fs::write(&path, json).map_err(|e| e.to_string()));
+    if let Err(e) = written {
+        applog::warn(&format!("Couldn't remember the failed {} for a retry: {}", failed.kind, e));
+        return;
+    }
+    // Every failure is news, so this skips the notice rate limit
+    let message = format!("The {} failed - it can be retried", describe(&failed));
+    let notice = Notice::warning("retry_available", message, Some("retry"));
+    app.emit("serpens://notice", &notice).ok();
+}
+
+fn describe(operation: &FailedOperation) -> String {
+    match (operation.kind.as_str(), &operation.branch) {
+        ("switch_branch", Some(branch)) => format!("switch to {}", branch),
+        ("pull_latest", _) => "pull".to_string(),
+        (kind, _) => kind.replace('_', " "),
+    }
+}
+
+fn read(addon: &Addon) -> Option<FailedOperation> {
+    let content = fs::read_to_string(addon.data_root().ok()?.join(RECORD_FILE)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// The failure `retry_last_operation` would retry, if any
+#[tauri::command]
+pub fn last_failed_operation(addon_id: Option<String>) -> Option<FailedOperation> {
+    let addon = addons::resolve(addon_id.as_deref()).ok()?;
+    read(&addon)
+}
+
+/// Run the last failed operation again with the same parameters, picking up a kept
+/// clone where there is one
+#[tauri::command]
+pub fn retry_last_operation(
+    addon_id: Option<String>,
+    app: AppHandle,
+    ops: State<'_, OperationState>,
+) -> Result<RetryResult, String> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    let operation = read(&addon).ok_or("Nothing to retry - the last operation didn't fail")?;
+    let method = match operation.kind.as_str() {
+        "switch_branch" => {
+            let branch = operation.branch.clone().ok_or("The failed switch didn't record its branch")?;
+            let url = remotes::remote_url(&addon, operation.remote.as_deref())?;
+            let resumable = addons_dir(&operation.blender_version)
+                .is_ok_and(|dir| partial_matches(&dir.join(addon.beside(PARTIAL_NAME)), &url, &branch));
+            crate::switch_branch(
+                branch,
+                operation.blender_version.clone(),
+                operation.remote.clone(),
+                Some(operation.force),
+                addon_id,
+                app,
+                ops,
+            )
+            .map_err(|e| e.message)?;
+            if resumable {
+                "resumed"
+            } else {
+                "restarted"
+            }
+        }
+        // A pull's fetch keeps nothing from a failed attempt, so it always starts over
+        "pull_latest" => {
+            crate::pull_latest(operation.blender_version.clone(), addon_id, app, ops)?;
+            "restarted"
+        }
+        other => return Err(format!("Don't know how to retry '{}'", other)),
+    };
+    applog::info(&format!("Retried the failed {} ({})", describe(&operation), method));
+    Ok(RetryResult {
+        kind: operation.kind,
+        blender_version: operation.blender_version,
+        method: method.to_string(),
+    })
+}
+
+/// A kept clone of `branch` from `source`
+fn partial_matches(partial: &Path, source: &str, branch: &str) -> bool {
+    partial.join(".git").exists()
+        && git_output(partial, &["remote", "get-url", "origin"])
+            .is_ok_and(|url| url == source || giturl::same_repo(&url, source))
+        && git_output(partial, &["rev-parse", "--abbrev-ref", "HEAD"]).is_ok_and(|head| head == branch)
+}
+
+/// Keep the clone in `staging` for a retry, replacing any older one
+pub fn keep_partial(addon: &Addon, staging: &Path, addons_path: &Path) {
+    let partial = addons_path.join(addon.beside(PARTIAL_NAME));
+    if remove_dir_robust(&partial).is_err() || fs::rename(staging, &partial).is_err() {
+        return;
+    }
+    applog::info(&format!("Kept the downloaded clone at {} for a retry", partial.display()));
+}
+
+/// Move a kept clone of `branch` from `source` to `staging`. One for anything else is
+/// out of date the moment a different install starts, so it's dropped.
+pub fn take_partial(addon: &Addon, addons_path: &Path, source: &str, branch: &str, staging: &Path) -> bool {
+    let partial = addons_path.join(addon.beside(PARTIAL_NAME));
+    if !partial.exists() {
+        return false;
+    }
+    if partial_matches(&partial, source, branch) && fs::rename(&partial, staging).is_ok() {
+        return true;
+    }
+    remove_dir_robust(&partial).ok();
+    false
+}
+
+/// Bring a kept clone up to the branch's tip: only what the remote gained since is
+/// downloaded, at the same depth the clone was taken at
+pub fn resume_fetch(repo: &Path, branch: &str, depth: Option<u32>, progress: &Progress) -> Result<Output, String> {
+    let shallow = git_output(repo, &["rev-parse", "--is-shallow-repository"]).is_ok_and(|s| s == "true");
+    let mut fetch = gitbin::git_command();
+    fetch.args(["fetch", "--progress"]).current_dir(repo);
+    if let (true, Some(depth)) = (shallow, depth) {
+        fetch.arg("--depth").arg(depth.to_string());
+    }
+    fetch.args(["origin", &format!("+refs/heads/{0}:refs/remotes/origin/{0}", branch)]);
+    let output = progress.output(&mut fetch).map_err(|e| gitbin::run_error(&e))?;
+    if output.status.success() {
+        git_output(repo, &["reset", "--hard", "--quiet", &format!("origin/{}", branch)])
+            .map_err(|e| format!("Failed to update the kept clone: {}", e))?;
+    }
+    Ok(output)
+}
+
+/// Whether a kept clone was the minimal fallback, which leaves file contents on the remote
+pub fn partial_is_minimal(repo: &Path) -> bool {
+    git_output(repo, &["config", "--get", "remote.origin.promisor"]).is_ok_and(|v| v == "true")
+}
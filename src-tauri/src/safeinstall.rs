@@ -0,0 +1,210 @@
+// Install a branch and keep it only if it checks out: the clone, a syntax check of every
+// file, and optionally Blender enabling it - otherwise the previous install comes back
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Instant;
+use tauri::{AppHandle, State};
+
+use crate::addons::Addon;
+use crate::checks::{load_check, syntax_check};
+use crate::ops::{OperationGuard, OperationState, Progress, Timed};
+use crate::{
+    addon_dir, addons, auto_backup_enabled, backups, copy_dir_all, install_branch_from, notices, notify,
+    pre_switch_backup_dir, refuse_unpushed, remotes, remove_dir_robust, snapshot_install, validate, CopyOptions,
+};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StepReport {
+    /// "install", "syntax", "load" or "rollback"
+    pub name: String,
+    /// "passed", "failed" or "skipped"
+    pub status: String,
+    pub detail: Option<String>,
+    #[serde(rename = "durationMs")]
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SafeInstallReport {
+    pub branch: String,
+    /// Every check passed and the new install is in place
+    pub success: bool,
+    pub steps: Vec<StepReport>,
+    /// A check failed after the install and the previous state was put back
+    #[serde(rename = "rolledBack")]
+    pub rolled_back: bool,
+}
+
+impl SafeInstallReport {
+    fn record(&mut self, name: &str, started: Instant, outcome: Result<Option<String>, String>) -> bool {
+        let (status, detail) = match outcome {
+            Ok(detail) => ("passed", detail),
+            Err(e) => ("failed", Some(e)),
+        };
+        self.steps.push(StepReport {
+            name: name.to_string(),
+            status: status.to_string(),
+            detail,
+            duration_ms: started.elapsed().as_millis() as u64,
+        });
+        status == "passed"
+    }
+
+    fn skip(&mut self, name: &str, why: &str) {
+        self.steps.push(StepReport {
+            name: name.to_string(),
+            status: "skipped".to_string(),
+            detail: Some(why.to_string()),
+            duration_ms: 0,
+        });
+    }
+}
+
+/// Switch to `branch`, then compile every file and, with `load_check`, have Blender enable
+/// the addon. Only a clean pass keeps the new install; a failed check restores the one
+/// from before. The report covers each step either way.
+#[tauri::command]
+pub async fn install_and_verify(
+    branch: String,
+    blender_version: String,
+    remote: Option<String>,
+    load_check: Option<bool>,
+    addon_id: Option<String>,
+    app: AppHandle,
+    ops: State<'_, OperationState>,
+) -> Result<Timed<SafeInstallReport>, String> {
+    validate::branch_name(&branch)?;
+    validate::blender_version(&blender_version)?;
+    let addon = addons::resolve(addon_id.as_deref())?;
+    let url = remotes::remote_url(&addon, remote.as_deref())?;
+    let guard = ops.begin("install_and_verify", &blender_version)?.cancellable();
+    tokio::task::spawn_blocking(move || {
+        let report = run(&addon, &url, &branch, &blender_version, load_check.unwrap_or(false), &app, &guard);
+        let outcome = match &report {
+            Ok(report) if report.success => {
+                Ok(format!("Installed {}{}", branch, notify::commit_suffix(&addon, &blender_version)))
+            }
+            Ok(report) => Err(failure_summary(report)),
+            Err(e) => Err(e.clone()),
+        };
+        guard.finish(&outcome);
+        notify::operation_finished(&app, "Safe install", &outcome);
+        notices::refresh(&addon, &app, &blender_version);
+        report.map(|report| guard.timed(report))
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+fn run(
+    addon: &Addon,
+    url: &str,
+    branch: &str,
+    blender_version: &str,
+    with_load_check: bool,
+    app: &AppHandle,
+    guard: &OperationGuard,
+) -> Result<SafeInstallReport, String> {
+    let progress = guard.progress();
+    let addon_path = addon_dir(addon, blender_version)?;
+    // A rollback puts the old install back wholesale, so commits only it has would survive
+    // a failure but not a success - refuse up front like switch_branch does
+    refuse_unpushed(&addon_path, false).map_err(|e| e.message)?;
+    let had_install = addon_path.exists();
+    // The switch snapshots the old install itself when auto-backup is on
+    if had_install && !auto_backup_enabled() {
+        snapshot_install(addon, &addon_path, blender_version, &progress)?;
+    }
+
+    let mut report = SafeInstallReport {
+        branch: branch.to_string(),
+        success: false,
+        steps: Vec::new(),
+        rolled_back: false,
+    };
+
+    let started = Instant::now();
+    let installed = install_branch_from(addon, url, branch, blender_version, &progress).map(|_| None);
+    if !report.record("install", started, installed) {
+        // The clone is staged, so a failed one never touched the current install
+        report.skip("syntax", "the install failed");
+        report.skip("load", "the install failed");
+        return Ok(report);
+    }
+
+    progress.phase("checking syntax");
+    let started = Instant::now();
+    let syntax = match syntax_check(addon, blender_version, app, guard)? {
+        None => Ok(Some("No Blender or Python found to compile with".to_string())),
+        Some(check) if check.ok => Ok(Some(format!("Compiled with {}", check.interpreter))),
+        Some(check) if check.errors.is_empty() => Err("The syntax check didn't finish".to_string()),
+        Some(check) => Err(check.errors.join("\n")),
+    };
+    let mut passed = report.record("syntax", started, syntax);
+
+    if !passed {
+        report.skip("load", "the syntax check failed");
+    } else if !with_load_check {
+        report.skip("load", "not requested");
+    } else {
+        progress.phase("verifying load");
+        let started = Instant::now();
+        let loaded = load_check(addon, blender_version, app, guard).and_then(|check| {
+            if check.loaded {
+                Ok(None)
+            } else {
+                Err(format!("Blender couldn't enable the addon ({})\n{}", check.status, last_lines(&check.log, 20)))
+            }
+        });
+        passed = report.record("load", started, loaded);
+    }
+
+    if passed && !guard.cancelled() {
+        report.success = true;
+        return Ok(report);
+    }
+    progress.phase("rolling back");
+    let started = Instant::now();
+    let rollback = roll_back(addon, &addon_path, blender_version, had_install, &progress).map(|_| None);
+    report.rolled_back = report.record("rollback", started, rollback);
+    Ok(report)
+}
+
+/// Put the pre-switch snapshot back, or remove the install if there wasn't one before
+fn roll_back(
+    addon: &Addon,
+    addon_path: &Path,
+    blender_version: &str,
+    had_install: bool,
+    progress: &Progress,
+) -> Result<(), String> {
+    remove_dir_robust(addon_path)?;
+    if !had_install {
+        return Ok(());
+    }
+    let backup = pre_switch_backup_dir(addon, blender_version)?;
+    copy_dir_all(&backup, addon_path, CopyOptions::from_settings(), progress)
+        .map_err(|e| format!("Failed to restore the previous install: {}", e))?;
+    backups::strip_meta(addon_path);
+    Ok(())
+}
+
+fn failure_summary(report: &SafeInstallReport) -> String {
+    let failed = report
+        .steps
+        .iter()
+        .find(|s| s.status == "failed")
+        .map(|s| s.name.as_str())
+        .unwrap_or("install");
+    if report.rolled_back {
+        format!("The {} step failed; the previous install was restored", failed)
+    } else {
+        format!("The {} step failed", failed)
+    }
+}
+
+fn last_lines(log: &str, count: usize) -> String {
+    let lines: Vec<&str> = log.lines().collect();
+    lines[lines.len().saturating_sub(count)..].join("\n")
+}
@@ -0,0 +1,249 @@
+// Downloading and applying a newer build of the manager found by check_app_update
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::appupdate::{self, ManagerUpdate};
+#[cfg(windows)]
+use crate::hidden_command;
+use crate::{applog, data_dir, load_settings};
+
+/// Which step went wrong, so the frontend can say more than "update failed"
+#[derive(Debug, Serialize, Clone)]
+pub struct UpdateError {
+    /// "disabled", "check", "download", "verify" or "install"
+    pub stage: String,
+    pub message: String,
+}
+
+impl UpdateError {
+    fn new(stage: &str, message: impl Into<String>) -> UpdateError {
+        UpdateError { stage: stage.to_string(), message: message.into() }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct DownloadedUpdate {
+    pub version: String,
+    pub path: String,
+    #[serde(rename = "sizeBytes")]
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+struct DownloadProgress {
+    downloaded: u64,
+    total: Option<u64>,
+}
+
+/// The verified installer waiting to be applied
+#[derive(Default)]
+pub struct SelfUpdateState(Mutex<Option<DownloadedUpdate>>);
+
+fn ensure_enabled() -> Result<(), UpdateError> {
+    if load_settings().map(|s| s.self_update).unwrap_or(true) {
+        Ok(())
+    } else {
+        Err(UpdateError::new("disabled", "Self-update is turned off in settings"))
+    }
+}
+
+fn updates_dir() -> Result<PathBuf, String> {
+    let dir = data_dir()?.join("updates");
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create updates folder: {}", e))?;
+    Ok(dir)
+}
+
+/// Download the installer for the newest release and check it against the published
+/// SHA-256. Nothing about the running install changes; a failed or unverifiable
+/// download is deleted.
+#[tauri::command]
+pub async fn download_app_update(
+    app: AppHandle,
+    state: State<'_, SelfUpdateState>,
+) -> Result<DownloadedUpdate, UpdateError> {
+    ensure_enabled()?;
+    let downloaded = tokio::task::spawn_blocking(move || download_latest(&app))
+        .await
+        .map_err(|e| UpdateError::new("download", format!("Task failed: {}", e)))??;
+    *state.0.lock().unwrap_or_else(|e| e.into_inner()) = Some(downloaded.clone());
+    Ok(downloaded)
+}
+
+fn download_latest(app: &AppHandle) -> Result<DownloadedUpdate, UpdateError> {
+    let update = appupdate::check_now().map_err(|e| UpdateError::new("check", e))?;
+    if !update.update_available {
+        return Err(UpdateError::new("check", format!("Already on the latest version ({})", update.current_version)));
+    }
+    let (Some(name), Some(checksum_url)) = (update.asset_name.clone(), update.checksum_url.clone()) else {
+        return Err(UpdateError::new(
+            "check",
+            "This release has no verifiable installer for this platform - download it from the release page",
+        ));
+    };
+
+    let dir = updates_dir().map_err(|e| UpdateError::new("download", e))?;
+    let dest = dir.join(&name);
+    let partial = dir.join(format!("{}.part", name));
+    let result = fetch_to(app, &update, &partial).and_then(|(size, digest)| {
+        verify(&checksum_url, &name, &digest)?;
+        fs::rename(&partial, &dest)
+            .map_err(|e| UpdateError::new("download", format!("Failed to save installer: {}", e)))?;
+        Ok(size)
+    });
+    match result {
+        Ok(size_bytes) => {
+            applog::info(&format!("Downloaded manager {} to {}", update.latest_version, dest.display()));
+            Ok(DownloadedUpdate {
+                version: update.latest_version,
+                path: dest.to_string_lossy().to_string(),
+                size_bytes,
+            })
+        }
+        Err(e) => {
+            fs::remove_file(&partial).ok();
+            applog::warn(&format!("Manager update download failed ({}): {}", e.stage, e.message));
+            Err(e)
+        }
+    }
+}
+
+/// Stream the installer to `dest`, hashing as it goes. Returns the size and hex digest.
+fn fetch_to(app: &AppHandle, update: &ManagerUpdate, dest: &Path) -> Result<(u64, String), UpdateError> {
+    let fail = |e: String| UpdateError::new("download", e);
+    let agent = ureq::AgentBuilder::new()
+        .timeout_connect(Duration::from_secs(15))
+        .timeout_read(Duration::from_secs(30))
+        .build();
+    let response = agent
+        .get(&update.download_url)
+        .set("User-Agent", "serpens-dev-manager")
+        .call()
+        .map_err(|e| fail(format!("Download failed: {}", e)))?;
+    let total: Option<u64> = response.header("content-length").and_then(|l| l.parse().ok());
+
+    let mut reader = response.into_reader();
+    let mut file = File::create(dest).map_err(|e| fail(format!("Failed to create download: {}", e)))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    let mut downloaded = 0u64;
+    let mut last_reported = 0u64;
+    loop {
+        let n = reader.read(&mut buffer).map_err(|e| fail(format!("Download interrupted: {}", e)))?;
+        if n == 0 {
+            break;
+        }
+        file.write_all(&buffer[..n])
+            .map_err(|e| fail(format!("Failed to write download: {}", e)))?;
+        hasher.update(&buffer[..n]);
+        downloaded += n as u64;
+        if downloaded - last_reported >= 512 * 1024 {
+            last_reported = downloaded;
+            app.emit("serpens://app-update-progress", DownloadProgress { downloaded, total }).ok();
+        }
+    }
+    file.sync_all().map_err(|e| fail(format!("Failed to write download: {}", e)))?;
+    app.emit("serpens://app-update-progress", DownloadProgress { downloaded, total }).ok();
+
+    if total.is_some_and(|t| t != downloaded) {
+        return Err(fail("Download ended early".to_string()));
+    }
+    let digest = hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect();
+    Ok((downloaded, digest))
+}
+
+/// Checksum files are either a bare digest or "digest  filename" lines
+fn verify(checksum_url: &str, name: &str, digest: &str) -> Result<(), UpdateError> {
+    let fail = |e: String| UpdateError::new("verify", e);
+    let published = ureq::get(checksum_url)
+        .set("User-Agent", "serpens-dev-manager")
+        .timeout(Duration::from_secs(15))
+        .call()
+        .map_err(|e| fail(format!("Couldn't fetch the published checksum: {}", e)))?
+        .into_string()
+        .map_err(|e| fail(format!("Couldn't read the published checksum: {}", e)))?;
+
+    let expected = published.lines().find_map(|line| {
+        let mut fields = line.split_whitespace();
+        let hash = fields.next()?;
+        match fields.next() {
+            Some(file) if file.trim_start_matches('*') != name => None,
+            _ => Some(hash.to_ascii_lowercase()),
+        }
+    });
+    match expected {
+        Some(expected) if expected == digest => Ok(()),
+        Some(_) => Err(fail("Checksum mismatch - the download is corrupt or has been tampered with".to_string())),
+        None => Err(fail(format!("No checksum published for {}", name))),
+    }
+}
+
+/// Hand over to the downloaded installer and quit, coming back up on the new version.
+/// On failure the app keeps running as it was.
+#[tauri::command]
+pub fn install_app_update_and_restart(app: AppHandle, state: State<'_, SelfUpdateState>) -> Result<(), UpdateError> {
+    ensure_enabled()?;
+    let downloaded = state
+        .0
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .ok_or_else(|| UpdateError::new("install", "No update has been downloaded yet"))?;
+    let installer = PathBuf::from(&downloaded.path);
+    if !installer.exists() {
+        return Err(UpdateError::new("install", "The downloaded installer has gone missing - download it again"));
+    }
+
+    apply(&installer).map_err(|e| {
+        applog::warn(&format!("Applying manager update failed: {}", e));
+        UpdateError::new("install", e)
+    })?;
+    applog::info(&format!("Restarting into manager {}", downloaded.version));
+    if cfg!(windows) {
+        // The installer replaces our files and relaunches us once it's done
+        app.exit(0);
+        Ok(())
+    } else {
+        app.restart()
+    }
+}
+
+#[cfg(windows)]
+fn apply(installer: &Path) -> Result<(), String> {
+    let is_msi = installer.extension().is_some_and(|e| e.eq_ignore_ascii_case("msi"));
+    let spawned = if is_msi {
+        hidden_command("msiexec").arg("/i").arg(installer).arg("/passive").spawn()
+    } else {
+        // NSIS installers: passive mode, relaunching the app when finished. The installer
+        // closes the running app itself.
+        hidden_command(installer).args(["/P", "/R"]).spawn()
+    };
+    spawned.map(|_| ()).map_err(|e| format!("Failed to start the installer: {}", e))
+}
+
+/// An AppImage replaces itself; other formats need the system package manager.
+#[cfg(not(windows))]
+fn apply(installer: &Path) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let is_appimage = installer.extension().is_some_and(|e| e == "AppImage");
+    let Some(current) = std::env::var_os("APPIMAGE").map(PathBuf::from).filter(|_| is_appimage) else {
+        return Err(format!("Install {} with your package manager, then restart", installer.display()));
+    };
+    // Copy next to the running image, then rename over it - atomic on one filesystem
+    let staged = current.with_extension("AppImage.new");
+    fs::copy(installer, &staged).map_err(|e| format!("Failed to stage update: {}", e))?;
+    let result = fs::set_permissions(&staged, fs::Permissions::from_mode(0o755))
+        .and_then(|_| fs::rename(&staged, &current))
+        .map_err(|e| format!("Failed to replace the AppImage: {}", e));
+    if result.is_err() {
+        fs::remove_file(&staged).ok();
+    }
+    result
+}
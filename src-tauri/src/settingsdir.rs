@@ -0,0 +1,101 @@
+// Making sure the manager's data folder can be written, so settings saves don't fail
+// on a folder restored or synced with read-only permissions
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::{applog, data_dir};
+
+/// Files in the data folder a settings save replaces
+const SETTINGS_FILES: &[&str] = &["settings.json", "settings.json.tmp"];
+const PROBE_FILE: &str = ".write-test";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SettingsDirCheck {
+    pub path: String,
+    pub writable: bool,
+    /// Read-only flags were cleared to make it writable
+    pub repaired: bool,
+    /// Why settings can't be saved, worded for the user; None when they can
+    pub error: Option<String>,
+}
+
+/// Check the data folder can be written, clearing read-only flags on it and the
+/// settings file if not
+#[tauri::command]
+pub fn check_settings_dir() -> Result<SettingsDirCheck, String> {
+    let dir = data_dir()?;
+    let check = check(&dir);
+    if check.repaired {
+        applog::info(&format!("Made {} writable again", dir.display()));
+    } else if let Some(error) = &check.error {
+        applog::warn(error);
+    }
+    Ok(check)
+}
+
+pub fn check(dir: &Path) -> SettingsDirCheck {
+    let path = dir.to_string_lossy().to_string();
+    let first = fs::create_dir_all(dir).and_then(|_| probe(dir));
+    if first.is_ok() {
+        return SettingsDirCheck { path, writable: true, repaired: false, error: None };
+    }
+    make_writable(dir);
+    match probe(dir) {
+        Ok(()) => SettingsDirCheck { path, writable: true, repaired: true, error: None },
+        Err(e) => SettingsDirCheck {
+            error: Some(format!(
+                "Settings can't be saved: {} isn't writable ({}). Check the folder's permissions, or that it isn't set read-only by a backup or sync tool.",
+                path, e
+            )),
+            path,
+            writable: false,
+            repaired: false,
+        },
+    }
+}
+
+/// What a save does: write a file, replace another with it, and remove it
+fn probe(dir: &Path) -> io::Result<()> {
+    let probe = dir.join(PROBE_FILE);
+    fs::write(&probe, b"ok")?;
+    let result = SETTINGS_FILES
+        .iter()
+        .map(|name| dir.join(name))
+        .filter(|existing| existing.exists())
+        .try_for_each(|existing| {
+            let meta = fs::metadata(&existing)?;
+            if meta.permissions().readonly() {
+                return Err(io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    format!("{} is read-only", existing.display()),
+                ));
+            }
+            Ok(())
+        });
+    fs::remove_file(&probe).ok();
+    result
+}
+
+fn make_writable(dir: &Path) {
+    let targets = std::iter::once(dir.to_path_buf()).chain(SETTINGS_FILES.iter().map(|name| dir.join(name)));
+    for target in targets {
+        let Ok(meta) = fs::metadata(&target) else { continue };
+        let mut permissions = meta.permissions();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            // Owner read/write, and enter for the folder
+            let wanted = if meta.is_dir() { 0o700 } else { 0o600 };
+            permissions.set_mode(permissions.mode() | wanted);
+        }
+        #[cfg(not(unix))]
+        {
+            #[allow(clippy::permissions_set_readonly_false)]
+            permissions.set_readonly(false);
+        }
+        fs::set_permissions(&target, permissions).ok();
+    }
+}
@@ -0,0 +1,71 @@
+// Blender versions whose install is one folder on disk - a working copy linked into
+// several versions, or an addons folder they all use - so working on one changes the rest
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::addons::Addon;
+use crate::{addon_dir, blender, blender_config_root, load_settings};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SharedInstall {
+    /// Where the versions' installs all lead
+    #[serde(rename = "realPath")]
+    pub real_path: String,
+    /// Oldest first
+    pub versions: Vec<String>,
+}
+
+/// Every Blender version with a config folder, plus the one in settings, oldest first
+pub fn known_versions() -> Vec<String> {
+    let mut versions: Vec<String> = blender_config_root()
+        .ok()
+        .and_then(|root| fs::read_dir(root).ok())
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|name| blender::is_version_dir_name(name))
+        .collect();
+    if let Ok(settings) = load_settings() {
+        if !versions.contains(&settings.blender_version) {
+            versions.push(settings.blender_version);
+        }
+    }
+    versions.sort_by_key(|v| blender::version_key(v));
+    versions
+}
+
+/// The versions in `versions` whose installs resolve to the same real path, grouped by
+/// it. A version with nothing installed shares nothing.
+pub fn shared_installs(addon: &Addon, versions: &[String]) -> Vec<SharedInstall> {
+    let mut by_path: BTreeMap<PathBuf, Vec<String>> = BTreeMap::new();
+    for version in versions {
+        let Some(real) = addon_dir(addon, version).ok().and_then(|dir| fs::canonicalize(dir).ok()) else { continue };
+        let group = by_path.entry(real).or_default();
+        if !group.contains(version) {
+            group.push(version.clone());
+        }
+    }
+    by_path
+        .into_iter()
+        .filter(|(_, versions)| versions.len() > 1)
+        .map(|(real, versions)| SharedInstall { real_path: real.to_string_lossy().to_string(), versions })
+        .collect()
+}
+
+/// The other known versions using `blender_version`'s install
+pub fn shared_with(addon: &Addon, blender_version: &str) -> Vec<String> {
+    let mut versions = known_versions();
+    if !versions.iter().any(|v| v == blender_version) {
+        versions.push(blender_version.to_string());
+    }
+    shared_installs(addon, &versions)
+        .into_iter()
+        .find(|group| group.versions.iter().any(|v| v == blender_version))
+        .map(|group| group.versions.into_iter().filter(|v| v != blender_version).collect())
+        .unwrap_or_default()
+}
@@ -0,0 +1,156 @@
+// Install specs - "repo#branch@commit" strings that pin an install to an exact state
+
+use std::fmt;
+use tauri::{AppHandle, State};
+
+use crate::addons::Addon;
+use crate::ops::{OperationState, Timed};
+use crate::{
+    addon_dir, addons, clone_install, git_addon_dir, git_output, giturl, load_settings, notices, notify,
+    refuse_unpushed, validate, SwitchError,
+};
+
+/// `[repo#]branch[@commit]`. The repo defaults to the official one and the commit to the
+/// branch tip. Repos are https or SSH URLs, or GitHub "owner/name" shorthand.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstallSpec {
+    pub repo: String,
+    pub branch: String,
+    pub commit: Option<String>,
+}
+
+impl InstallSpec {
+    pub fn parse(addon: &Addon, spec: &str) -> Result<InstallSpec, String> {
+        let spec = spec.trim();
+        if spec.is_empty() {
+            return Err("Empty install spec".to_string());
+        }
+        let (repo, rest) = match spec.rsplit_once('#') {
+            Some((repo, rest)) => (parse_repo(repo)?, rest),
+            None => (addon.url(), spec),
+        };
+        // Branch names may contain '@', so only a trailing "@<hex>" counts as the commit
+        let (branch, commit) = match rest.rsplit_once('@') {
+            Some((branch, commit)) => {
+                if !is_commit(commit) {
+                    return Err(format!("Invalid commit '{}' in spec: expected 7-40 hex characters", commit));
+                }
+                (branch, Some(commit.to_ascii_lowercase()))
+            }
+            None => (rest, None),
+        };
+        validate::branch_name(branch).map_err(|e| format!("Invalid branch segment in spec: {}", e))?;
+        Ok(InstallSpec { repo, branch: branch.to_string(), commit })
+    }
+}
+
+impl fmt::Display for InstallSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}#{}", self.repo, self.branch)?;
+        if let Some(commit) = &self.commit {
+            write!(f, "@{}", commit)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn parse_repo(repo: &str) -> Result<String, String> {
+    let invalid = || format!("Invalid repo '{}' in spec: expected an https or SSH URL, or owner/name", repo);
+    // Full URLs are the user's choice of protocol and used as given
+    if let Some(scheme) = ["https://", "ssh://", "git+ssh://"].iter().find(|s| repo.starts_with(*s)) {
+        if repo.len() <= scheme.len() || repo.contains(char::is_whitespace) {
+            return Err(invalid());
+        }
+        return Ok(repo.to_string());
+    }
+    // scp-style "git@github.com:owner/name.git"
+    if let Some((user_host, path)) = repo.split_once(':') {
+        let (user, host) = user_host.split_once('@').ok_or_else(invalid)?;
+        if user.is_empty()
+            || host.is_empty()
+            || path.is_empty()
+            || repo.starts_with('-')
+            || repo.contains(char::is_whitespace)
+        {
+            return Err(invalid());
+        }
+        return Ok(repo.to_string());
+    }
+    let parts: Vec<&str> = repo.split('/').collect();
+    let part_ok = |p: &str| {
+        !p.is_empty() && !p.starts_with('.') && p.chars().all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c))
+    };
+    match parts.as_slice() {
+        [owner, name] if part_ok(owner) && part_ok(name) => {
+            Ok(giturl::preferred(&format!("https://github.com/{}/{}.git", owner, name.trim_end_matches(".git"))))
+        }
+        _ => Err(invalid()),
+    }
+}
+
+fn is_commit(commit: &str) -> bool {
+    (7..=40).contains(&commit.len()) && commit.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// The spec that reproduces the install as it is now
+#[tauri::command]
+pub fn get_install_spec(blender_version: String, addon_id: Option<String>) -> Result<String, String> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    let addon_path = git_addon_dir(&addon, &blender_version)?;
+    let branch = git_output(&addon_path, &["rev-parse", "--abbrev-ref", "HEAD"])?;
+    if branch == "HEAD" {
+        return Err("HEAD is detached - attach to a branch before sharing a spec".to_string());
+    }
+    let repo = git_output(&addon_path, &["remote", "get-url", "origin"]).unwrap_or_else(|_| addon.url());
+    let commit = git_output(&addon_path, &["rev-parse", "--short=12", "HEAD"])?;
+    Ok(InstallSpec { repo, branch, commit: Some(commit) }.to_string())
+}
+
+/// Install exactly what `spec` describes. The commit is checked against the branch's
+/// history in the fresh clone before anything replaces the current install, and
+/// unpushed commits in that install stop it unless `force` is set.
+#[tauri::command]
+pub fn apply_install_spec(
+    blender_version: String,
+    spec: String,
+    force: Option<bool>,
+    addon_id: Option<String>,
+    app: AppHandle,
+    ops: State<'_, OperationState>,
+) -> Result<Timed<bool>, SwitchError> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    let parsed = InstallSpec::parse(&addon, &spec)?;
+    let guard = ops.begin("apply_install_spec", &blender_version)?;
+    let progress = guard.progress();
+    progress.phase("validating");
+    let refused = addon_dir(&addon, &blender_version)
+        .map_err(SwitchError::from)
+        .and_then(|dir| refuse_unpushed(&dir, force.unwrap_or(false)));
+    if let Err(error) = refused {
+        guard.finish::<()>(&Err(error.message.clone()));
+        return Err(error);
+    }
+
+    let heads = git_output(&std::env::temp_dir(), &["ls-remote", "--heads", "--", &parsed.repo, &parsed.branch]);
+    let result = match heads {
+        Err(e) => Err(format!("Couldn't reach {}: {}", parsed.repo, e)),
+        Ok(heads) if heads.is_empty() => Err(format!("Branch '{}' doesn't exist on {}", parsed.branch, parsed.repo)),
+        Ok(_) => {
+            let depth = load_settings().map(|s| s.clone_depth).unwrap_or(Some(1));
+            clone_install(
+                &addon,
+                &parsed.repo,
+                depth,
+                &parsed.branch,
+                parsed.commit.as_deref(),
+                &blender_version,
+                &progress,
+            )
+        }
+    };
+    let result = guard.finish_timed(result.map(|_| true));
+    let summary = result.as_ref().map(|_| format!("Installed {}", parsed));
+    notify::operation_finished(&app, "Install", &summary.map_err(Clone::clone));
+    notices::refresh(&addon, &app, &blender_version);
+    Ok(result?)
+}
@@ -0,0 +1,135 @@
+// Managing stashed edits in the installed repo
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::State;
+
+use crate::addons::Addon;
+use crate::ops::OperationState;
+use crate::{addons, git_addon_dir, git_output, gitbin};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StashEntry {
+    pub index: u32,
+    /// "stash@{0}"
+    #[serde(rename = "ref")]
+    pub stash_ref: String,
+    /// Branch the stash was made on
+    pub branch: Option<String>,
+    pub message: String,
+    pub date: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StashApplyResult {
+    /// Applied cleanly; when false, `conflicts` lists the files needing attention
+    pub clean: bool,
+    pub conflicts: Vec<String>,
+}
+
+#[tauri::command]
+pub fn list_stashes(blender_version: String, addon_id: Option<String>) -> Result<Vec<StashEntry>, String> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    let addon_path = git_addon_dir(&addon, &blender_version)?;
+    stash_entries(&addon_path)
+}
+
+fn stash_entries(addon_path: &Path) -> Result<Vec<StashEntry>, String> {
+    let output = git_output(addon_path, &["stash", "list", "--format=%gd%x1f%gs%x1f%cI"])?;
+    Ok(output
+        .lines()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let mut fields = line.split('\x1f');
+            let stash_ref = fields.next()?.to_string();
+            let (branch, message) = parse_subject(fields.next()?);
+            Some(StashEntry {
+                index: i as u32,
+                stash_ref,
+                branch,
+                message,
+                date: fields.next().unwrap_or_default().to_string(),
+            })
+        })
+        .collect())
+}
+
+/// "On main: my message" or "WIP on main: ab12cd3 last commit subject"
+fn parse_subject(subject: &str) -> (Option<String>, String) {
+    let rest = subject.strip_prefix("WIP on ").or_else(|| subject.strip_prefix("On "));
+    match rest.and_then(|r| r.split_once(": ")) {
+        Some((branch, message)) => (Some(branch.to_string()), message.to_string()),
+        None => (None, subject.to_string()),
+    }
+}
+
+fn stash_ref(addon_path: &Path, index: u32) -> Result<String, String> {
+    let count = stash_entries(addon_path)?.len() as u32;
+    if index >= count {
+        return Err(format!("No stash at index {} ({} stash{})", index, count, if count == 1 { "" } else { "es" }));
+    }
+    Ok(format!("stash@{{{}}}", index))
+}
+
+/// Apply a stash and keep it. Conflicts are reported rather than treated as an error,
+/// since the changes have been applied with markers the user needs to resolve.
+#[tauri::command]
+pub fn apply_stash(
+    index: u32,
+    blender_version: String,
+    addon_id: Option<String>,
+    ops: State<'_, OperationState>,
+) -> Result<StashApplyResult, String> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    let guard = ops.begin("apply_stash", &blender_version)?;
+    let result = apply(&addon, index, &blender_version);
+    guard.finish(&result);
+    result
+}
+
+fn apply(addon: &Addon, index: u32, blender_version: &str) -> Result<StashApplyResult, String> {
+    let addon_path = git_addon_dir(addon, blender_version)?;
+    let stash = stash_ref(&addon_path, index)?;
+
+    let output = gitbin::git_command()
+        .args(["stash", "apply", &stash])
+        .current_dir(&addon_path)
+        .output()
+        .map_err(|e| gitbin::run_error(&e))?;
+    if output.status.success() {
+        return Ok(StashApplyResult { clean: true, conflicts: Vec::new() });
+    }
+
+    let conflicts: Vec<String> = git_output(&addon_path, &["diff", "--name-only", "--diff-filter=U"])
+        .unwrap_or_default()
+        .lines()
+        .map(str::to_string)
+        .collect();
+    if conflicts.is_empty() {
+        // Refused outright, e.g. local edits to the same files - nothing was changed
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Failed to apply stash: {}", stderr.trim()));
+    }
+    Ok(StashApplyResult { clean: false, conflicts })
+}
+
+#[tauri::command]
+pub fn drop_stash(
+    index: u32,
+    blender_version: String,
+    addon_id: Option<String>,
+    ops: State<'_, OperationState>,
+) -> Result<bool, String> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    let guard = ops.begin("drop_stash", &blender_version)?;
+    let result = drop_entry(&addon, index, &blender_version);
+    guard.finish(&result);
+    result
+}
+
+fn drop_entry(addon: &Addon, index: u32, blender_version: &str) -> Result<bool, String> {
+    let addon_path = git_addon_dir(addon, blender_version)?;
+    let stash = stash_ref(&addon_path, index)?;
+    git_output(&addon_path, &["stash", "drop", &stash]).map_err(|e| format!("Failed to drop stash: {}", e))?;
+    Ok(true)
+}
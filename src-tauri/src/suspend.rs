@@ -0,0 +1,157 @@
+// Freezing and thawing a running git command, for pausing a long clone or pull.
+//
+// git does the network work in helper processes (git-remote-https, index-pack), so
+// suspending only the git we spawned would leave the transfer running. On Unix the
+// command gets its own process group and the whole group is stopped; on Windows every
+// process descended from it is suspended.
+
+use std::process::Command;
+
+/// Put `command` where `suspend` can reach all of its helpers
+pub fn prepare(command: &mut Command) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    #[cfg(not(unix))]
+    let _ = command;
+}
+
+#[cfg(unix)]
+fn signal_group(pid: u32, signal: &str) -> Result<(), String> {
+    // A negative pid addresses the process group `prepare` created
+    let output = Command::new("kill")
+        .arg(format!("-{}", signal))
+        .arg("--")
+        .arg(format!("-{}", pid))
+        .output()
+        .map_err(|e| format!("Failed to run kill: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!("Failed to signal git: {}", String::from_utf8_lossy(&output.stderr).trim()))
+    }
+}
+
+#[cfg(unix)]
+pub fn suspend(pid: u32) -> Result<(), String> {
+    signal_group(pid, "STOP")
+}
+
+#[cfg(unix)]
+pub fn resume(pid: u32) -> Result<(), String> {
+    signal_group(pid, "CONT")
+}
+
+#[cfg(windows)]
+pub fn suspend(pid: u32) -> Result<(), String> {
+    windows::for_tree(pid, windows::suspend_process)
+}
+
+#[cfg(windows)]
+pub fn resume(pid: u32) -> Result<(), String> {
+    windows::for_tree(pid, windows::resume_process)
+}
+
+#[cfg(windows)]
+mod windows {
+    use std::ffi::c_void;
+
+    type Handle = *mut c_void;
+
+    const TH32CS_SNAPPROCESS: u32 = 0x2;
+    const PROCESS_SUSPEND_RESUME: u32 = 0x0800;
+    const INVALID_HANDLE_VALUE: Handle = -1isize as Handle;
+
+    #[repr(C)]
+    struct ProcessEntry32W {
+        dw_size: u32,
+        cnt_usage: u32,
+        th32_process_id: u32,
+        th32_default_heap_id: usize,
+        th32_module_id: u32,
+        cnt_threads: u32,
+        th32_parent_process_id: u32,
+        pc_pri_class_base: i32,
+        dw_flags: u32,
+        sz_exe_file: [u16; 260],
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn CreateToolhelp32Snapshot(flags: u32, process_id: u32) -> Handle;
+        fn Process32FirstW(snapshot: Handle, entry: *mut ProcessEntry32W) -> i32;
+        fn Process32NextW(snapshot: Handle, entry: *mut ProcessEntry32W) -> i32;
+        fn OpenProcess(access: u32, inherit: i32, process_id: u32) -> Handle;
+        fn CloseHandle(handle: Handle) -> i32;
+    }
+
+    // Undocumented but stable since XP; what Process Explorer and Resource Monitor use
+    #[link(name = "ntdll")]
+    extern "system" {
+        fn NtSuspendProcess(process: Handle) -> i32;
+        fn NtResumeProcess(process: Handle) -> i32;
+    }
+
+    /// (pid, parent pid) for every process on the system
+    fn process_list() -> Result<Vec<(u32, u32)>, String> {
+        let mut processes = Vec::new();
+        unsafe {
+            let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0);
+            if snapshot == INVALID_HANDLE_VALUE {
+                return Err("Failed to list processes".to_string());
+            }
+            let mut entry: ProcessEntry32W = std::mem::zeroed();
+            entry.dw_size = std::mem::size_of::<ProcessEntry32W>() as u32;
+            let mut more = Process32FirstW(snapshot, &mut entry) != 0;
+            while more {
+                processes.push((entry.th32_process_id, entry.th32_parent_process_id));
+                more = Process32NextW(snapshot, &mut entry) != 0;
+            }
+            CloseHandle(snapshot);
+        }
+        Ok(processes)
+    }
+
+    /// Apply `f` to `root` and everything it started, parents first
+    pub fn for_tree(root: u32, f: fn(u32) -> Result<(), String>) -> Result<(), String> {
+        let processes = process_list()?;
+        let mut tree = vec![root];
+        let mut i = 0;
+        while i < tree.len() {
+            let parent = tree[i];
+            let children: Vec<u32> = processes
+                .iter()
+                .filter(|(pid, ppid)| *ppid == parent && !tree.contains(pid))
+                .map(|(pid, _)| *pid)
+                .collect();
+            tree.extend(children);
+            i += 1;
+        }
+        tree.into_iter().try_for_each(f)
+    }
+
+    fn with_process(pid: u32, f: unsafe extern "system" fn(Handle) -> i32) -> Result<(), String> {
+        unsafe {
+            let process = OpenProcess(PROCESS_SUSPEND_RESUME, 0, pid);
+            if process.is_null() {
+                return Err(format!("Failed to open git process {}", pid));
+            }
+            let status = f(process);
+            CloseHandle(process);
+            if status < 0 {
+                return Err(format!("Windows refused to suspend/resume process {} (0x{:08x})", pid, status));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn suspend_process(pid: u32) -> Result<(), String> {
+        with_process(pid, NtSuspendProcess)
+    }
+
+    pub fn resume_process(pid: u32) -> Result<(), String> {
+        with_process(pid, NtResumeProcess)
+    }
+}
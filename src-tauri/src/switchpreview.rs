@@ -0,0 +1,196 @@
+// What switching would change, for the confirmation dialog: the files that differ
+// between the installed commit and the target branch, and which of them hold local edits
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::State;
+
+use crate::addons::Addon;
+use crate::marker::{detect_origin, InstallOrigin};
+use crate::ops::{OperationState, Progress};
+use crate::{addon_dir, addons, divergence, git_output, remotes, validate, verify};
+
+/// Where the target tip is fetched to in the install; removed again afterwards so it
+/// never looks like a branch
+const PREVIEW_REF: &str = "refs/serpens/preview";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FileChange {
+    /// "added", "modified", "deleted", "renamed", "copied" or "type changed"
+    pub status: String,
+    pub path: String,
+    /// Where a renamed or copied file came from
+    #[serde(rename = "oldPath")]
+    pub old_path: Option<String>,
+    /// The file has local edits in the install, which the switch overwrites
+    #[serde(rename = "locallyModified")]
+    pub locally_modified: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SwitchPreview {
+    pub branch: String,
+    /// False when there is nothing to diff against and the install is replaced in full
+    pub computable: bool,
+    /// Why it couldn't be computed
+    pub reason: Option<String>,
+    /// The installed commit
+    pub installed: Option<String>,
+    /// The target branch's tip
+    pub target: Option<String>,
+    /// Commits on the target that the install doesn't have
+    pub ahead: Option<u32>,
+    /// Commits in the install that the target doesn't have
+    pub behind: Option<u32>,
+    pub files: Vec<FileChange>,
+    /// Tracked files edited or deleted in the install, whether the switch touches them or
+    /// not - a switch is a fresh clone, so all of them are lost
+    #[serde(rename = "localChanges")]
+    pub local_changes: Vec<String>,
+}
+
+impl SwitchPreview {
+    fn full_replacement(branch: String, reason: &str) -> SwitchPreview {
+        SwitchPreview {
+            branch,
+            computable: false,
+            reason: Some(reason.to_string()),
+            installed: None,
+            target: None,
+            ahead: None,
+            behind: None,
+            files: Vec::new(),
+            local_changes: Vec::new(),
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn preview_switch(
+    blender_version: String,
+    branch: String,
+    remote: Option<String>,
+    addon_id: Option<String>,
+    ops: State<'_, OperationState>,
+) -> Result<SwitchPreview, String> {
+    validate::blender_version(&blender_version)?;
+    validate::branch_name(&branch)?;
+    let addon = addons::resolve(addon_id.as_deref())?;
+    let url = remotes::remote_url(&addon, remote.as_deref())?;
+    let guard = ops.begin("preview_switch", &blender_version)?;
+    tokio::task::spawn_blocking(move || {
+        let result = preview(&addon, &blender_version, branch, &url, &guard.progress());
+        guard.finish(&result);
+        result
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+fn preview(
+    addon: &Addon,
+    blender_version: &str,
+    branch: String,
+    url: &str,
+    progress: &Progress,
+) -> Result<SwitchPreview, String> {
+    let addon_path = addon_dir(addon, blender_version)?;
+    let reason = match detect_origin(&addon_path) {
+        None => Some("Nothing is installed yet - the branch is installed fresh"),
+        Some(InstallOrigin::Archive) => {
+            Some("The install has no git history to compare against, so every file is replaced")
+        }
+        _ if !addon_path.join(".git").exists() => {
+            Some("The linked folder isn't a git clone, so every file is replaced")
+        }
+        _ => None,
+    };
+    if let Some(reason) = reason {
+        return Ok(SwitchPreview::full_replacement(branch, reason));
+    }
+    let Ok(head) = git_output(&addon_path, &["rev-parse", "HEAD"]) else {
+        return Ok(SwitchPreview::full_replacement(branch, "The install's git repository has no commit checked out"));
+    };
+
+    progress.phase("fetching");
+    let shallow = git_output(&addon_path, &["rev-parse", "--is-shallow-repository"]).is_ok_and(|s| s == "true");
+    let refspec = format!("+refs/heads/{}:{}", branch, PREVIEW_REF);
+    // A shallow install only needs the tip's files; its history is counted in the cache repo
+    let mut fetch = vec!["fetch", "--quiet", "--no-tags"];
+    if shallow {
+        fetch.extend(["--depth", "1"]);
+    }
+    fetch.extend(["--", url, refspec.as_str()]);
+    git_output(&addon_path, &fetch).map_err(|e| format!("Couldn't fetch {}: {}", branch, e))?;
+    let compared = compare(&addon_path, &head, url, shallow);
+    git_output(&addon_path, &["update-ref", "-d", PREVIEW_REF]).ok();
+    let (target, files, (ahead, behind)) = compared?;
+
+    progress.phase("checking local edits");
+    let local = verify::verify_git(&addon_path)?;
+    let mut local_changes: Vec<String> = local.modified.into_iter().chain(local.missing).collect();
+    local_changes.sort();
+    let files = files
+        .into_iter()
+        .map(|change| FileChange {
+            locally_modified: local_changes.iter().any(|p| *p == change.path || Some(p) == change.old_path.as_ref()),
+            ..change
+        })
+        .collect();
+
+    Ok(SwitchPreview {
+        branch,
+        computable: true,
+        reason: None,
+        installed: Some(head),
+        target: Some(target),
+        ahead,
+        behind,
+        files,
+        local_changes,
+    })
+}
+
+type Comparison = (String, Vec<FileChange>, (Option<u32>, Option<u32>));
+
+fn compare(addon_path: &Path, head: &str, url: &str, shallow: bool) -> Result<Comparison, String> {
+    let target = git_output(addon_path, &["rev-parse", PREVIEW_REF])?;
+    let listing = git_output(addon_path, &["diff", "--name-status", "-z", "-M", head, &target])?;
+    let mut files = Vec::new();
+    let mut fields = listing.split('\0').filter(|f| !f.is_empty());
+    while let Some(code) = fields.next() {
+        let two_paths = code.starts_with('R') || code.starts_with('C');
+        let old_path = if two_paths { fields.next().map(str::to_string) } else { None };
+        let Some(path) = fields.next() else { break };
+        let status = match &code[..1] {
+            "A" => "added",
+            "D" => "deleted",
+            "R" => "renamed",
+            "C" => "copied",
+            "T" => "type changed",
+            _ => "modified",
+        };
+        files.push(FileChange {
+            status: status.to_string(),
+            path: path.to_string(),
+            old_path,
+            locally_modified: false,
+        });
+    }
+
+    let counts = if shallow {
+        divergence::via_cache_repo(url, head, &[&target])
+            .into_iter()
+            .next()
+            .map(|(_, counts)| counts)
+    } else {
+        let range = format!("{}...{}", target, head);
+        git_output(addon_path, &["rev-list", "--left-right", "--count", &range])
+            .ok()
+            .and_then(|counts| {
+                let mut parts = counts.split_whitespace().map(str::parse::<u32>);
+                Some((parts.next()?.ok()?, parts.next()?.ok()?))
+            })
+    };
+    Ok((target, files, (counts.map(|c| c.0), counts.map(|c| c.1))))
+}
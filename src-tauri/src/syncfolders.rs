@@ -0,0 +1,178 @@
+// Addons folders inside OneDrive, Dropbox or Google Drive: the sync client holds files
+// open while it uploads them, so deletes and copies there need patience
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use crate::{addons_dir, validate};
+
+/// Attempts for a file operation under a sync root, and the wait before the first retry
+/// (doubling each time, so about six seconds in all)
+const SYNC_ATTEMPTS: u32 = 7;
+const FIRST_WAIT: Duration = Duration::from_millis(100);
+
+/// Windows' ERROR_ACCESS_DENIED, ERROR_SHARING_VIOLATION and ERROR_LOCK_VIOLATION -
+/// what a file the sync client has open comes back as
+const LOCK_ERRORS: &[i32] = &[5, 32, 33];
+
+/// The ERROR_CLOUD_FILE_* codes that clear up by themselves: provider not running yet,
+/// not in sync, out of resources, network unavailable, unsuccessful, in use, request
+/// aborted, property lock conflict, request cancelled
+const CLOUD_TRANSIENT: &[i32] = &[362, 377, 387, 388, 389, 391, 393, 397, 398];
+
+/// What the cloud errors mean for the user
+const CLOUD_MESSAGES: &[(i32, &str)] = &[
+    (362, "the cloud sync client isn't running - start OneDrive and try again"),
+    (377, "the file isn't in sync with the cloud yet"),
+    (386, "the cloud sync client needs you to sign in again"),
+    (388, "the file is only in the cloud and the network is unavailable"),
+    (391, "the cloud sync client is using the file"),
+    (392, "the file is pinned (\"Always keep on this device\") - unpin it first"),
+    (395, "the cloud sync client denied access"),
+];
+
+/// FILE_ATTRIBUTE_OFFLINE, FILE_ATTRIBUTE_RECALL_ON_OPEN and
+/// FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS - a placeholder whose contents are in the cloud
+#[cfg(windows)]
+const PLACEHOLDER_ATTRIBUTES: u32 = 0x1000 | 0x40000 | 0x400000;
+
+/// Folder names the clients create when the environment doesn't say where they sync
+const ROOT_NAMES: &[(&str, &str)] = &[
+    ("OneDrive", "OneDrive"),
+    ("Dropbox", "Dropbox"),
+    ("Google Drive", "Google Drive"),
+    ("My Drive", "Google Drive"),
+    ("GoogleDrive", "Google Drive"),
+];
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SyncFolder {
+    /// "OneDrive", "Dropbox" or "Google Drive"
+    pub provider: String,
+    /// The synced folder the path is inside
+    pub root: String,
+    pub path: String,
+}
+
+/// The sync root `path` is under, if any
+pub fn sync_root(path: &Path) -> Option<SyncFolder> {
+    // The addons folder may not exist yet; where it would be created is what counts
+    let path = path
+        .ancestors()
+        .find(|p| p.exists())
+        .and_then(|p| fs::canonicalize(p).ok())
+        .unwrap_or_else(|| path.to_path_buf());
+    let lower = path.to_string_lossy().to_lowercase();
+    let known = configured_roots();
+    if let Some((provider, root)) = known.iter().find(|(_, root)| {
+        let root = root.to_string_lossy().to_lowercase();
+        !root.is_empty() && lower.starts_with(root.trim_end_matches(['\\', '/']))
+    }) {
+        return Some(SyncFolder {
+            provider: provider.to_string(),
+            root: root.to_string_lossy().to_string(),
+            path: path.to_string_lossy().to_string(),
+        });
+    }
+    // "OneDrive - Contoso" is how business accounts are named
+    path.ancestors().find_map(|dir| {
+        let name = dir.file_name()?.to_string_lossy().to_string();
+        let (_, provider) = ROOT_NAMES
+            .iter()
+            .find(|(folder, _)| name == *folder || name.starts_with(&format!("{} - ", folder)))?;
+        Some(SyncFolder {
+            provider: provider.to_string(),
+            root: dir.to_string_lossy().to_string(),
+            path: path.to_string_lossy().to_string(),
+        })
+    })
+}
+
+/// Where OneDrive says it syncs, from the variables it sets for every process
+fn configured_roots() -> Vec<(&'static str, PathBuf)> {
+    ["OneDrive", "OneDriveConsumer", "OneDriveCommercial"]
+        .iter()
+        .filter_map(std::env::var_os)
+        .filter(|value| !value.is_empty())
+        .map(|value| ("OneDrive", PathBuf::from(value)))
+        .collect()
+}
+
+/// Whether the addons folder for `blender_version` is inside a synced folder
+#[tauri::command]
+pub fn check_sync_folder(blender_version: String) -> Result<Option<SyncFolder>, String> {
+    validate::blender_version(&blender_version)?;
+    Ok(sync_root(&addons_dir(&blender_version)?))
+}
+
+/// The error a sync client holding the file open produces
+fn is_lock_error(e: &io::Error) -> bool {
+    e.raw_os_error().is_some_and(|code| LOCK_ERRORS.contains(&code)) || e.kind() == io::ErrorKind::PermissionDenied
+}
+
+/// Held open by another process, locally or by the cloud provider - worth retrying
+pub fn is_in_use(e: &io::Error) -> bool {
+    is_lock_error(e) || is_transient_cloud_error(e)
+}
+
+/// A cloud provider error worth waiting out
+fn is_transient_cloud_error(e: &io::Error) -> bool {
+    e.raw_os_error().is_some_and(|code| CLOUD_TRANSIENT.contains(&code))
+}
+
+/// The user's wording for a cloud provider error, None for anything else
+pub fn describe_cloud_error(e: &io::Error) -> Option<&'static str> {
+    let code = e.raw_os_error()?;
+    CLOUD_MESSAGES
+        .iter()
+        .find(|(c, _)| *c == code)
+        .map(|(_, message)| *message)
+        .or_else(|| (362..=398).contains(&code).then_some("the cloud sync client refused the operation"))
+}
+
+/// A file whose contents live in the cloud and are downloaded when it's read
+#[cfg(windows)]
+pub fn is_placeholder(path: &Path) -> bool {
+    use std::os::windows::fs::MetadataExt;
+    fs::symlink_metadata(path).is_ok_and(|meta| meta.file_attributes() & PLACEHOLDER_ATTRIBUTES != 0)
+}
+
+#[cfg(not(windows))]
+pub fn is_placeholder(_path: &Path) -> bool {
+    false
+}
+
+/// Run `op` on something at `path`, retrying with backoff while the file is locked - but
+/// only inside a sync root, where that lock is expected to clear by itself - or while
+/// the cloud provider reports a passing problem
+pub fn retry_locked<T>(path: &Path, mut op: impl FnMut() -> io::Result<T>) -> io::Result<T> {
+    // Only looked up once something fails, since this runs for every file copied
+    let mut synced = None;
+    let mut wait = FIRST_WAIT;
+    for _ in 1..SYNC_ATTEMPTS {
+        match op() {
+            Err(e)
+                if is_transient_cloud_error(&e)
+                    || (is_lock_error(&e) && *synced.get_or_insert_with(|| sync_root(path).is_some())) =>
+            {
+                thread::sleep(wait);
+                wait *= 2;
+            }
+            result => return result,
+        }
+    }
+    op()
+}
+
+/// Attempts `remove_dir_robust` makes per path: more when a sync client may be holding files
+pub fn delete_attempts(path: &Path) -> u64 {
+    if sync_root(path).is_some() {
+        SYNC_ATTEMPTS as u64
+    } else {
+        5
+    }
+}
@@ -0,0 +1,96 @@
+// Staging folders a crashed or killed install left in the addons and extensions dirs,
+// where Blender would otherwise try to load them as add-ons
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::{applog, blender_config_root, dir_size, remove_dir_robust, userdirs, STAGING_PREFIX};
+
+/// Younger than this and the folder may belong to an install still running
+const MIN_AGE: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct TempCleanup {
+    pub removed: Vec<String>,
+    #[serde(rename = "freedBytes")]
+    pub freed_bytes: u64,
+    /// Folders that matched but couldn't be deleted, with why
+    pub failed: Vec<String>,
+}
+
+/// Delete leftover staging folders for every Blender version
+#[tauri::command]
+pub async fn cleanup_temp_dirs() -> Result<TempCleanup, String> {
+    tokio::task::spawn_blocking(clean_all).await.map_err(|e| format!("Task failed: {}", e))?
+}
+
+pub fn clean_all() -> Result<TempCleanup, String> {
+    let mut cleanup = TempCleanup::default();
+    for dir in install_dirs()? {
+        clean_dir(&dir, &mut cleanup);
+    }
+    if !cleanup.removed.is_empty() {
+        applog::info(&format!(
+            "Removed {} leftover temp folder{} ({} bytes)",
+            cleanup.removed.len(),
+            if cleanup.removed.len() == 1 { "" } else { "s" },
+            cleanup.freed_bytes
+        ));
+    }
+    Ok(cleanup)
+}
+
+/// Everywhere an install is staged: a moved addons folder, and each version's scripts/addons
+/// and extension repos
+fn install_dirs() -> Result<Vec<PathBuf>, String> {
+    let mut dirs = userdirs::overridden_addons_dirs();
+    let Ok(versions) = fs::read_dir(blender_config_root()?) else { return Ok(dirs) };
+    for version in versions.flatten().map(|e| e.path()).filter(|p| p.is_dir()) {
+        dirs.push(version.join("scripts").join("addons"));
+        if let Ok(repos) = fs::read_dir(version.join("extensions")) {
+            dirs.extend(repos.flatten().map(|e| e.path()).filter(|p| p.is_dir()));
+        }
+    }
+    Ok(dirs)
+}
+
+/// Sweep one folder before staging a new install in it
+pub fn clean_dir(dir: &Path, cleanup: &mut TempCleanup) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        let old_enough = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .is_some_and(|age| age >= MIN_AGE);
+        if !is_staging_name(&name) || !old_enough || !entry.file_type().is_ok_and(|t| t.is_dir()) {
+            continue;
+        }
+        let size = dir_size(&path);
+        match remove_dir_robust(&path) {
+            Ok(()) => {
+                applog::info(&format!("Removed leftover temp folder {}", path.display()));
+                cleanup.freed_bytes += size;
+                cleanup.removed.push(path.to_string_lossy().to_string());
+            }
+            Err(e) => cleanup.failed.push(e),
+        }
+    }
+}
+
+/// Exactly what `StagingDir` creates: ".serpens_tmp_<tag>_<nanos>", plus "_old" for the
+/// install swapped out of the way
+fn is_staging_name(name: &str) -> bool {
+    let Some(rest) = name.strip_prefix(STAGING_PREFIX) else { return false };
+    let rest = rest.strip_suffix("_old").unwrap_or(rest);
+    let Some((tag, stamp)) = rest.rsplit_once('_') else { return false };
+    !tag.is_empty()
+        && tag.chars().all(|c| c.is_ascii_lowercase())
+        && !stamp.is_empty()
+        && stamp.chars().all(|c| c.is_ascii_digit())
+}
@@ -0,0 +1,35 @@
+// Fixtures the tests share: scratch folders and small git repositories to run against
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::git_output;
+
+/// An empty folder under the system temp dir for one test, named after its module and
+/// the test so tests running side by side don't meet
+pub fn scratch(module: &str, name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("serpens-{}-{}-{}", module, name, std::process::id()));
+    fs::remove_dir_all(&dir).ok();
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Run git in `dir`, failing the test if it fails
+pub fn git(dir: &Path, args: &[&str]) {
+    git_output(dir, args).unwrap_or_else(|e| panic!("git {}: {}", args.join(" "), e));
+}
+
+/// Write `message` into `file` and commit it, with an identity that doesn't depend on
+/// the machine's git config
+pub fn commit(repo: &Path, file: &str, message: &str) {
+    fs::write(repo.join(file), message).unwrap();
+    git(repo, &["add", file]);
+    git(repo, &["-c", "user.name=Test", "-c", "user.email=test@example.com", "commit", "-q", "-m", message]);
+}
+
+/// An empty bare repository at `dir/remote.git` whose default branch is `main`
+pub fn bare_remote(dir: &Path) -> PathBuf {
+    let remote = dir.join("remote.git");
+    git(dir, &["init", "-q", "--bare", "-b", "main", &remote.to_string_lossy()]);
+    remote
+}
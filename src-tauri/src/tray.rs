@@ -0,0 +1,140 @@
+// System tray icon - status at a glance plus the everyday actions
+
+use std::thread;
+use tauri::image::Image;
+use tauri::menu::{Menu, MenuItem, PredefinedMenuItem};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Manager, Wry};
+
+use crate::addons::Addon;
+use crate::ops::OperationState;
+use crate::updates::{allow_background, run_update_check, BackgroundGate, UpdateState};
+use crate::{installation_status, load_settings, open_folder, pull_latest};
+
+const TRAY_ID: &str = "main";
+
+/// Menu items whose text changes with the update state
+pub struct TrayMenu {
+    pull: MenuItem<Wry>,
+}
+
+pub fn create(app: &AppHandle) -> tauri::Result<()> {
+    let check = MenuItem::with_id(app, "check", "Check for updates", true, None::<&str>)?;
+    let pull = MenuItem::with_id(app, "pull", "Pull latest", true, None::<&str>)?;
+    let open = MenuItem::with_id(app, "open", "Open Blender addons folder", true, None::<&str>)?;
+    let show = MenuItem::with_id(app, "show", "Show window", true, None::<&str>)?;
+    let quit = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
+    let separator = PredefinedMenuItem::separator(app)?;
+    let menu = Menu::with_items(app, &[&check, &pull, &open, &separator, &show, &quit])?;
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("Serpens Dev Manager")
+        .menu(&menu)
+        .show_menu_on_left_click(false)
+        .on_menu_event(|app, event| handle_menu(app, event.id.as_ref()))
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click { button: MouseButton::Left, button_state: MouseButtonState::Up, .. } = event {
+                show_window(tray.app_handle());
+            }
+        });
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+
+    app.manage(TrayMenu { pull });
+    refresh(app);
+    Ok(())
+}
+
+fn handle_menu(app: &AppHandle, id: &str) {
+    allow_background(app);
+    let app = app.clone();
+    let version = load_settings().map(|s| s.blender_version).unwrap_or_else(|_| "5.0".to_string());
+    match id {
+        "check" => {
+            thread::spawn(move || {
+                run_update_check(&Addon::builtin(), &app, &version).ok();
+            });
+        }
+        "pull" => {
+            thread::spawn(move || {
+                if pull_latest(version.clone(), None, app.clone(), app.state::<OperationState>()).is_ok() {
+                    run_update_check(&Addon::builtin(), &app, &version).ok();
+                }
+            });
+        }
+        "open" => {
+            open_folder(version).ok();
+        }
+        "show" => show_window(&app),
+        "quit" => app.exit(0),
+        _ => {}
+    }
+}
+
+pub fn show_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        window.show().ok();
+        window.unminimize().ok();
+        window.set_focus().ok();
+    }
+    crate::window_state::changed(app);
+}
+
+/// Bring tooltip, icon and menu text in line with the last known update status.
+pub fn refresh(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else { return };
+    let status = app
+        .try_state::<UpdateState>()
+        .and_then(|state| state.0.lock().ok().and_then(|s| s.clone()));
+    let pending = status.as_ref().map(|s| s.pending).unwrap_or(0);
+
+    // Reading the install runs git, which waits until background work is allowed
+    let quiet = !app.try_state::<BackgroundGate>().is_some_and(|gate| gate.is_open());
+    let branch = status.as_ref().map(|s| s.branch.clone()).or_else(|| {
+        if quiet {
+            return None;
+        }
+        let version = load_settings().ok()?.blender_version;
+        installation_status(&Addon::builtin(), version).ok()?.branch
+    });
+    let state_line = match &status {
+        Some(_) if pending > 0 => format!("{} update{} available", pending, if pending == 1 { "" } else { "s" }),
+        Some(_) => "Up to date".to_string(),
+        None => "Not checked yet".to_string(),
+    };
+    let branch_line = match branch {
+        Some(b) => format!("Branch: {}", b),
+        None if quiet => "Branch not read yet".to_string(),
+        None => "Not installed".to_string(),
+    };
+    let tooltip = format!("Serpens Dev Manager\n{}\n{}", branch_line, state_line);
+    tray.set_tooltip(Some(tooltip)).ok();
+
+    if let Some(icon) = app.default_window_icon() {
+        let icon = if pending > 0 { with_badge(icon) } else { icon.clone() };
+        tray.set_icon(Some(icon)).ok();
+    }
+    if let Some(menu) = app.try_state::<TrayMenu>() {
+        let text = if pending > 0 { format!("Pull latest ({} pending)", pending) } else { "Pull latest".to_string() };
+        menu.pull.set_text(text).ok();
+    }
+}
+
+/// The app icon with an orange dot in the top-right corner
+fn with_badge(icon: &Image<'_>) -> Image<'static> {
+    let (width, height) = (icon.width(), icon.height());
+    let mut rgba = icon.rgba().to_vec();
+    let radius = (width.min(height) / 4) as i64;
+    let (cx, cy) = (width as i64 - radius - 1, radius + 1);
+    for y in 0..height as i64 {
+        for x in 0..width as i64 {
+            if (x - cx).pow(2) + (y - cy).pow(2) <= radius.pow(2) {
+                let i = ((y * width as i64 + x) * 4) as usize;
+                rgba[i..i + 4].copy_from_slice(&[255, 140, 0, 255]);
+            }
+        }
+    }
+    Image::new(&rgba, width, height).to_owned()
+}
@@ -0,0 +1,125 @@
+// Removing Serpens from a Blender version altogether
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::addons::Addon;
+use crate::blender::blender_running;
+use crate::ops::{OperationState, Progress};
+use crate::updates::UpdateState;
+use crate::{
+    addons, addons_dir, backups, load_settings, lockers, notices, pycache, remove_dir_checked, tray, DeleteError,
+};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UninstallResult {
+    /// Every path that was deleted
+    pub removed: Vec<String>,
+    /// The snapshot taken first, when one was asked for
+    #[serde(rename = "backupPath")]
+    pub backup_path: Option<String>,
+}
+
+/// Sent whenever an install appears or disappears outside the usual switch flow
+#[derive(Debug, Serialize, Clone)]
+struct InstallationChanged {
+    #[serde(rename = "blenderVersion")]
+    blender_version: String,
+    installed: bool,
+}
+
+#[tauri::command]
+pub fn uninstall_addon(
+    blender_version: String,
+    keep_backup: bool,
+    clear_pycache: Option<bool>,
+    force: Option<bool>,
+    addon_id: Option<String>,
+    app: AppHandle,
+    ops: State<'_, OperationState>,
+) -> Result<UninstallResult, DeleteError> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    let guard = ops.begin("uninstall_addon", &blender_version)?;
+    let result = uninstall(
+        &addon,
+        &blender_version,
+        keep_backup,
+        clear_pycache.unwrap_or(false),
+        force.unwrap_or(false),
+        &guard.progress(),
+    );
+    guard.finish(&result.as_ref().map_err(|e| e.message.clone()));
+    drop(guard);
+
+    if result.is_ok() {
+        // Nothing is installed, so the last update check no longer describes anything
+        *app.state::<UpdateState>().0.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        tray::refresh(&app);
+        app.emit(
+            "serpens://installation-changed",
+            InstallationChanged { blender_version: blender_version.clone(), installed: false },
+        )
+        .ok();
+        notices::refresh(&addon, &app, &blender_version);
+    }
+    result
+}
+
+fn uninstall(
+    addon: &Addon,
+    blender_version: &str,
+    keep_backup: bool,
+    clear_pycache: bool,
+    force: bool,
+    progress: &Progress,
+) -> Result<UninstallResult, DeleteError> {
+    progress.phase("validating");
+    let addons_path = addons_dir(blender_version)?;
+    let addon_path = addons_path.join(&addon.folder_name);
+    if fs::symlink_metadata(&addon_path).is_err() {
+        return Err("Serpens isn't installed for this Blender version".to_string().into());
+    }
+    if !force {
+        // Which programs actually hold the files, where the system can say; otherwise
+        // any running Blender counts
+        match lockers::tree_holders(&addon_path) {
+            Some(holders) if !holders.is_empty() => {
+                return Err(format!(
+                    "{} {} the addon's files open - close {} first, or uninstall with force",
+                    lockers::describe(&holders),
+                    if holders.len() == 1 { "has" } else { "have" },
+                    if holders.len() == 1 { "it" } else { "them" }
+                )
+                .into());
+            }
+            Some(_) => {}
+            None if blender_running() => {
+                return Err("Blender is running - close it first, or uninstall with force".to_string().into());
+            }
+            None => {}
+        }
+    }
+
+    let backup_path = if keep_backup {
+        progress.phase("backing up");
+        let max = load_settings().map(|s| s.max_backups).unwrap_or(0);
+        Some(
+            backups::create_timestamped_backup(addon, blender_version, max)?
+                .to_string_lossy()
+                .to_string(),
+        )
+    } else {
+        None
+    };
+
+    progress.phase("removing");
+    remove_dir_checked(&addon_path)?;
+    let mut removed = vec![addon_path.to_string_lossy().to_string()];
+    if clear_pycache {
+        // The install itself is gone, so only the addons folder's own cache is left
+        let bytecode = pycache::scan(addon, &addons_path, true);
+        removed.extend(bytecode.files.iter().map(|f| addons_path.join(f).to_string_lossy().to_string()));
+    }
+    Ok(UninstallResult { removed, backup_path })
+}
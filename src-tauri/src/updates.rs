@@ -0,0 +1,278 @@
+// Checking the installed branch for new commits, on demand and in the background
+
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::{Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::addons::Addon;
+use crate::ops::OperationState;
+use crate::{addons, applog, branchwatch, git_addon_dir, git_output, gitbin, load_settings, notices, tray};
+
+/// The check at launch gives up after this, so a slow network can't leave a fetch
+/// running behind a freshly opened app
+const LAUNCH_CHECK_TIMEOUT: Duration = Duration::from_secs(20);
+/// Any other check gives up after this, so a stalled fetch can't hold the operation lock
+const CHECK_TIMEOUT: Duration = Duration::from_secs(60);
+/// Let the window come up before the first network round-trip
+const LAUNCH_CHECK_DELAY: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UpdateStatus {
+    pub branch: String,
+    #[serde(rename = "updateAvailable")]
+    pub update_available: bool,
+    /// Commits on the remote branch that aren't installed yet
+    pub pending: u32,
+    #[serde(rename = "localCommit")]
+    pub local_commit: String,
+    #[serde(rename = "remoteCommit")]
+    pub remote_commit: String,
+    #[serde(rename = "checkedAt")]
+    pub checked_at: String,
+}
+
+/// Result of the most recent check, shared with the tray
+#[derive(Default)]
+pub struct UpdateState(pub Mutex<Option<UpdateStatus>>);
+
+/// Wakes the background checker when settings are saved, so a new interval or a
+/// check turned off applies straight away instead of after the current wait
+#[derive(Default)]
+pub struct CheckerWake {
+    generation: Mutex<u64>,
+    changed: Condvar,
+}
+
+impl CheckerWake {
+    pub fn notify(&self) {
+        *self.generation.lock().unwrap_or_else(|e| e.into_inner()) += 1;
+        self.changed.notify_all();
+    }
+
+    /// Sleep for `timeout`, or until the next `notify`
+    fn wait(&self, timeout: Duration) {
+        let generation = self.generation.lock().unwrap_or_else(|e| e.into_inner());
+        let seen = *generation;
+        let _ = self
+            .changed
+            .wait_timeout_while(generation, timeout, |generation| *generation == seen)
+            .unwrap_or_else(|e| e.into_inner());
+    }
+}
+
+/// Closed until background git and network work may run. With `checkOnLaunch` on it
+/// opens at startup; with it off, at the first thing the user does.
+#[derive(Default)]
+pub struct BackgroundGate {
+    open: Mutex<bool>,
+    opened: Condvar,
+}
+
+impl BackgroundGate {
+    pub fn is_open(&self) -> bool {
+        *self.open.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// True the first time, when it was closed
+    fn open(&self) -> bool {
+        let mut open = self.open.lock().unwrap_or_else(|e| e.into_inner());
+        let was_closed = !*open;
+        *open = true;
+        self.opened.notify_all();
+        was_closed
+    }
+
+    /// Block until it opens
+    pub fn wait(&self) {
+        let open = self.open.lock().unwrap_or_else(|e| e.into_inner());
+        drop(self.opened.wait_while(open, |open| !*open).unwrap_or_else(|e| e.into_inner()));
+    }
+}
+
+/// Let background work start, catching up on what startup held back the first time
+pub fn allow_background(app: &AppHandle) {
+    if !app.state::<BackgroundGate>().open() {
+        return;
+    }
+    tray::refresh(app);
+    if let Ok(settings) = load_settings() {
+        notices::refresh(&Addon::builtin(), app, &settings.blender_version);
+    }
+}
+
+/// The frontend's first click or key press, when launch checks are off
+#[tauri::command]
+pub fn user_active(app: AppHandle) {
+    allow_background(&app);
+}
+
+/// The last check's result, for a window that opened after it was announced
+#[tauri::command]
+pub fn last_update_status(state: State<'_, UpdateState>) -> Option<UpdateStatus> {
+    state.0.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+#[tauri::command]
+pub async fn check_for_updates(
+    blender_version: String,
+    addon_id: Option<String>,
+    app: AppHandle,
+) -> Result<UpdateStatus, String> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    tokio::task::spawn_blocking(move || run_update_check(&addon, &app, &blender_version))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Fetch the installed branch and count what's new, then publish the result to the
+/// tray and the frontend. Holds the operation lock since fetching writes to .git, so the
+/// fetch gives up after `CHECK_TIMEOUT` rather than hold it indefinitely.
+pub fn run_update_check(addon: &Addon, app: &AppHandle, blender_version: &str) -> Result<UpdateStatus, String> {
+    check_and_publish(addon, app, blender_version, Some(CHECK_TIMEOUT))
+}
+
+/// `run_update_check`, giving up on the fetch after `timeout`
+fn check_and_publish(
+    addon: &Addon,
+    app: &AppHandle,
+    blender_version: &str,
+    timeout: Option<Duration>,
+) -> Result<UpdateStatus, String> {
+    let status = {
+        let guard = app.state::<OperationState>().begin("check_for_updates", blender_version)?;
+        let result =
+            git_addon_dir(addon, blender_version).and_then(|addon_path| fetch_update_status(&addon_path, timeout));
+        guard.finish(&result);
+        result?
+    };
+
+    // The tray follows Serpens; another addon's check only goes to the window
+    if addon.is_builtin() {
+        *app.state::<UpdateState>().0.lock().unwrap_or_else(|e| e.into_inner()) = Some(status.clone());
+        tray::refresh(app);
+    }
+    app.emit("serpens://update-status", &status).ok();
+    Ok(status)
+}
+
+pub fn fetch_update_status(addon_path: &Path, timeout: Option<Duration>) -> Result<UpdateStatus, String> {
+    let branch = git_output(addon_path, &["rev-parse", "--abbrev-ref", "HEAD"])?;
+    if branch == "HEAD" {
+        return Err("HEAD is detached - not on a branch".to_string());
+    }
+
+    fetch_branch(addon_path, &branch, timeout).map_err(|e| format!("Failed to fetch: {}", e))?;
+
+    let remote_ref = format!("origin/{}", branch);
+    let local_commit = git_output(addon_path, &["rev-parse", "HEAD"])?;
+    let remote_commit = git_output(addon_path, &["rev-parse", &remote_ref])?;
+    let pending = if local_commit == remote_commit {
+        0
+    } else {
+        git_output(addon_path, &["rev-list", "--count", &format!("HEAD..{}", remote_ref)])
+            .ok()
+            .and_then(|n| n.parse().ok())
+            // Shallow history can hide the count; we still know something changed
+            .unwrap_or(1)
+    };
+
+    Ok(UpdateStatus {
+        branch,
+        update_available: pending > 0,
+        pending,
+        local_commit,
+        remote_commit,
+        checked_at: chrono::Local::now().to_rfc3339(),
+    })
+}
+
+/// `git fetch origin <branch>`, killed if it runs past `timeout`. With a timeout nobody is
+/// there to answer a credential prompt, so git isn't allowed to ask.
+fn fetch_branch(addon_path: &Path, branch: &str, timeout: Option<Duration>) -> Result<(), String> {
+    let Some(timeout) = timeout else {
+        return git_output(addon_path, &["fetch", "--quiet", "--", "origin", branch]).map(|_| ());
+    };
+    let mut child = gitbin::git_command()
+        .args(["fetch", "--quiet", "--", "origin", branch])
+        .current_dir(addon_path)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| gitbin::run_error(&e))?;
+    let started = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if started.elapsed() > timeout => {
+                child.kill().ok();
+                child.wait().ok();
+                return Err(format!("gave up after {}s", timeout.as_secs()));
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(100)),
+            Err(e) => return Err(format!("Failed to wait for git: {}", e)),
+        }
+    };
+    if status.success() {
+        return Ok(());
+    }
+    let mut stderr = String::new();
+    if let Some(mut pipe) = child.stderr.take() {
+        pipe.read_to_string(&mut stderr).ok();
+    }
+    Err(stderr.trim().to_string())
+}
+
+/// Check the configured install once at launch when `checkOnLaunch` is on, then
+/// periodically along with the remote for new or deleted branches. With it off, nothing
+/// here runs until the user has done something and a full interval has passed since.
+/// Skips the install check quietly when another operation holds the lock or nothing is
+/// installed; the branch check runs regardless. Both are for Serpens, the addon the tray shows.
+pub fn spawn_update_checker(app: AppHandle) {
+    thread::spawn(move || {
+        let addon = &Addon::builtin();
+        let mut last_check = None;
+        if load_settings().map(|s| s.check_on_launch).unwrap_or(true) {
+            thread::sleep(LAUNCH_CHECK_DELAY);
+            launch_check(addon, &app);
+            last_check = Some(Instant::now());
+        } else {
+            app.state::<BackgroundGate>().wait();
+        }
+        let started = Instant::now();
+        loop {
+            let minutes = load_settings().map(|s| s.update_check_minutes).unwrap_or(0);
+            let interval = Duration::from_secs(u64::from(minutes) * 60);
+            let since = last_check.unwrap_or(started).elapsed();
+            if minutes > 0 && since >= interval {
+                if let Ok(settings) = load_settings() {
+                    branchwatch::check_new_branches(addon, &app).ok();
+                    run_update_check(addon, &app, &settings.blender_version).ok();
+                }
+                last_check = Some(Instant::now());
+                continue;
+            }
+            // Off: wait for settings to change. On: until due, or settings change.
+            let wait = if minutes == 0 { Duration::from_secs(60 * 60) } else { interval - since };
+            app.state::<CheckerWake>().wait(wait);
+        }
+    });
+}
+
+fn launch_check(addon: &Addon, app: &AppHandle) {
+    let Ok(settings) = load_settings() else { return };
+    branchwatch::check_new_branches(addon, app).ok();
+    // Nothing installed from git is nothing to check
+    if git_addon_dir(addon, &settings.blender_version).is_err() {
+        return;
+    }
+    if let Err(e) = check_and_publish(addon, app, &settings.blender_version, Some(LAUNCH_CHECK_TIMEOUT)) {
+        applog::warn(&format!("Update check at launch failed: {}", e));
+    }
+}
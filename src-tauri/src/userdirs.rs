@@ -0,0 +1,93 @@
+// Where Blender keeps a version's addons and extensions. Blender moves them when
+// BLENDER_USER_SCRIPTS, BLENDER_USER_EXTENSIONS or BLENDER_USER_RESOURCES is set, and the
+// customPath setting overrides the addons folder outright for setups the manager can't see.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::{blender_config_root, load_settings, validate};
+
+const USER_SCRIPTS: &str = "BLENDER_USER_SCRIPTS";
+const USER_EXTENSIONS: &str = "BLENDER_USER_EXTENSIONS";
+const USER_RESOURCES: &str = "BLENDER_USER_RESOURCES";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UserDirs {
+    #[serde(rename = "addonsDir")]
+    pub addons_dir: String,
+    /// "setting", the environment variable that decided, or "default"
+    #[serde(rename = "addonsSource")]
+    pub addons_source: String,
+    #[serde(rename = "extensionsDir")]
+    pub extensions_dir: String,
+    #[serde(rename = "extensionsSource")]
+    pub extensions_source: String,
+}
+
+/// An environment variable as Blender reads it - set to something, not just present
+fn env_dir(name: &str) -> Option<PathBuf> {
+    std::env::var_os(name).filter(|v| !v.is_empty()).map(PathBuf::from)
+}
+
+/// The addons folder for `blender_version` and what decided it
+fn resolve_addons(blender_version: &str) -> Result<(PathBuf, &'static str), String> {
+    let custom = load_settings().map(|s| s.custom_path).unwrap_or_default();
+    if !custom.trim().is_empty() {
+        return Ok((PathBuf::from(custom.trim()), "setting"));
+    }
+    if let Some(scripts) = env_dir(USER_SCRIPTS) {
+        return Ok((scripts.join("addons"), USER_SCRIPTS));
+    }
+    if let Some(resources) = env_dir(USER_RESOURCES) {
+        return Ok((resources.join("scripts").join("addons"), USER_RESOURCES));
+    }
+    Ok((blender_config_root()?.join(blender_version).join("scripts").join("addons"), "default"))
+}
+
+/// The extensions folder (one subfolder per repo) for `blender_version` and what decided it
+fn resolve_extensions(blender_version: &str) -> Result<(PathBuf, &'static str), String> {
+    if let Some(extensions) = env_dir(USER_EXTENSIONS) {
+        return Ok((extensions, USER_EXTENSIONS));
+    }
+    if let Some(resources) = env_dir(USER_RESOURCES) {
+        return Ok((resources.join("extensions"), USER_RESOURCES));
+    }
+    Ok((blender_config_root()?.join(blender_version).join("extensions"), "default"))
+}
+
+pub fn addons_dir(blender_version: &str) -> Result<PathBuf, String> {
+    resolve_addons(blender_version).map(|(path, _)| path)
+}
+
+pub fn extensions_dir(blender_version: &str) -> Result<PathBuf, String> {
+    resolve_extensions(blender_version).map(|(path, _)| path)
+}
+
+/// Every addons folder other than the per-version defaults, for sweeps that walk all of them
+pub fn overridden_addons_dirs() -> Vec<PathBuf> {
+    let custom = load_settings().map(|s| s.custom_path).unwrap_or_default();
+    let mut dirs: Vec<PathBuf> = [
+        (!custom.trim().is_empty()).then(|| PathBuf::from(custom.trim())),
+        env_dir(USER_SCRIPTS).map(|p| p.join("addons")),
+        env_dir(USER_RESOURCES).map(|p| p.join("scripts").join("addons")),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    dirs.dedup();
+    dirs
+}
+
+/// Where the manager looks for a version's addons and extensions, and why
+#[tauri::command]
+pub fn blender_user_dirs(blender_version: String) -> Result<UserDirs, String> {
+    validate::blender_version(&blender_version)?;
+    let (addons, addons_source) = resolve_addons(&blender_version)?;
+    let (extensions, extensions_source) = resolve_extensions(&blender_version)?;
+    Ok(UserDirs {
+        addons_dir: addons.to_string_lossy().to_string(),
+        addons_source: addons_source.to_string(),
+        extensions_dir: extensions.to_string_lossy().to_string(),
+        extensions_source: extensions_source.to_string(),
+    })
+}
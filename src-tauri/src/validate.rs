@@ -0,0 +1,59 @@
+// Checks for user-supplied names before they reach git or the filesystem
+
+use crate::blender::is_version_dir_name;
+use crate::gitbin;
+
+/// Let the UI check a free-text branch name before offering to switch to it
+#[tauri::command]
+pub fn validate_ref_name(name: String) -> Result<bool, String> {
+    branch_name(&name)?;
+    Ok(true)
+}
+
+/// A branch name git would accept and that can't be mistaken for a command-line option.
+/// Our own rules run first so obviously bad input never reaches a process; git then has
+/// the final say when it's available.
+pub fn branch_name(name: &str) -> Result<(), String> {
+    branch_name_rules(name)?;
+    let checked = gitbin::git_command().args(["check-ref-format", "--branch", name]).output();
+    match checked {
+        Ok(output) if !output.status.success() => Err(format!("Invalid branch name '{}': rejected by git", name)),
+        // Not having git here isn't this name's fault; the operation itself will say so
+        _ => Ok(()),
+    }
+}
+
+fn branch_name_rules(name: &str) -> Result<(), String> {
+    let invalid = |why: &str| Err(format!("Invalid branch name '{}': {}", name, why));
+    if name.is_empty() {
+        return invalid("empty");
+    }
+    if name.len() > 200 {
+        return invalid("too long");
+    }
+    if name.starts_with('-') {
+        return invalid("cannot start with '-'");
+    }
+    if let Some(c) = name.chars().find(|c| c.is_control() || c.is_whitespace() || "~^:?*[\\".contains(*c)) {
+        return invalid(&format!("contains '{}'", c.escape_default()));
+    }
+    if name.contains("..") || name.contains("//") || name.contains("@{") {
+        return invalid("contains '..', '//' or '@{'");
+    }
+    if name.starts_with('/') || name.ends_with('/') || name.ends_with('.') || name.ends_with(".lock") {
+        return invalid("bad start or end");
+    }
+    if name.split('/').any(|part| part.starts_with('.')) {
+        return invalid("path components cannot start with '.'");
+    }
+    Ok(())
+}
+
+/// A Blender config folder name like "4.2"
+pub fn blender_version(version: &str) -> Result<(), String> {
+    if is_version_dir_name(version) {
+        Ok(())
+    } else {
+        Err(format!("Invalid Blender version '{}': expected something like 4.2", version))
+    }
+}
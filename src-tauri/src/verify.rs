@@ -0,0 +1,284 @@
+// Checking that the installed files are all still there and unchanged - antivirus
+// quarantines and sync tools sometimes take files out from under the addon
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::Path;
+use tauri::State;
+
+use crate::addons::Addon;
+use crate::fingerprint::{self, is_manager_file};
+use crate::ops::{OperationState, Progress};
+use crate::repo::{self, StatusEntry};
+use crate::{addon_dir, addons, git_addon_dir, git_output, pre_switch_backup};
+
+/// Paths per `git checkout` call, well under Windows' command-line limit
+const CHECKOUT_CHUNK: usize = 100;
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct VerifyResult {
+    /// "git", "manifest", or None when there was nothing to check against
+    pub method: Option<String>,
+    /// "intact", "modified" (files changed but none gone), "damaged" (files missing)
+    /// or "unverifiable"
+    pub verdict: String,
+    pub missing: Vec<String>,
+    pub modified: Vec<String>,
+    /// Files on disk the install didn't come with
+    pub extra: Vec<String>,
+}
+
+#[tauri::command]
+pub async fn verify_installation(blender_version: String, addon_id: Option<String>) -> Result<VerifyResult, String> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    tokio::task::spawn_blocking(move || {
+        let addon_path = addon_dir(&addon, &blender_version)?;
+        if !addon_path.exists() {
+            return Err("No installation found".to_string());
+        }
+        verify(&addon_path)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+pub fn verify(addon_path: &Path) -> Result<VerifyResult, String> {
+    let mut result = if addon_path.join(".git").exists() {
+        verify_git(addon_path)?
+    } else if let Some(manifest) = fingerprint::read_manifest(addon_path) {
+        verify_manifest(addon_path, manifest)?
+    } else {
+        return Ok(VerifyResult { verdict: "unverifiable".to_string(), ..Default::default() });
+    };
+    result.verdict = if !result.missing.is_empty() {
+        "damaged"
+    } else if !result.modified.is_empty() {
+        "modified"
+    } else {
+        "intact"
+    }
+    .to_string();
+    Ok(result)
+}
+
+/// Set in .git while a merge, rebase, cherry-pick or revert is unfinished
+const IN_PROGRESS: &[&str] =
+    &["MERGE_HEAD", "REBASE_HEAD", "rebase-merge", "rebase-apply", "CHERRY_PICK_HEAD", "REVERT_HEAD"];
+
+/// Whether the install is exactly what was installed: for git, a clean status on a branch
+/// with nothing half-done; otherwise every file matching the install manifest. Errors
+/// when there's nothing to compare against, rather than guessing either way.
+#[tauri::command]
+pub async fn is_pristine(blender_version: String, addon_id: Option<String>) -> Result<bool, String> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    tokio::task::spawn_blocking(move || {
+        let addon_path = addon_dir(&addon, &blender_version)?;
+        if !addon_path.exists() {
+            return Err("No installation found".to_string());
+        }
+        if addon_path.join(".git").exists() {
+            git_pristine(&addon_path)
+        } else if let Some(manifest) = fingerprint::read_manifest(&addon_path) {
+            let result = verify_manifest(&addon_path, manifest)?;
+            Ok(result.missing.is_empty() && result.modified.is_empty() && result.extra.is_empty())
+        } else {
+            Err("The install has no git history or manifest to compare against".to_string())
+        }
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+fn git_pristine(addon_path: &Path) -> Result<bool, String> {
+    if git_output(addon_path, &["rev-parse", "--abbrev-ref", "HEAD"])? == "HEAD" {
+        return Ok(false);
+    }
+    let git_dir = addon_path.join(git_output(addon_path, &["rev-parse", "--git-dir"])?);
+    if IN_PROGRESS.iter().any(|name| git_dir.join(name).exists()) {
+        return Ok(false);
+    }
+    let status = repo::porcelain_status(addon_path, &["status", "--untracked-files=all"])?;
+    Ok(status.iter().all(|entry| entry.code == "??" && ignored_extra(&entry.path)))
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RepairResult {
+    /// Files put back from git
+    pub restored: Vec<String>,
+    /// Edited files under the user-data folders, left alone unless overwriting was confirmed
+    pub skipped: Vec<String>,
+    /// Still missing or modified after the repair
+    pub unfixed: Vec<String>,
+    /// Whether git's object store had to be fetched again
+    pub refetched: bool,
+    /// The verdict of the check run afterwards
+    pub verdict: String,
+    pub recommendation: Option<String>,
+}
+
+/// Put missing and modified tracked files back from git without re-cloning. Edits to the
+/// user's own folders (packages, snippets) are only overwritten with `overwrite_user_data`.
+#[tauri::command]
+pub async fn repair_installation(
+    blender_version: String,
+    overwrite_user_data: Option<bool>,
+    addon_id: Option<String>,
+    ops: State<'_, OperationState>,
+) -> Result<RepairResult, String> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    let guard = ops.begin("repair_installation", &blender_version)?;
+    tokio::task::spawn_blocking(move || {
+        let result = repair(&addon, &blender_version, overwrite_user_data.unwrap_or(false), &guard.progress());
+        guard.finish(&result);
+        result
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+fn repair(
+    addon: &Addon,
+    blender_version: &str,
+    overwrite_user_data: bool,
+    progress: &Progress,
+) -> Result<RepairResult, String> {
+    progress.phase("verifying");
+    let addon_path = git_addon_dir(addon, blender_version)
+        .map_err(|e| format!("{} - only git installs can be repaired; reinstall the branch instead", e))?;
+    let before = verify_git(&addon_path)?;
+
+    let is_user_data = |path: &str| path.split('/').next().is_some_and(|top| addon.preserved().contains(&top));
+    let (skipped, modified): (Vec<String>, Vec<String>) =
+        before.modified.into_iter().partition(|p| is_user_data(p) && !overwrite_user_data);
+    let targets: Vec<String> = before.missing.into_iter().chain(modified).collect();
+    if targets.is_empty() {
+        return Ok(RepairResult {
+            restored: Vec::new(),
+            skipped,
+            unfixed: Vec::new(),
+            refetched: false,
+            verdict: "intact".to_string(),
+            recommendation: None,
+        });
+    }
+
+    pre_switch_backup(addon, &addon_path, blender_version, progress)?;
+
+    progress.phase("restoring files");
+    let mut refetched = false;
+    if let Err(e) = checkout_paths(&addon_path, &targets, progress) {
+        if !looks_corrupt(&e) {
+            return Err(format!("Failed to restore files: {}", e));
+        }
+        progress.phase("refetching objects");
+        refetch(&addon_path)?;
+        refetched = true;
+        progress.phase("restoring files");
+        // Whatever still fails shows up as unfixed below
+        checkout_paths(&addon_path, &targets, progress).ok();
+    }
+
+    progress.phase("re-verifying");
+    let after = verify(&addon_path)?;
+    let still_bad: BTreeSet<&String> = after.missing.iter().chain(after.modified.iter()).collect();
+    let (unfixed, restored): (Vec<String>, Vec<String>) = targets.into_iter().partition(|p| still_bad.contains(p));
+    let recommendation = (!unfixed.is_empty())
+        .then(|| "Some files couldn't be restored - reinstall the branch to get a fresh clone".to_string());
+    Ok(RepairResult {
+        restored,
+        skipped,
+        unfixed,
+        refetched,
+        verdict: after.verdict,
+        recommendation,
+    })
+}
+
+fn checkout_paths(addon_path: &Path, paths: &[String], progress: &Progress) -> Result<(), String> {
+    let chunks: Vec<&[String]> = paths.chunks(CHECKOUT_CHUNK).collect();
+    for (i, chunk) in chunks.iter().enumerate() {
+        progress.percent(i as f32 * 100.0 / chunks.len() as f32);
+        let mut args = vec!["checkout", "HEAD", "--"];
+        args.extend(chunk.iter().map(String::as_str));
+        git_output(addon_path, &args)?;
+    }
+    Ok(())
+}
+
+/// git's wording when objects it needs are missing or damaged
+fn looks_corrupt(error: &str) -> bool {
+    let error = error.to_lowercase();
+    ["unable to read", "corrupt", "bad object", "loose object", "invalid object", "missing blob"]
+        .iter()
+        .any(|needle| error.contains(needle))
+}
+
+/// Download the branch's objects again. `--refetch` (git 2.36+) ignores what's already in
+/// the store; older gits get a plain fetch, which at least replaces missing objects.
+fn refetch(addon_path: &Path) -> Result<(), String> {
+    let branch = git_output(addon_path, &["rev-parse", "--abbrev-ref", "HEAD"])?;
+    git_output(addon_path, &["fetch", "--refetch", "--", "origin", &branch])
+        .or_else(|_| git_output(addon_path, &["fetch", "--", "origin", &branch]))
+        .map_err(|e| format!("Failed to fetch objects again: {}", e))?;
+    Ok(())
+}
+
+/// `git status` finds deletions and edits; the existence pass over `ls-files` also catches
+/// files hidden from status by skip-worktree or assume-unchanged
+pub fn verify_git(addon_path: &Path) -> Result<VerifyResult, String> {
+    let status = repo::porcelain_status(addon_path, &["status", "--untracked-files=all"])
+        .map_err(|e| format!("Failed to read git status: {}", e))?;
+    let mut missing = BTreeSet::new();
+    let mut modified = BTreeSet::new();
+    let mut extra = BTreeSet::new();
+    for StatusEntry { code, path, .. } in status {
+        if code == "??" {
+            if !ignored_extra(&path) {
+                extra.insert(path);
+            }
+        } else if code.contains('D') && !addon_path.join(&path).exists() {
+            missing.insert(path);
+        } else {
+            modified.insert(path);
+        }
+    }
+
+    let tracked =
+        git_output(addon_path, &["ls-files", "-z"]).map_err(|e| format!("Failed to list tracked files: {}", e))?;
+    for path in tracked.split('\0').filter(|p| !p.is_empty()) {
+        if std::fs::symlink_metadata(addon_path.join(path)).is_err() {
+            modified.remove(path);
+            missing.insert(path.to_string());
+        }
+    }
+
+    Ok(VerifyResult {
+        method: Some("git".to_string()),
+        verdict: String::new(),
+        missing: missing.into_iter().collect(),
+        modified: modified.into_iter().collect(),
+        extra: extra.into_iter().collect(),
+    })
+}
+
+fn verify_manifest(
+    addon_path: &Path,
+    manifest: std::collections::BTreeMap<String, String>,
+) -> Result<VerifyResult, String> {
+    let current = fingerprint::file_hashes(addon_path).map_err(|e| format!("Failed to hash installed files: {}", e))?;
+    let mut result = VerifyResult { method: Some("manifest".to_string()), ..Default::default() };
+    for (path, hash) in &manifest {
+        match current.get(path) {
+            None => result.missing.push(path.clone()),
+            Some(now) if now != hash => result.modified.push(path.clone()),
+            Some(_) => {}
+        }
+    }
+    result.extra = current.into_keys().filter(|p| !manifest.contains_key(p)).collect();
+    Ok(result)
+}
+
+/// Bytecode and our own bookkeeping turn up untracked in every install
+fn ignored_extra(path: &str) -> bool {
+    path.split('/').any(|part| part == "__pycache__") || is_manager_file(path)
+}
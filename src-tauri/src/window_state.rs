@@ -0,0 +1,150 @@
+// Remembering where the window was - size, position, maximized and tray mode
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, PhysicalPosition, PhysicalSize, WebviewWindow};
+
+use crate::data_dir;
+
+/// Wait for moves/resizes to settle before writing, so dragging doesn't hammer the disk
+const SAVE_DELAY: Duration = Duration::from_millis(500);
+
+/// How much of the window has to land on a monitor to count as reachable
+const MIN_VISIBLE: i32 = 100;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct WindowGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub maximized: bool,
+    /// Hidden in the tray rather than on screen
+    #[serde(rename = "inTray")]
+    pub in_tray: bool,
+}
+
+/// Wakes the background writer whenever the window moves or resizes
+pub struct WindowStateSaver(Mutex<Sender<()>>);
+
+fn state_path() -> Result<PathBuf, String> {
+    Ok(data_dir()?.join("window_state.json"))
+}
+
+fn load() -> Option<WindowGeometry> {
+    let content = fs::read_to_string(state_path().ok()?).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Apply the saved geometry to the (still hidden) window. Returns whether the window
+/// was last left in the tray.
+pub fn restore(window: &WebviewWindow) -> bool {
+    let Some(saved) = load() else { return false };
+    if saved.width == 0 || saved.height == 0 {
+        return false;
+    }
+
+    if let Some((position, size)) = fit_to_monitors(window, saved) {
+        window.set_size(size).ok();
+        window.set_position(position).ok();
+    }
+    if saved.maximized {
+        window.maximize().ok();
+    }
+    saved.in_tray
+}
+
+/// The saved rectangle if enough of it is on some connected monitor, otherwise the saved
+/// size shrunk to fit the primary monitor and centered there.
+fn fit_to_monitors(
+    window: &WebviewWindow,
+    saved: WindowGeometry,
+) -> Option<(PhysicalPosition<i32>, PhysicalSize<u32>)> {
+    let monitors = window.available_monitors().ok()?;
+    let (w, h) = (saved.width as i32, saved.height as i32);
+    let reachable = monitors.iter().any(|m| {
+        let (mp, ms) = (m.position(), m.size());
+        let overlap_x = (saved.x + w).min(mp.x + ms.width as i32) - saved.x.max(mp.x);
+        let overlap_y = (saved.y + h).min(mp.y + ms.height as i32) - saved.y.max(mp.y);
+        // The title bar has to be on screen or the window can't be dragged back
+        overlap_x >= MIN_VISIBLE && overlap_y >= MIN_VISIBLE.min(h) && saved.y >= mp.y
+    });
+    if reachable {
+        return Some((PhysicalPosition::new(saved.x, saved.y), PhysicalSize::new(saved.width, saved.height)));
+    }
+
+    let monitor = window.primary_monitor().ok().flatten().or_else(|| monitors.into_iter().next())?;
+    let (mp, ms) = (monitor.position(), monitor.size());
+    let size = PhysicalSize::new(saved.width.min(ms.width), saved.height.min(ms.height));
+    let position =
+        PhysicalPosition::new(mp.x + (ms.width - size.width) as i32 / 2, mp.y + (ms.height - size.height) as i32 / 2);
+    Some((position, size))
+}
+
+/// Start the debounced writer and hand back the handle window events use to poke it.
+pub fn spawn_saver(app: AppHandle) -> WindowStateSaver {
+    let (tx, rx) = mpsc::channel::<()>();
+    thread::spawn(move || {
+        while rx.recv().is_ok() {
+            // Keep absorbing events until things go quiet for a moment
+            loop {
+                match rx.recv_timeout(SAVE_DELAY) {
+                    Ok(()) => continue,
+                    Err(RecvTimeoutError::Timeout) => break,
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+            }
+            if let Some(window) = app.get_webview_window("main") {
+                save(&window);
+            }
+        }
+    });
+    WindowStateSaver(Mutex::new(tx))
+}
+
+/// Note that the window moved, resized or went to the tray.
+pub fn changed(app: &AppHandle) {
+    if let Some(saver) = app.try_state::<WindowStateSaver>() {
+        saver.0.lock().unwrap_or_else(|e| e.into_inner()).send(()).ok();
+    }
+}
+
+fn save(window: &WebviewWindow) {
+    let maximized = window.is_maximized().unwrap_or(false);
+    let minimized = window.is_minimized().unwrap_or(false);
+    let in_tray = !window.is_visible().unwrap_or(true);
+
+    // Maximized/minimized/hidden geometry isn't what the user wants back, so keep the last
+    // normal rectangle and only update the flags
+    let mut geometry = load();
+    if !(maximized || minimized || in_tray) {
+        if let (Ok(position), Ok(size)) = (window.outer_position(), window.inner_size()) {
+            geometry = Some(WindowGeometry {
+                x: position.x,
+                y: position.y,
+                width: size.width,
+                height: size.height,
+                maximized,
+                in_tray,
+            });
+        }
+    }
+    let Some(mut geometry) = geometry else { return };
+    if !minimized {
+        geometry.maximized = maximized;
+    }
+    geometry.in_tray = in_tray;
+
+    let Ok(path) = state_path() else { return };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).ok();
+    }
+    if let Ok(content) = serde_json::to_string_pretty(&geometry) {
+        fs::write(path, content).ok();
+    }
+}
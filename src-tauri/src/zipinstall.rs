@@ -0,0 +1,204 @@
+// Installing the addon from a zip - dropped on the window or picked in the UI
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, State};
+use zip::ZipArchive;
+
+use crate::addons::Addon;
+use crate::archive::bl_info_version;
+use crate::fingerprint;
+use crate::folderinstall;
+use crate::marker::{read_marker, write_marker, InstallMarker};
+use crate::ops::{OperationState, Progress};
+use crate::{
+    addon_dir, addons, addons_dir, git_output, pre_switch_backup, refuse_unpushed, swap_into_place, StagingDir,
+    SwitchError,
+};
+
+/// What a zip turned out to contain
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ZipAddon {
+    #[serde(rename = "zipPath")]
+    pub zip_path: String,
+    /// The folder the addon sits in inside the zip ("scripting_nodes-1.2.0"), if any
+    #[serde(rename = "topFolder")]
+    pub top_folder: Option<String>,
+    /// bl_info version, when it could be read
+    pub version: Option<String>,
+}
+
+/// Check that `zip_path` holds a Blender addon: an `__init__.py` with a `bl_info`, either at
+/// the root or inside a single top-level folder.
+pub fn inspect(zip_path: &Path) -> Result<ZipAddon, String> {
+    let file = File::open(zip_path).map_err(|e| format!("Failed to open zip: {}", e))?;
+    let mut archive = ZipArchive::new(file).map_err(|_| "This isn't a valid zip file".to_string())?;
+
+    let names: Vec<PathBuf> = (0..archive.len())
+        .filter_map(|i| archive.by_index(i).ok().and_then(|f| f.enclosed_name()))
+        .collect();
+    let top_folder = if names.iter().any(|n| n == Path::new("__init__.py")) {
+        None
+    } else {
+        // Zips made on macOS carry a __MACOSX folder of resource forks next to the real one
+        let mut tops: Vec<String> = names
+            .iter()
+            .filter(|n| n.components().count() > 1)
+            .filter_map(|n| n.components().next())
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .filter(|t| t != "__MACOSX")
+            .collect();
+        tops.sort();
+        tops.dedup();
+        match tops.as_slice() {
+            [top] if names.contains(&Path::new(top).join("__init__.py")) => Some(top.clone()),
+            _ => return Err("No __init__.py found - this doesn't look like a Blender addon".to_string()),
+        }
+    };
+
+    let init_name = match &top_folder {
+        Some(top) => format!("{}/__init__.py", top),
+        None => "__init__.py".to_string(),
+    };
+    let mut source = String::new();
+    archive
+        .by_name(&init_name)
+        .map_err(|e| format!("Failed to read __init__.py: {}", e))?
+        .read_to_string(&mut source)
+        .map_err(|e| format!("Failed to read __init__.py: {}", e))?;
+    if !source.contains("bl_info") {
+        return Err("__init__.py has no bl_info - this doesn't look like a Blender addon".to_string());
+    }
+
+    Ok(ZipAddon {
+        zip_path: zip_path.to_string_lossy().to_string(),
+        top_folder,
+        version: bl_info_version(&source),
+    })
+}
+
+/// Unpack the addon's files (without the top folder) into `dest`.
+fn extract(zip_path: &Path, top_folder: Option<&str>, dest: &Path, progress: &Progress) -> Result<(), String> {
+    let file = File::open(zip_path).map_err(|e| format!("Failed to open zip: {}", e))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("Failed to read zip: {}", e))?;
+    fs::create_dir_all(dest).map_err(|e| format!("Failed to create install folder: {}", e))?;
+
+    let total = archive.len();
+    for i in 0..total {
+        progress.percent(i as f32 * 100.0 / total as f32);
+        let mut entry = archive.by_index(i).map_err(|e| format!("Failed to read zip: {}", e))?;
+        // enclosed_name rejects absolute paths and ../ tricks
+        let Some(name) = entry.enclosed_name() else { continue };
+        let rel = match top_folder {
+            Some(top) => match name.strip_prefix(top) {
+                Ok(rel) => rel.to_path_buf(),
+                Err(_) => continue,
+            },
+            None => name,
+        };
+        if rel.as_os_str().is_empty() {
+            continue;
+        }
+
+        let out = dest.join(&rel);
+        if entry.is_dir() {
+            fs::create_dir_all(&out).map_err(|e| format!("Failed to create {}: {}", rel.display(), e))?;
+        } else {
+            if let Some(parent) = out.parent() {
+                fs::create_dir_all(parent).map_err(|e| format!("Failed to create folder: {}", e))?;
+            }
+            let mut target = File::create(&out).map_err(|e| format!("Failed to write {}: {}", rel.display(), e))?;
+            io::copy(&mut entry, &mut target).map_err(|e| format!("Failed to write {}: {}", rel.display(), e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Replace the install with the contents of an addon zip, under the usual folder name
+/// whatever the zip called it. Refused like a switch when that would lose unpushed commits.
+#[tauri::command]
+pub fn install_from_zip(
+    zip_path: String,
+    blender_version: String,
+    force: Option<bool>,
+    addon_id: Option<String>,
+    ops: State<'_, OperationState>,
+) -> Result<bool, SwitchError> {
+    let addon = addons::resolve(addon_id.as_deref())?;
+    let guard = ops.begin("install_from_zip", &blender_version)?;
+    let result = addon_dir(&addon, &blender_version)
+        .map_err(SwitchError::from)
+        .and_then(|addon_path| refuse_unpushed(&addon_path, force.unwrap_or(false)))
+        .and_then(|_| Ok(install_zip(&addon, &PathBuf::from(zip_path), &blender_version, &guard.progress())?));
+    guard.finish(&result.as_ref().map_err(|e| e.message.clone()));
+    result.map(|_| true)
+}
+
+fn install_zip(addon: &Addon, zip_path: &Path, blender_version: &str, progress: &Progress) -> Result<(), String> {
+    // Everything that can reject the zip happens before the current install is touched
+    progress.phase("checking zip");
+    let found = inspect(zip_path)?;
+    let sha256 = file_sha256(zip_path)?;
+
+    let addons_path = addons_dir(blender_version)?;
+    let addon_path = addons_path.join(&addon.folder_name);
+    fs::create_dir_all(&addons_path).map_err(|e| format!("Failed to create addons dir: {}", e))?;
+
+    let previous_branch = read_marker(&addon_path)
+        .and_then(|m| m.branch)
+        .or_else(|| git_output(&addon_path, &["rev-parse", "--abbrev-ref", "HEAD"]).ok());
+
+    pre_switch_backup(addon, &addon_path, blender_version, progress)?;
+
+    let staging = StagingDir::new(&addons_path, "zip");
+    progress.phase("extracting");
+    extract(zip_path, found.top_folder.as_deref(), staging.path(), progress)?;
+    write_marker(
+        addon,
+        staging.path(),
+        &InstallMarker {
+            installed_at: Some(chrono::Local::now().to_rfc3339()),
+            previous_branch,
+            source_zip: zip_path.file_name().map(|n| n.to_string_lossy().to_string()),
+            source_sha256: Some(sha256),
+            ..Default::default()
+        },
+    )?;
+    fingerprint::write_manifest(staging.path())?;
+    progress.phase("swapping");
+    swap_into_place(&staging, &addon_path)
+}
+
+fn file_sha256(path: &Path) -> Result<String, String> {
+    let mut file = File::open(path).map_err(|e| format!("Failed to open zip: {}", e))?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher).map_err(|e| format!("Failed to read zip: {}", e))?;
+    Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// A file was dropped on the window: ask the frontend to confirm a plausible addon zip
+/// or folder, and explain why anything else can't be installed.
+pub fn handle_drop(app: &AppHandle, paths: &[PathBuf]) {
+    if let [path] = paths {
+        if path.is_dir() {
+            match folderinstall::inspect(path) {
+                Ok(addon) => app.emit("serpens://folder-dropped", &addon).ok(),
+                Err(e) => app.emit("serpens://zip-drop-rejected", &e).ok(),
+            };
+            return;
+        }
+    }
+    let result = match paths {
+        [path] if path.extension().is_some_and(|e| e.eq_ignore_ascii_case("zip")) => inspect(path),
+        [_] => Err("Only .zip files and folders can be installed - drop an addon zip".to_string()),
+        [] => return,
+        _ => Err("Please drop a single zip at a time".to_string()),
+    };
+    match result {
+        Ok(addon) => app.emit("serpens://zip-dropped", &addon).ok(),
+        Err(e) => app.emit("serpens://zip-drop-rejected", &e).ok(),
+    };
+}